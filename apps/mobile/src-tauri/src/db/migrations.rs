@@ -0,0 +1,532 @@
+use crate::error::AppError;
+use rusqlite::{Connection, Transaction};
+
+/// A single, numbered schema change.
+/// Versions must be strictly increasing by one; the applied version is stored in `PRAGMA user_version`.
+pub struct Migration {
+    pub version: u32,
+    pub description: &'static str,
+    pub up: fn(&Transaction) -> rusqlite::Result<()>,
+}
+
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "initial schema (todos, work_logs, articles)",
+        up: initial_schema,
+    },
+    Migration {
+        version: 2,
+        description: "feed_state for conditional GET and fetch health",
+        up: feed_state,
+    },
+    Migration {
+        version: 3,
+        description: "user-managed feed subscriptions",
+        up: feeds,
+    },
+    Migration {
+        version: 4,
+        description: "full-text search over articles",
+        up: articles_fts,
+    },
+    Migration {
+        version: 5,
+        description: "article reading state (unread/read/saved/archived)",
+        up: article_state,
+    },
+    Migration {
+        version: 6,
+        description: "recommendation batch outcomes",
+        up: recommendation_batches,
+    },
+    Migration {
+        version: 7,
+        description: "learned keyword weights",
+        up: keyword_weights,
+    },
+    Migration {
+        version: 8,
+        description: "offline outbox for Brain Server operations",
+        up: sync_outbox,
+    },
+    Migration {
+        version: 9,
+        description: "server ratings and pull cursor for Brain Server sync",
+        up: server_pull,
+    },
+    Migration {
+        version: 10,
+        description: "todo due dates, priorities, categories and notes",
+        up: todo_details,
+    },
+    Migration {
+        version: 11,
+        description: "recurring todos",
+        up: todo_recurrence,
+    },
+    Migration {
+        version: 12,
+        description: "todo subtasks",
+        up: todo_subtasks,
+    },
+    Migration {
+        version: 13,
+        description: "gap-based todo positions",
+        up: todo_position_gaps,
+    },
+    Migration {
+        version: 14,
+        description: "todo due times",
+        up: todo_due_time,
+    },
+    Migration {
+        version: 15,
+        description: "work log timers",
+        up: work_timers,
+    },
+    Migration {
+        version: 16,
+        description: "work log descriptions",
+        up: work_log_description,
+    },
+    Migration {
+        version: 17,
+        description: "sync outbox payload revisions",
+        up: sync_outbox_revision,
+    },
+];
+
+/// Highest schema version this binary knows how to handle.
+pub fn latest_version() -> u32 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+}
+
+pub fn current_version(conn: &Connection) -> Result<u32, AppError> {
+    let version: u32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    Ok(version)
+}
+
+/// Applies every pending migration in order, each inside its own transaction.
+/// Refuses to touch a database written by a newer version of the app.
+pub fn run_migrations(conn: &mut Connection) -> Result<u32, AppError> {
+    let current = current_version(conn)?;
+    let latest = latest_version();
+
+    if current > latest {
+        return Err(AppError::Database(format!(
+            "Database schema version {} is newer than the supported version {}. Please update the app.",
+            current, latest
+        )));
+    }
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+        let tx = conn.transaction()?;
+        (migration.up)(&tx).map_err(|e| {
+            AppError::Database(format!(
+                "Migration {} ({}) failed: {}",
+                migration.version, migration.description, e
+            ))
+        })?;
+        tx.pragma_update(None, "user_version", migration.version)?;
+        tx.commit()?;
+        println!(
+            "Applied migration {}: {}",
+            migration.version, migration.description
+        );
+    }
+
+    Ok(latest)
+}
+
+// Version 1 mirrors the schema that used to be created ad hoc in `init_db`.
+// `IF NOT EXISTS` and the column checks keep it safe for databases created before migrations existed.
+fn initial_schema(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS todos (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            text TEXT NOT NULL,
+            completed BOOLEAN DEFAULT 0,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE TABLE IF NOT EXISTS work_logs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project TEXT NOT NULL,
+            hours REAL NOT NULL,
+            date TEXT NOT NULL,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE TABLE IF NOT EXISTS articles (
+            id TEXT PRIMARY KEY,
+            title TEXT,
+            summary TEXT,
+            url TEXT UNIQUE,
+            tags TEXT,
+            published_at TEXT,
+            image_url TEXT NULL,
+            author TEXT NULL,
+            feedback_helpful BOOLEAN NULL,
+            feedback_reason TEXT NULL,
+            feedback_at TEXT NULL,
+            server_article_id TEXT NULL,
+            synced_at TEXT NULL
+        );",
+    )?;
+
+    // Columns added after the first release
+    add_column_if_not_exists(tx, "articles", "server_article_id", "TEXT NULL")?;
+    add_column_if_not_exists(tx, "articles", "synced_at", "TEXT NULL")?;
+
+    // Optimization: Partial index to speed up fetching candidate articles (unread)
+    // Most reads filter for `feedback_helpful IS NULL`.
+    tx.execute(
+        "CREATE INDEX IF NOT EXISTS idx_articles_feedback ON articles(feedback_helpful) WHERE feedback_helpful IS NULL",
+        [],
+    )?;
+
+    Ok(())
+}
+
+fn feed_state(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE feed_state (
+            url TEXT PRIMARY KEY,
+            etag TEXT NULL,
+            last_modified TEXT NULL,
+            last_status INTEGER NULL,
+            last_error TEXT NULL,
+            last_fetched_at TEXT NULL,
+            last_success_at TEXT NULL,
+            consecutive_failures INTEGER NOT NULL DEFAULT 0
+        );",
+    )
+}
+
+/// Default subscriptions as of this migration, as (url, category) pairs. Frozen here so that
+/// every install starts from the same list, whatever the app ships as defaults later.
+pub const SEED_FEEDS: &[(&str, &str)] = &[
+    // Rust
+    ("https://blog.rust-lang.org/feed.xml", "Rust"),
+    ("https://this-week-in-rust.org/rss.xml", "Rust"),
+    // Android / Kotlin
+    ("https://feeds.feedburner.com/blogspot/hsDu", "Android"),
+    ("https://androidweekly.net/rss", "Android"),
+    // Tauri
+    ("https://tauri.app/blog/rss.xml", "Tauri"),
+    // Web / TypeScript
+    (
+        "https://devblogs.microsoft.com/typescript/feed/",
+        "TypeScript",
+    ),
+    ("https://css-tricks.com/feed/", "Web"),
+    ("https://www.smashingmagazine.com/feed/", "Web"),
+    ("https://web.dev/feed.xml", "Web"),
+    ("https://fettblog.eu/feed.xml", "TypeScript"),
+    ("https://levelup.gitconnected.com/feed", "Web"),
+    ("https://2ality.com/feeds/posts.xml", "TypeScript"),
+    // React
+    ("https://react.dev/feed.xml", "React"),
+    ("https://overreacted.io/rss.xml", "React"),
+    ("https://tkdodo.eu/blog/rss.xml", "React"),
+    ("https://kentcdodds.com/blog/rss.xml", "React"),
+    ("https://www.joshwcomeau.com/rss.xml", "React"),
+    ("https://robinwieruch.de/index.xml", "React"),
+    ("https://ui.dev/blog/rss", "React"),
+    ("https://www.developerway.com/rss.xml", "React"),
+    // AI
+    ("https://openai.com/blog/rss.xml", "AI"),
+    ("https://blogs.microsoft.com/ai/feed/", "AI"),
+    // General / Tech
+    ("https://news.ycombinator.com/rss", "General"),
+    ("https://dev.to/feed", "General"),
+];
+
+// Subscriptions used to be a compile-time constant; the table replaces it.
+fn feeds(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE feeds (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            url TEXT NOT NULL UNIQUE,
+            title TEXT NULL,
+            category TEXT NOT NULL,
+            enabled BOOLEAN NOT NULL DEFAULT 1,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );",
+    )?;
+
+    let mut stmt = tx.prepare("INSERT OR IGNORE INTO feeds (url, category) VALUES (?1, ?2)")?;
+    for (url, category) in SEED_FEEDS {
+        stmt.execute(rusqlite::params![url, category])?;
+    }
+
+    Ok(())
+}
+
+// External-content FTS5 index over `articles`, kept in sync by triggers so every writer is covered.
+fn articles_fts(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "CREATE VIRTUAL TABLE articles_fts USING fts5(
+            title,
+            summary,
+            author,
+            content = 'articles',
+            content_rowid = 'rowid',
+            tokenize = 'unicode61 remove_diacritics 2'
+        );
+
+        CREATE TRIGGER articles_fts_insert AFTER INSERT ON articles BEGIN
+            INSERT INTO articles_fts(rowid, title, summary, author)
+                VALUES (new.rowid, new.title, new.summary, new.author);
+        END;
+
+        CREATE TRIGGER articles_fts_delete AFTER DELETE ON articles BEGIN
+            INSERT INTO articles_fts(articles_fts, rowid, title, summary, author)
+                VALUES ('delete', old.rowid, old.title, old.summary, old.author);
+        END;
+
+        CREATE TRIGGER articles_fts_update AFTER UPDATE OF title, summary, author ON articles BEGIN
+            INSERT INTO articles_fts(articles_fts, rowid, title, summary, author)
+                VALUES ('delete', old.rowid, old.title, old.summary, old.author);
+            INSERT INTO articles_fts(rowid, title, summary, author)
+                VALUES (new.rowid, new.title, new.summary, new.author);
+        END;
+
+        -- Index articles stored before this migration
+        INSERT INTO articles_fts(articles_fts) VALUES ('rebuild');",
+    )
+}
+
+fn article_state(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "ALTER TABLE articles ADD COLUMN state TEXT NOT NULL DEFAULT 'unread';
+        ALTER TABLE articles ADD COLUMN state_changed_at TEXT NULL;
+
+        -- Rated articles have been read
+        UPDATE articles SET state = 'read', state_changed_at = feedback_at
+            WHERE feedback_helpful IS NOT NULL;
+
+        CREATE INDEX idx_articles_state ON articles(state, state_changed_at);",
+    )
+}
+
+fn recommendation_batches(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE recommendation_batches (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            created_at TEXT NOT NULL,
+            outcome TEXT NOT NULL,
+            provider TEXT NULL,
+            model TEXT NULL,
+            attempts INTEGER NOT NULL DEFAULT 0,
+            article_ids TEXT NOT NULL,
+            error TEXT NULL
+        );
+
+        CREATE INDEX idx_recommendation_batches_created ON recommendation_batches(created_at);",
+    )
+}
+
+/// Keyword weights the scoring started from, frozen like `SEED_FEEDS`
+pub const SEED_KEYWORD_WEIGHTS: &[(&str, f64)] = &[
+    ("rust", 10.0),
+    ("tauri", 10.0),
+    ("react", 10.0),
+    ("typescript", 10.0),
+    ("javascript", 10.0),
+    ("android", 10.0),
+    ("kotlin", 10.0),
+    ("webassembly", 10.0),
+    ("wasm", 10.0),
+    ("docker", 10.0),
+    ("kubernetes", 10.0),
+    ("llvm", 10.0),
+    ("compiler", 10.0),
+    ("code", 3.0),
+    ("programming", 3.0),
+    ("developer", 3.0),
+    ("api", 3.0),
+    ("frontend", 3.0),
+    ("backend", 3.0),
+    ("database", 3.0),
+    ("algorithm", 3.0),
+    ("git", 3.0),
+    ("linux", 3.0),
+    ("windows", 3.0),
+    ("macos", 3.0),
+    ("design pattern", 3.0),
+    ("refactoring", 3.0),
+    ("stock", -20.0),
+    ("market", -20.0),
+    ("buffett", -20.0),
+    ("berkshire", -20.0),
+    ("invest", -20.0),
+    ("politics", -20.0),
+    ("crime", -20.0),
+    ("murder", -20.0),
+    ("sport", -20.0),
+    ("celebrity", -20.0),
+    ("gossip", -20.0),
+    ("bitcoin", -20.0),
+    ("crypto", -20.0),
+    ("blockchain", -20.0),
+];
+
+// Seeded from the keyword lists of the time; feedback only ever changes `adjustment`.
+fn keyword_weights(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE keyword_weights (
+            term TEXT PRIMARY KEY,
+            base_weight REAL NOT NULL DEFAULT 0,
+            adjustment REAL NOT NULL DEFAULT 0,
+            feedback_count INTEGER NOT NULL DEFAULT 0,
+            seeded BOOLEAN NOT NULL DEFAULT 0,
+            updated_at TEXT NULL
+        );",
+    )?;
+
+    let mut stmt = tx.prepare(
+        "INSERT OR IGNORE INTO keyword_weights (term, base_weight, seeded) VALUES (?1, ?2, 1)",
+    )?;
+    for (term, weight) in SEED_KEYWORD_WEIGHTS {
+        stmt.execute(rusqlite::params![term, weight])?;
+    }
+
+    Ok(())
+}
+
+fn sync_outbox(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE sync_outbox (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            op_type TEXT NOT NULL,
+            dedupe_key TEXT NOT NULL UNIQUE,
+            payload TEXT NOT NULL,
+            attempts INTEGER NOT NULL DEFAULT 0,
+            next_attempt_at TEXT NULL,
+            last_error TEXT NULL,
+            created_at TEXT NOT NULL
+        );
+
+        CREATE INDEX idx_sync_outbox_due ON sync_outbox(next_attempt_at);",
+    )
+}
+
+fn server_pull(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "ALTER TABLE articles ADD COLUMN server_rating REAL NULL;
+        ALTER TABLE articles ADD COLUMN server_positive_count INTEGER NULL;
+        ALTER TABLE articles ADD COLUMN server_negative_count INTEGER NULL;
+        ALTER TABLE articles ADD COLUMN server_updated_at TEXT NULL;
+
+        CREATE INDEX idx_articles_server_article_id ON articles(server_article_id);
+
+        CREATE TABLE sync_meta (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        );",
+    )
+}
+
+fn todo_details(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "ALTER TABLE todos ADD COLUMN due_date TEXT NULL;
+        ALTER TABLE todos ADD COLUMN priority TEXT NOT NULL DEFAULT 'medium';
+        ALTER TABLE todos ADD COLUMN category TEXT NULL;
+        ALTER TABLE todos ADD COLUMN tags TEXT NOT NULL DEFAULT '[]';
+        ALTER TABLE todos ADD COLUMN notes TEXT NULL;
+        ALTER TABLE todos ADD COLUMN completed_at TEXT NULL;
+        ALTER TABLE todos ADD COLUMN updated_at TEXT NULL;
+
+        UPDATE todos SET updated_at = created_at;
+
+        CREATE INDEX idx_todos_due_date ON todos(due_date);",
+    )
+}
+
+fn todo_recurrence(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "ALTER TABLE todos ADD COLUMN recurrence TEXT NULL;
+        ALTER TABLE todos ADD COLUMN occurrence INTEGER NOT NULL DEFAULT 1;",
+    )
+}
+
+fn todo_subtasks(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "ALTER TABLE todos ADD COLUMN parent_id INTEGER NULL REFERENCES todos(id) ON DELETE CASCADE;
+        ALTER TABLE todos ADD COLUMN position INTEGER NOT NULL DEFAULT 0;
+
+        UPDATE todos SET position = id;
+
+        CREATE INDEX idx_todos_parent_id ON todos(parent_id, position);",
+    )
+}
+
+/// Spreads existing positions out to the gap used by `TodoRepository::move_todo`
+fn todo_position_gaps(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch("UPDATE todos SET position = position * 1024;")
+}
+
+fn todo_due_time(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch("ALTER TABLE todos ADD COLUMN due_time TEXT NULL;")
+}
+
+fn work_timers(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "ALTER TABLE work_logs ADD COLUMN started_at TEXT NULL;
+        ALTER TABLE work_logs ADD COLUMN ended_at TEXT NULL;
+        ALTER TABLE work_logs ADD COLUMN duration_seconds INTEGER NULL;
+
+        -- A timer that hasn't been stopped yet. Time from finished (paused) segments is in
+        -- elapsed_seconds; resumed_at is when the current segment began, NULL while paused.
+        CREATE TABLE work_timers (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project TEXT NOT NULL,
+            started_at TEXT NOT NULL,
+            resumed_at TEXT NULL,
+            elapsed_seconds INTEGER NOT NULL DEFAULT 0
+        );",
+    )
+}
+
+fn work_log_description(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "ALTER TABLE work_logs ADD COLUMN description TEXT NULL;
+
+        CREATE INDEX idx_work_logs_date ON work_logs(date);",
+    )
+}
+
+/// Bumped whenever an entry's payload is replaced, so a new payload gets a new idempotency key
+fn sync_outbox_revision(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch("ALTER TABLE sync_outbox ADD COLUMN revision INTEGER NOT NULL DEFAULT 0;")
+}
+
+fn add_column_if_not_exists(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    column_type: &str,
+) -> rusqlite::Result<()> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let mut rows = stmt.query([])?;
+    let mut exists = false;
+    while let Some(row) = rows.next()? {
+        let name: String = row.get(1)?;
+        if name == column {
+            exists = true;
+            break;
+        }
+    }
+    if !exists {
+        conn.execute(
+            &format!(
+                "ALTER TABLE {} ADD COLUMN {} {}",
+                table, column, column_type
+            ),
+            [],
+        )?;
+    }
+    Ok(())
+}
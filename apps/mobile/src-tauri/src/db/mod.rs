@@ -0,0 +1,55 @@
+pub mod migrations;
+
+use crate::error::AppError;
+use r2d2::Pool;
+use r2d2_sqlite::SqliteConnectionManager;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+pub type DbPool = Pool<SqliteConnectionManager>;
+
+pub fn init_db(app_handle: &AppHandle) -> Result<DbPool, String> {
+    let app_dir = app_handle
+        .path()
+        .app_data_dir()
+        .unwrap_or(PathBuf::from("."));
+
+    if !app_dir.exists() {
+        fs::create_dir_all(&app_dir).map_err(|e| e.to_string())?;
+    }
+
+    let db_path = app_dir.join("app.db");
+    println!("Database path: {:?}", db_path);
+
+    let manager = SqliteConnectionManager::file(db_path);
+    create_pool(manager).map_err(|e| e.to_string())
+}
+
+/// Builds a connection pool and brings the schema up to date.
+/// Production and tests both go through here so the schema cannot drift between them.
+/// Foreign keys are enforced on every connection (SQLite leaves them off by default).
+pub fn create_pool(manager: SqliteConnectionManager) -> Result<DbPool, AppError> {
+    let manager = manager.with_init(|conn| conn.pragma_update(None, "foreign_keys", true));
+    let pool = Pool::new(manager)?;
+
+    let mut conn = pool.get()?;
+    migrations::run_migrations(&mut conn)?;
+
+    Ok(pool)
+}
+
+/// Fresh, fully migrated in-memory database.
+/// Each call gets its own named database so tests running in parallel don't share rows.
+#[cfg(test)]
+pub fn create_test_pool() -> DbPool {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    // Use shared cache to ensure all connections in the pool see the same in-memory DB
+    let name = format!(
+        "file:test_db_{}?mode=memory&cache=shared",
+        COUNTER.fetch_add(1, Ordering::SeqCst)
+    );
+    create_pool(SqliteConnectionManager::file(name)).unwrap()
+}
@@ -1,108 +1,858 @@
 #[cfg(test)]
 mod tests {
-    use crate::features::recommendation::repository::SqliteRecommendationRepository;
+    use crate::db::create_test_pool;
+    use crate::db::migrations::SEED_FEEDS;
+    use crate::db::migrations::{current_version, latest_version, run_migrations, MIGRATIONS};
+    use crate::error::AppError;
+    use crate::features::recommendation::model::{
+        Article, ArticleCategory, ArticleState, FeedState, RecommendationBatch,
+        RecommendationOutcome, RecommendationStats, SearchFilters,
+    };
+    use crate::features::recommendation::repository::{
+        RecommendationRepository, SqliteRecommendationRepository,
+    };
     use crate::features::recommendation::system::RecommendationState;
-    use crate::features::todo::repository::SqliteTodoRepository;
+    use crate::features::todo::model::{NewTodo, Priority, Todo, TodoPatch, TodoSort};
+    use crate::features::todo::recurrence::Recurrence;
+    use crate::features::todo::repository::{SqliteTodoRepository, TodoRepository};
     use crate::features::todo::service::TodoState;
-    use crate::features::worklog::repository::SqliteWorkLogRepository;
+    use crate::features::worklog::model::{ExportFormat, NewWorkLog, WorkLogImport, WorkLogPatch};
+    use crate::features::worklog::repository::{SqliteWorkLogRepository, WorkLogRepository};
     use crate::features::worklog::service::WorkLogState;
-    use r2d2::Pool;
-    use r2d2_sqlite::SqliteConnectionManager;
+    use rusqlite::Connection;
     use std::sync::Arc;
 
-    fn setup_memory_db() -> Pool<SqliteConnectionManager> {
-        // Use shared cache to ensure all connections in the pool see the same in-memory DB
-        let manager = SqliteConnectionManager::file("file::memory:?cache=shared");
-        let pool = Pool::new(manager).unwrap();
-        let conn = pool.get().unwrap();
-
-        // Init tables (copied from db.rs for testing)
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS todos (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                text TEXT NOT NULL,
-                completed BOOLEAN DEFAULT 0,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
-            )",
-            [],
-        )
-        .unwrap();
-
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS work_logs (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                project TEXT NOT NULL,
-                hours REAL NOT NULL,
-                date TEXT NOT NULL,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
-            )",
-            [],
-        )
-        .unwrap();
-
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS articles (
-                id TEXT PRIMARY KEY,
-                title TEXT,
-                summary TEXT,
-                url TEXT UNIQUE,
-                tags TEXT,
-                published_at TEXT,
-                image_url TEXT NULL,
-                author TEXT NULL,
-                feedback_helpful BOOLEAN NULL,
-                feedback_reason TEXT NULL,
-                feedback_at TEXT NULL
-            )",
-            [],
-        )
-        .unwrap();
-
-        pool
-    }
-
     #[test]
     fn test_todo_crud() {
-        let pool = setup_memory_db();
+        let pool = create_test_pool();
         let repo = Arc::new(SqliteTodoRepository::new(pool.clone()));
         let state = TodoState::new(repo);
 
         // Add
-        let todos = state.add("Test Todo".to_string()).unwrap();
+        let todos = state.add(new_todo("Test Todo")).unwrap();
         assert_eq!(todos.len(), 1);
         assert_eq!(todos[0].text, "Test Todo");
-        assert_eq!(todos[0].completed, false);
+        assert!(!todos[0].completed);
 
         let id = todos[0].id;
 
         // Toggle
         let todos = state.toggle(id).unwrap();
-        assert_eq!(todos[0].completed, true);
+        assert!(todos[0].completed);
 
         // Delete
         let todos = state.delete(id).unwrap();
         assert_eq!(todos.len(), 0);
+
+        // Missing ids are reported as such, not as database failures
+        assert!(matches!(state.toggle(id), Err(AppError::NotFound(_))));
+        assert!(matches!(state.delete(id), Err(AppError::NotFound(_))));
+    }
+
+    fn new_todo(text: &str) -> NewTodo {
+        NewTodo {
+            text: text.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_todo_details_and_queries() {
+        let pool = create_test_pool();
+        let state = TodoState::new(Arc::new(SqliteTodoRepository::new(pool)));
+        let today = chrono::NaiveDate::from_ymd_opt(2025, 3, 10).unwrap();
+
+        state
+            .add(NewTodo {
+                text: "  Write report ".into(),
+                due_date: Some("2025-03-07".into()),
+                due_time: None,
+                priority: Priority::High,
+                category: Some("Work".into()),
+                tags: vec!["q1".into(), " Q1 ".into(), "".into(), "finance".into()],
+                notes: Some("   ".into()),
+                recurrence: None,
+            })
+            .unwrap();
+        state
+            .add(NewTodo {
+                text: "Call plumber".into(),
+                due_date: Some("2025-03-10".into()),
+                category: Some("Personal".into()),
+                ..Default::default()
+            })
+            .unwrap();
+        let todos = state.add(new_todo("Read a book")).unwrap();
+
+        let report = &todos[0];
+        assert_eq!(report.text, "Write report");
+        assert_eq!(report.tags, vec!["q1", "finance"]);
+        assert_eq!(report.notes, None);
+        assert_eq!(report.priority, Priority::High);
+        assert!(!report.created_at.is_empty());
+        assert_eq!(report.created_at, report.updated_at);
+        assert_eq!(todos[2].priority, Priority::Medium);
+
+        let texts =
+            |todos: Vec<Todo>| -> Vec<String> { todos.into_iter().map(|t| t.text).collect() };
+        assert_eq!(texts(state.overdue(today).unwrap()), vec!["Write report"]);
+        assert_eq!(texts(state.due_today(today).unwrap()), vec!["Call plumber"]);
+        assert_eq!(
+            texts(state.by_category("work").unwrap()),
+            vec!["Write report"]
+        );
+        assert_eq!(
+            texts(state.by_priority(Priority::Medium).unwrap()),
+            vec!["Call plumber", "Read a book"]
+        );
+
+        // Patch: omitted fields stay, null clears, completing stamps completed_at
+        let patch: TodoPatch = serde_json::from_value(serde_json::json!({
+            "due_date": null,
+            "notes": "Use the new template",
+            "completed": true
+        }))
+        .unwrap();
+        let todos = state.update(report.id, patch).unwrap();
+        let report = &todos[0];
+        assert_eq!(report.due_date, None);
+        assert_eq!(report.category.as_deref(), Some("Work"));
+        assert_eq!(report.notes.as_deref(), Some("Use the new template"));
+        assert!(report.completed && report.completed_at.is_some());
+        assert!(state.overdue(today).unwrap().is_empty());
+
+        let todos = state.toggle(report.id).unwrap();
+        assert!(!todos[0].completed && todos[0].completed_at.is_none());
+
+        // Invalid input is rejected before it reaches the database
+        let bad_date = NewTodo {
+            due_date: Some("10/03/2025".into()),
+            ..new_todo("Dentist")
+        };
+        assert!(matches!(state.add(bad_date), Err(AppError::Validation(_))));
+        assert!(matches!(
+            state.add(new_todo("   ")),
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            state.update(999, TodoPatch::default()),
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_completing_recurring_todo_schedules_next() {
+        let pool = create_test_pool();
+        let state = TodoState::new(Arc::new(SqliteTodoRepository::new(pool)));
+
+        let recurrence: Recurrence = serde_json::from_value(serde_json::json!({
+            "type": "monthly",
+            "day": 31,
+            "count": 2
+        }))
+        .unwrap();
+        let todos = state
+            .add(NewTodo {
+                due_date: Some("2099-01-31".into()),
+                tags: vec!["bills".into()],
+                recurrence: Some(recurrence.clone()),
+                ..new_todo("Pay rent")
+            })
+            .unwrap();
+        let first = todos[0].id;
+        assert_eq!(todos[0].occurrence, 1);
+
+        let todos = state.toggle(first).unwrap();
+        assert_eq!(todos.len(), 2);
+        assert!(todos[0].completed && todos[0].recurrence.is_none());
+        let next = &todos[1];
+        assert_eq!(next.text, "Pay rent");
+        assert_eq!(next.tags, vec!["bills"]);
+        assert_eq!(next.due_date.as_deref(), Some("2099-02-28"));
+        assert_eq!(next.occurrence, 2);
+        assert_eq!(next.recurrence.as_ref(), Some(&recurrence));
+        assert!(!next.completed);
+
+        // Reopening and completing again doesn't spawn a duplicate
+        state.toggle(first).unwrap();
+        assert_eq!(state.toggle(first).unwrap().len(), 2);
+
+        // The count is reached: completing the last occurrence ends the series
+        let complete = TodoPatch {
+            completed: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(state.update(next.id, complete).unwrap().len(), 2);
+
+        let invalid: Recurrence = serde_json::from_value(serde_json::json!({
+            "type": "every_n_days",
+            "days": 0
+        }))
+        .unwrap();
+        let bad = NewTodo {
+            recurrence: Some(invalid),
+            ..new_todo("Water plants")
+        };
+        assert!(matches!(state.add(bad), Err(AppError::Validation(_))));
+    }
+
+    #[test]
+    fn test_next_occurrence_copies_nested_subtasks() {
+        let pool = create_test_pool();
+        let state = TodoState::new(Arc::new(SqliteTodoRepository::new(pool)));
+
+        let recurrence: Recurrence =
+            serde_json::from_value(serde_json::json!({"type": "daily"})).unwrap();
+        let todos = state
+            .add(NewTodo {
+                due_date: Some("2099-01-01".into()),
+                recurrence: Some(recurrence),
+                ..new_todo("Weekly review")
+            })
+            .unwrap();
+        let review = todos[0].id;
+        state.add_subtask(review, new_todo("Inbox")).unwrap();
+        let todos = state.add_subtask(review, new_todo("Projects")).unwrap();
+        let projects = todos[0].subtasks[1].id;
+        state.add_subtask(projects, new_todo("Brain")).unwrap();
+        let todos = state.toggle(projects).unwrap();
+        let brain = todos[0].subtasks[1].subtasks[0].id;
+        state.add_subtask(brain, new_todo("Sync")).unwrap();
+
+        let todos = state.toggle(review).unwrap();
+        let next = &todos[1];
+        assert_eq!(next.due_date.as_deref(), Some("2099-01-02"));
+        let texts = |todos: &[Todo]| todos.iter().map(|t| t.text.clone()).collect::<Vec<_>>();
+        assert_eq!(texts(&next.subtasks), vec!["Inbox", "Projects"]);
+        assert_eq!(texts(&next.subtasks[1].subtasks), vec!["Brain"]);
+        assert_eq!(texts(&next.subtasks[1].subtasks[0].subtasks), vec!["Sync"]);
+        // Copies start unchecked
+        assert!(!next.subtasks[1].completed);
+        // The completed occurrence keeps its own tree
+        assert_eq!(
+            texts(&todos[0].subtasks[1].subtasks[0].subtasks),
+            vec!["Sync"]
+        );
+    }
+
+    #[test]
+    fn test_subtasks_tree_reorder_and_cascade() {
+        let pool = create_test_pool();
+        let repo = Arc::new(SqliteTodoRepository::new(pool));
+        let state = TodoState::new(repo.clone());
+
+        let todos = state.add(new_todo("Move house")).unwrap();
+        let parent = todos[0].id;
+        state.add(new_todo("Unrelated")).unwrap();
+        for text in ["Book van", "Pack kitchen", "Forward mail"] {
+            state.add_subtask(parent, new_todo(text)).unwrap();
+        }
+        let todos = state.get_all().unwrap();
+        assert_eq!(todos.len(), 2);
+        let ids: Vec<u32> = todos[0].subtasks.iter().map(|t| t.id).collect();
+        let (van, kitchen, mail) = (ids[0], ids[1], ids[2]);
+        state.add_subtask(kitchen, new_todo("Buy boxes")).unwrap();
+
+        // Progress counts direct subtasks only
+        let todos = state.toggle(van).unwrap();
+        let progress = todos[0].progress.unwrap();
+        assert_eq!((progress.completed, progress.total), (1, 3));
+        assert_eq!(todos[0].subtasks[1].subtasks[0].text, "Buy boxes");
+        assert_eq!(todos[1].progress, None);
+
+        let todos = state
+            .reorder_subtasks(parent, &[mail, van, kitchen])
+            .unwrap();
+        let texts: Vec<&str> = todos[0].subtasks.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, vec!["Forward mail", "Book van", "Pack kitchen"]);
+        assert!(matches!(
+            state.reorder_subtasks(parent, &[mail, van]),
+            Err(AppError::Validation(_))
+        ));
+
+        // A todo can't end up under its own subtree
+        let boxes = todos[0].subtasks[2].subtasks[0].id;
+        assert!(matches!(
+            state.move_subtask(parent, Some(boxes)),
+            Err(AppError::Validation(_))
+        ));
+        let todos = state.move_subtask(mail, None).unwrap();
+        assert_eq!(todos.len(), 3);
+        assert_eq!(todos[2].text, "Forward mail");
+        assert_eq!(todos[0].progress.unwrap().total, 2);
+
+        // Deleting the parent takes the whole subtree with it
+        let todos = state.delete(parent).unwrap();
+        assert_eq!(todos.len(), 2);
+        assert!(repo.get(kitchen).unwrap().is_none());
+        assert!(repo.get(boxes).unwrap().is_none());
+        assert!(matches!(
+            state.add_subtask(parent, new_todo("Orphan")),
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_move_todo_and_sort_modes() {
+        let pool = create_test_pool();
+        let state = TodoState::new(Arc::new(SqliteTodoRepository::new(pool)));
+        let texts =
+            |todos: &[Todo]| -> Vec<String> { todos.iter().map(|t| t.text.clone()).collect() };
+
+        state
+            .add(NewTodo {
+                due_date: Some("2025-05-02".into()),
+                ..new_todo("A")
+            })
+            .unwrap();
+        state
+            .add(NewTodo {
+                priority: Priority::High,
+                ..new_todo("B")
+            })
+            .unwrap();
+        state
+            .add(NewTodo {
+                due_date: Some("2025-05-01".into()),
+                priority: Priority::Low,
+                ..new_todo("C")
+            })
+            .unwrap();
+        let todos = state.add(new_todo("D")).unwrap();
+        let id = |name: &str| todos.iter().find(|t| t.text == name).unwrap().id;
+        let (a, b, c, d) = (id("A"), id("B"), id("C"), id("D"));
+        let positions: Vec<i64> = todos.iter().map(|t| t.position).collect();
+
+        // Only the moved todo gets a new position
+        let moved = state.move_todo(d, Some(b), None).unwrap();
+        assert_eq!(texts(&moved), vec!["A", "D", "B", "C"]);
+        let unchanged: Vec<i64> = moved
+            .iter()
+            .filter(|t| t.id != d)
+            .map(|t| t.position)
+            .collect();
+        assert_eq!(unchanged, positions[..3].to_vec());
+
+        let moved = state.move_todo(a, None, Some(c)).unwrap();
+        assert_eq!(texts(&moved), vec!["D", "B", "C", "A"]);
+
+        // Repeatedly splitting the same gap eventually forces a renumbering, which keeps the order
+        for _ in 0..12 {
+            state.move_todo(c, None, Some(d)).unwrap();
+            state.move_todo(b, None, Some(d)).unwrap();
+        }
+        assert_eq!(texts(&state.get_all().unwrap()), vec!["D", "B", "C", "A"]);
+
+        // Dropping next to a subtask makes the todo a subtask too
+        state.add_subtask(a, new_todo("A1")).unwrap();
+        let a1 = state.get_all().unwrap()[3].subtasks[0].id;
+        let moved = state.move_todo(d, Some(a1), None).unwrap();
+        assert_eq!(texts(&moved), vec!["B", "C", "A"]);
+        assert_eq!(texts(&moved[2].subtasks), vec!["D", "A1"]);
+        assert!(matches!(
+            state.move_todo(a, Some(d), None),
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            state.move_todo(b, Some(c), Some(a)),
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            state.move_todo(b, Some(999), None),
+            Err(AppError::NotFound(_))
+        ));
+
+        let sorted = |sort: TodoSort| texts(&state.get_sorted(sort).unwrap());
+        assert_eq!(sorted(TodoSort::Manual), vec!["B", "C", "A"]);
+        assert_eq!(sorted(TodoSort::DueDate), vec!["C", "A", "B"]);
+        assert_eq!(sorted(TodoSort::Priority), vec!["B", "A", "C"]);
+        assert_eq!(sorted(TodoSort::Created), vec!["A", "B", "C"]);
+    }
+
+    #[test]
+    fn test_quick_add_stores_parsed_fields() {
+        let pool = create_test_pool();
+        let state = TodoState::new(Arc::new(SqliteTodoRepository::new(pool)));
+        let today = chrono::NaiveDate::from_ymd_opt(2025, 3, 12).unwrap();
+
+        let todos = state
+            .quick_add(
+                "pay invoice tomorrow 3pm !high #work #q1 every friday",
+                today,
+            )
+            .unwrap();
+        let todo = &todos[0];
+        assert_eq!(todo.text, "pay invoice");
+        assert_eq!(todo.due_date.as_deref(), Some("2025-03-13"));
+        assert_eq!(todo.due_time.as_deref(), Some("15:00"));
+        assert_eq!(todo.priority, Priority::High);
+        assert_eq!(todo.category.as_deref(), Some("work"));
+        assert_eq!(todo.tags, vec!["q1"]);
+        assert!(todo.recurrence.is_some());
+
+        let patch: TodoPatch =
+            serde_json::from_value(serde_json::json!({ "due_time": "25:00" })).unwrap();
+        assert!(matches!(
+            state.update(todo.id, patch),
+            Err(AppError::Validation(_))
+        ));
     }
 
     #[test]
     fn test_worklog_crud() {
-        let pool = setup_memory_db();
+        let pool = create_test_pool();
         let repo = Arc::new(SqliteWorkLogRepository::new(pool.clone()));
         let state = WorkLogState::new(repo);
 
         // Add
-        let logs = state.add("Project X".to_string(), 2.5).unwrap();
+        let logs = state
+            .add(NewWorkLog {
+                project: "Project X".to_string(),
+                hours: Some(2.5),
+                ..Default::default()
+            })
+            .unwrap();
         assert_eq!(logs.len(), 1);
         assert_eq!(logs[0].project, "Project X");
         assert_eq!(logs[0].hours, 2.5);
     }
 
+    #[test]
+    fn test_worklog_edit_and_validation() {
+        let pool = create_test_pool();
+        let state = WorkLogState::new(Arc::new(SqliteWorkLogRepository::new(pool)));
+        let entry = |hours: f32, date: &str| NewWorkLog {
+            project: "Brain".into(),
+            hours: Some(hours),
+            date: Some(date.into()),
+            ..Default::default()
+        };
+
+        // A forgotten entry for an earlier day, with start and end instead of hours
+        let logs = state
+            .add(NewWorkLog {
+                project: " Brain ".into(),
+                started_at: Some("2025-03-10T09:00:00+09:00".into()),
+                ended_at: Some("2025-03-10T11:30:00+09:00".into()),
+                description: Some("Sync design".into()),
+                ..Default::default()
+            })
+            .unwrap();
+        let log = &logs[0];
+        assert_eq!(log.project, "Brain");
+        assert_eq!(log.date, "2025-03-10");
+        assert_eq!(log.hours, 2.5);
+        assert_eq!(log.duration_seconds, Some(9000));
+        assert_eq!(log.description.as_deref(), Some("Sync design"));
+        let timed = log.id;
+
+        // Fix a typo: explicit hours that contradict the times are rejected, clearing them works
+        let patch: WorkLogPatch =
+            serde_json::from_value(serde_json::json!({ "hours": 3.0 })).unwrap();
+        assert!(matches!(
+            state.update(timed, patch),
+            Err(AppError::Validation(_))
+        ));
+        let patch: WorkLogPatch = serde_json::from_value(serde_json::json!({
+            "hours": 3.0,
+            "started_at": null,
+            "ended_at": null,
+            "description": null
+        }))
+        .unwrap();
+        let logs = state.update(timed, patch).unwrap();
+        assert_eq!(logs[0].hours, 3.0);
+        assert_eq!(logs[0].duration_seconds, None);
+        assert_eq!(logs[0].description, None);
+
+        // At most 24 hours per day, counting the other entries but not the one being edited
+        let logs = state.add(entry(20.0, "2025-03-10")).unwrap();
+        let big = logs[0].id;
+        assert!(matches!(
+            state.add(entry(1.5, "2025-03-10")),
+            Err(AppError::Validation(_))
+        ));
+        state.add(entry(1.0, "2025-03-10")).unwrap();
+        let patch = WorkLogPatch {
+            hours: Some(19.5),
+            ..Default::default()
+        };
+        state.update(big, patch).unwrap();
+        let patch = WorkLogPatch {
+            hours: Some(21.0),
+            ..Default::default()
+        };
+        assert!(matches!(
+            state.update(big, patch),
+            Err(AppError::Validation(_))
+        ));
+        // Moving it to another day frees the room
+        let patch = WorkLogPatch {
+            date: Some("2025-03-11".into()),
+            ..Default::default()
+        };
+        state.update(big, patch).unwrap();
+        state.add(entry(15.0, "2025-03-10")).unwrap();
+
+        for bad in [
+            entry(-1.0, "2025-03-12"),
+            entry(f32::NAN, "2025-03-12"),
+            entry(1.0, "12/03/2025"),
+            NewWorkLog {
+                project: "  ".into(),
+                ..entry(1.0, "2025-03-12")
+            },
+            NewWorkLog {
+                project: "Brain".into(),
+                ..Default::default()
+            },
+            NewWorkLog {
+                started_at: Some("2025-03-12T11:00:00+09:00".into()),
+                ended_at: Some("2025-03-12T10:00:00+09:00".into()),
+                ..entry(1.0, "2025-03-12")
+            },
+        ] {
+            assert!(
+                matches!(state.add(bad.clone()), Err(AppError::Validation(_))),
+                "{:?}",
+                bad
+            );
+        }
+
+        let logs = state.delete(timed).unwrap();
+        assert!(logs.iter().all(|l| l.id != timed));
+        assert!(matches!(state.delete(timed), Err(AppError::NotFound(_))));
+        assert!(matches!(
+            state.update(timed, WorkLogPatch::default()),
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_work_log_stats() {
+        let pool = create_test_pool();
+        let state = WorkLogState::new(Arc::new(SqliteWorkLogRepository::new(pool)));
+        let log = |project: &str, hours: f32, date: &str| {
+            state
+                .add(NewWorkLog {
+                    project: project.into(),
+                    hours: Some(hours),
+                    date: Some(date.into()),
+                    ..Default::default()
+                })
+                .unwrap();
+        };
+        // 2025-03-12 is a Wednesday; its week runs 03-10 to 03-16
+        log("Brain", 2.0, "2025-03-06");
+        log("Brain", 1.0, "2025-03-09");
+        log("Brain", 3.0, "2025-03-10");
+        log("Mobile", 1.5, "2025-03-11");
+        log("Brain", 0.5, "2025-03-12");
+        log("Mobile", 0.25, "2025-03-12");
+        log("Mobile", 4.0, "2025-03-14");
+        let today = chrono::NaiveDate::from_ymd_opt(2025, 3, 12).unwrap();
+
+        let totals = state.totals(today).unwrap();
+        assert_eq!(totals.today, "2025-03-12");
+        assert_eq!(totals.today_hours, 0.75);
+        assert_eq!(totals.week_start, "2025-03-10");
+        assert_eq!(totals.week_hours, 9.25);
+
+        let all = state.project_hours(None, None).unwrap();
+        let flat: Vec<(&str, f64)> = all.iter().map(|p| (p.project.as_str(), p.hours)).collect();
+        assert_eq!(flat, vec![("Brain", 6.5), ("Mobile", 5.75)]);
+        let week = state
+            .project_hours(Some("2025-03-10"), Some("2025-03-12"))
+            .unwrap();
+        let flat: Vec<(&str, f64, f64)> = week
+            .iter()
+            .map(|p| (p.project.as_str(), p.hours, p.share))
+            .collect();
+        assert_eq!(flat, vec![("Brain", 3.5, 0.667), ("Mobile", 1.75, 0.333)]);
+        assert!(matches!(
+            state.project_hours(Some("2025-03-12"), Some("2025-03-10")),
+            Err(AppError::Validation(_))
+        ));
+
+        let daily = state.daily_hours("2025-03-08", "2025-03-12").unwrap();
+        let flat: Vec<(&str, f64)> = daily.iter().map(|d| (d.date.as_str(), d.hours)).collect();
+        assert_eq!(
+            flat,
+            vec![
+                ("2025-03-08", 0.0),
+                ("2025-03-09", 1.0),
+                ("2025-03-10", 3.0),
+                ("2025-03-11", 1.5),
+                ("2025-03-12", 0.75)
+            ]
+        );
+        assert!(state.daily_hours("2025-03-12", "2025-03-11").is_err());
+        assert!(state.daily_hours("2020-01-01", "2025-03-12").is_err());
+
+        let trend = state.trend(today, 3, 4).unwrap();
+        let flat: Vec<(&str, f64, f64)> = trend
+            .points
+            .iter()
+            .map(|p| (p.date.as_str(), p.hours, p.average))
+            .collect();
+        // Each average reaches back four days, before the first point shown
+        assert_eq!(
+            flat,
+            vec![
+                ("2025-03-10", 3.0, 1.0),
+                ("2025-03-11", 1.5, 1.38),
+                ("2025-03-12", 0.75, 1.56)
+            ]
+        );
+        assert_eq!(trend.current_streak, 4);
+        assert_eq!(trend.longest_streak, 4);
+        // A future-dated log doesn't extend today's streak
+        let later = state
+            .trend(chrono::NaiveDate::from_ymd_opt(2025, 3, 16).unwrap(), 7, 7)
+            .unwrap();
+        assert_eq!(later.points.len(), 7);
+        assert_eq!(later.current_streak, 0);
+        assert!(state.trend(today, 0, 7).is_err());
+        assert!(state.trend(today, 7, 0).is_err());
+    }
+
+    #[test]
+    fn test_work_log_export_and_import() {
+        let pool = create_test_pool();
+        let state = WorkLogState::new(Arc::new(SqliteWorkLogRepository::new(pool)));
+        let kst = chrono::FixedOffset::east_opt(9 * 3600).unwrap();
+        state
+            .add(NewWorkLog {
+                project: "Brain".into(),
+                started_at: Some("2025-03-10T09:00:00+09:00".into()),
+                ended_at: Some("2025-03-10T11:00:00+09:00".into()),
+                description: Some("Design review".into()),
+                ..Default::default()
+            })
+            .unwrap();
+        for (project, hours, date) in [("Mobile", 1.5, "2025-03-11"), ("Brain", 3.0, "2025-04-01")]
+        {
+            state
+                .add(NewWorkLog {
+                    project: project.into(),
+                    hours: Some(hours),
+                    date: Some(date.into()),
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+        let now = chrono::DateTime::parse_from_rfc3339("2025-04-02T12:00:00+09:00").unwrap();
+
+        let csv = state
+            .export(
+                ExportFormat::Csv,
+                Some("2025-03-01"),
+                Some("2025-03-31"),
+                true,
+                now,
+            )
+            .unwrap();
+        assert!(csv.contains("2025-03-10,Brain,2.00,2025-03-10T09:00:00+09:00,"));
+        assert!(csv.contains("2025-03-11,Mobile,1.50,,,\r\n"));
+        assert!(!csv.contains("2025-04-01"));
+        assert!(csv.contains("project,hours,share\r\nBrain,2.00,0.571\r\nMobile,1.50,0.429\r\n"));
+
+        let ical = state
+            .export(ExportFormat::Ical, None, None, false, now)
+            .unwrap();
+        assert_eq!(ical.matches("BEGIN:VEVENT").count(), 1);
+        assert!(ical.contains("SUMMARY:Brain\r\n"));
+
+        let json = state
+            .export(ExportFormat::Json, None, Some("2025-03-31"), false, now)
+            .unwrap();
+        let sheet: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(sheet["to"], "2025-03-31");
+        assert_eq!(sheet["logs"].as_array().unwrap().len(), 2);
+        assert!(sheet.get("projects").is_none());
+        assert!(state
+            .export(ExportFormat::Csv, Some("March"), None, false, now)
+            .is_err());
+
+        // Re-importing our own export finds nothing new
+        let result = state.import_csv(&csv, kst).unwrap();
+        assert_eq!(
+            result,
+            WorkLogImport {
+                imported: 0,
+                duplicates: 2,
+                errors: vec![],
+            }
+        );
+
+        let other = "Project,Description,Start date,Start time,End date,End time,Duration\n\
+                     brain,Design review,2025-03-10,09:00,2025-03-10,11:00,2:00:00\n\
+                     Brain,Pairing,2025-03-10,13:00,2025-03-10,14:30,1:30:00\n\
+                     Brain,Pairing again,2025-03-10,13:00,2025-03-10,14:30,1:30:00\n\
+                     Brain,Too long,2025-03-12,09:00,2025-03-12,10:00,2:00:00\n\
+                     Brain,Marathon,2025-03-11,00:00,2025-03-11,23:00,23:00:00\n\
+                     ,No project,2025-03-13,09:00,2025-03-13,10:00,1:00:00\n\
+                     Brain,Bad,2025-03-14,nine,2025-03-14,10:00,1:00:00\n";
+        let result = state.import_csv(other, kst).unwrap();
+        assert_eq!(result.imported, 1);
+        // The stored morning session (project case differs) and the repeated afternoon row
+        assert_eq!(result.duplicates, 2);
+        let lines: Vec<&str> = result
+            .errors
+            .iter()
+            .map(|e| e.split(':').next().unwrap())
+            .collect();
+        // More hours than the times allow, a day over 24 hours, no project, a bad time
+        assert_eq!(lines, vec!["Line 5", "Line 6", "Line 7", "Line 8"]);
+
+        let logs = state
+            .repo
+            .get_between(Some("2025-03-10"), Some("2025-03-10"))
+            .unwrap();
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[1].description.as_deref(), Some("Pairing"));
+        assert_eq!(logs[1].duration_seconds, Some(5400));
+
+        assert!(matches!(
+            state.import_csv("hours\n1\n", kst),
+            Err(AppError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_work_timer_lifecycle() {
+        let pool = create_test_pool();
+        let repo = SqliteWorkLogRepository::new(pool.clone());
+        let at = |time: &str| {
+            chrono::DateTime::parse_from_rfc3339(&format!("2025-03-12T{}+09:00", time)).unwrap()
+        };
+
+        let id = repo.start_timer("Brain", false, at("09:00:00")).unwrap();
+        // Only one running timer unless asked for
+        assert!(matches!(
+            repo.start_timer("Other", false, at("09:05:00")),
+            Err(AppError::Validation(_))
+        ));
+        let other = repo.start_timer("Other", true, at("09:05:00")).unwrap();
+        repo.stop_timer(other, at("09:10:00")).unwrap();
+
+        repo.pause_timer(id, at("09:30:00")).unwrap();
+        assert!(matches!(
+            repo.pause_timer(id, at("09:31:00")),
+            Err(AppError::Validation(_))
+        ));
+
+        // The timer lives in the database, so a fresh repository (an app restart) still has it
+        let repo = SqliteWorkLogRepository::new(pool);
+        let timers = repo.get_timers(at("09:45:00")).unwrap();
+        assert_eq!(timers.len(), 1);
+        assert!(!timers[0].running);
+        assert_eq!(timers[0].elapsed_seconds, 30 * 60);
+
+        repo.resume_timer(id, false, at("10:00:00")).unwrap();
+        assert_eq!(
+            repo.get_timers(at("10:15:00")).unwrap()[0].elapsed_seconds,
+            45 * 60
+        );
+        repo.stop_timer(id, at("10:45:30")).unwrap();
+        assert!(repo.get_timers(at("11:00:00")).unwrap().is_empty());
+        assert!(matches!(
+            repo.stop_timer(id, at("11:00:00")),
+            Err(AppError::NotFound(_))
+        ));
+
+        let logs = repo.get_all().unwrap();
+        assert_eq!(logs.len(), 2);
+        let log = &logs[0];
+        assert_eq!(log.project, "Brain");
+        assert_eq!(log.duration_seconds, Some(75 * 60 + 30));
+        assert!((log.hours - 1.2583).abs() < 0.001);
+        assert_eq!(log.date, "2025-03-12");
+        assert_eq!(log.started_at.as_deref(), Some("2025-03-12T09:00:00+09:00"));
+        assert_eq!(log.ended_at.as_deref(), Some("2025-03-12T10:45:30+09:00"));
+        assert_eq!(logs[1].duration_seconds, Some(5 * 60));
+
+        // Editing the log keeps its worked time; the 30 paused minutes are not added back
+        let state = WorkLogState::new(Arc::new(repo));
+        let timer_log = log.id;
+        let patch: WorkLogPatch = serde_json::from_value(serde_json::json!({
+            "description": "Planning",
+            "started_at": "2025-03-12T09:00:00+09:00"
+        }))
+        .unwrap();
+        let edited = state.update(timer_log, patch).unwrap().remove(0);
+        assert_eq!(edited.duration_seconds, Some(75 * 60 + 30));
+        assert!((edited.hours - 1.2583).abs() < 0.001);
+        assert_eq!(edited.description.as_deref(), Some("Planning"));
+
+        // Hours may be corrected down, but not past the span between start and end
+        let patch = WorkLogPatch {
+            hours: Some(1.25),
+            ..Default::default()
+        };
+        let edited = state.update(timer_log, patch).unwrap().remove(0);
+        assert_eq!(edited.hours, 1.25);
+        assert_eq!(edited.duration_seconds, Some(75 * 60));
+        let patch = WorkLogPatch {
+            hours: Some(2.0),
+            ..Default::default()
+        };
+        assert!(matches!(
+            state.update(timer_log, patch),
+            Err(AppError::Validation(_))
+        ));
+
+        // Its timesheet row imports as a duplicate rather than failing the hours check
+        let kst = chrono::FixedOffset::east_opt(9 * 3600).unwrap();
+        let now = at("12:00:00");
+        let csv = state
+            .export(ExportFormat::Csv, None, None, false, now)
+            .unwrap();
+        let result = state.import_csv(&csv, kst).unwrap();
+        assert_eq!(result.duplicates, 2);
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+
+        // A timer left running overnight can't be stopped into more than 24 hours on its day
+        let next_day = |time: &str| {
+            chrono::DateTime::parse_from_rfc3339(&format!("2025-03-13T{}+09:00", time)).unwrap()
+        };
+        let forgotten = state
+            .repo
+            .start_timer("Brain", false, at("18:00:00"))
+            .unwrap();
+        assert!(matches!(
+            state.stop_timer_at(forgotten, next_day("19:00:00")),
+            Err(AppError::Validation(_))
+        ));
+        assert_eq!(state.repo.get_timers(now).unwrap().len(), 1);
+        // Nor can one that fits alone but not next to the day's other logs
+        state.discard_timer(forgotten).unwrap();
+        assert!(matches!(
+            state.discard_timer(forgotten),
+            Err(AppError::NotFound(_))
+        ));
+        let late = state
+            .repo
+            .start_timer("Brain", false, at("20:00:00"))
+            .unwrap();
+        assert!(matches!(
+            state.stop_timer_at(late, next_day("19:30:00")),
+            Err(AppError::Validation(_))
+        ));
+        let logs = state.stop_timer_at(late, at("21:00:00")).unwrap();
+        assert_eq!(logs.len(), 3);
+        assert!(matches!(
+            state.stop_timer_at(late, at("21:00:00")),
+            Err(AppError::NotFound(_))
+        ));
+    }
+
     #[test]
     fn test_articles_db_ops() {
         // Changed to synchronous test since we refactored the logic to be synchronous helper methods.
         // This avoids async/tokio complexity in unit tests for pure DB logic.
 
-        let pool = setup_memory_db();
+        let pool = create_test_pool();
         let conn = pool.get().unwrap();
         conn.execute(
              "INSERT INTO articles (id, title, summary, url, tags, published_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
@@ -117,4 +867,451 @@ mod tests {
         assert!(!articles.is_empty());
         assert_eq!(articles[0].title, "Test Article");
     }
+
+    #[test]
+    fn test_migrations_are_sequential() {
+        for (i, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version, i as u32 + 1);
+        }
+    }
+
+    #[test]
+    fn test_migrations_fresh_db() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        assert_eq!(current_version(&conn).unwrap(), 0);
+
+        run_migrations(&mut conn).unwrap();
+        assert_eq!(current_version(&conn).unwrap(), latest_version());
+
+        // Re-running is a no-op
+        run_migrations(&mut conn).unwrap();
+        assert_eq!(current_version(&conn).unwrap(), latest_version());
+    }
+
+    #[test]
+    fn test_migrations_upgrade_legacy_db() {
+        // Schema as created by releases before server sync existed (no user_version set)
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE articles (
+                id TEXT PRIMARY KEY,
+                title TEXT,
+                summary TEXT,
+                url TEXT UNIQUE,
+                tags TEXT,
+                published_at TEXT,
+                image_url TEXT NULL,
+                author TEXT NULL,
+                feedback_helpful BOOLEAN NULL,
+                feedback_reason TEXT NULL,
+                feedback_at TEXT NULL
+            );
+            INSERT INTO articles (id, title, summary, url, tags, published_at)
+                VALUES ('1', 'Old', 'Summary', 'http://old.com', '[]', '2023-01-01');",
+        )
+        .unwrap();
+
+        run_migrations(&mut conn).unwrap();
+
+        let (title, server_id): (String, Option<String>) = conn
+            .query_row(
+                "SELECT title, server_article_id FROM articles WHERE id = '1'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(title, "Old");
+        assert!(server_id.is_none());
+    }
+
+    #[test]
+    fn test_migrations_refuse_newer_db() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "user_version", latest_version() + 1)
+            .unwrap();
+
+        assert!(run_migrations(&mut conn).is_err());
+    }
+
+    #[test]
+    fn test_feed_state_round_trip() {
+        let pool = create_test_pool();
+        let repo = SqliteRecommendationRepository::new(pool);
+
+        let mut state = FeedState::new("https://example.com/feed.xml");
+        state.record_success(200, Some("\"abc\"".into()), None, "2025-01-01T00:00:00Z");
+        repo.save_feed_states(std::slice::from_ref(&state)).unwrap();
+
+        state.record_failure(Some(500), "Internal Server Error", "2025-01-02T00:00:00Z");
+        repo.save_feed_states(std::slice::from_ref(&state)).unwrap();
+
+        let states = repo.get_feed_states().unwrap();
+        assert_eq!(states, vec![state]);
+        assert_eq!(states[0].etag.as_deref(), Some("\"abc\""));
+        assert_eq!(states[0].consecutive_failures, 1);
+    }
+
+    #[test]
+    fn test_feed_subscriptions() {
+        let pool = create_test_pool();
+        let repo = SqliteRecommendationRepository::new(pool);
+
+        // Seeded by the migration
+        let feeds = repo.get_feeds().unwrap();
+        assert_eq!(feeds.len(), SEED_FEEDS.len());
+        assert_eq!(feeds[0].url, SEED_FEEDS[0].0);
+        assert_eq!(feeds[0].category, SEED_FEEDS[0].1.parse().unwrap());
+
+        let feed = repo
+            .add_feed(
+                "https://example.com/atom.xml",
+                None,
+                &ArticleCategory::Kotlin,
+            )
+            .unwrap();
+        assert!(repo
+            .add_feed(
+                "https://example.com/atom.xml",
+                None,
+                &ArticleCategory::Kotlin
+            )
+            .is_err());
+        assert!(repo
+            .add_feed("ftp://example.com/feed", None, &ArticleCategory::Web)
+            .is_err());
+
+        repo.rename_feed(feed.id, Some("Example")).unwrap();
+        repo.set_feed_category(feed.id, &ArticleCategory::Android)
+            .unwrap();
+        repo.set_feed_enabled(feed.id, false).unwrap();
+
+        let stored = repo
+            .get_feeds()
+            .unwrap()
+            .into_iter()
+            .find(|f| f.id == feed.id)
+            .unwrap();
+        assert_eq!(stored.title.as_deref(), Some("Example"));
+        assert_eq!(stored.category, ArticleCategory::Android);
+        assert!(!stored.enabled);
+        assert_eq!(repo.get_enabled_feeds().unwrap().len(), SEED_FEEDS.len());
+
+        repo.remove_feed(feed.id).unwrap();
+        assert_eq!(repo.get_feeds().unwrap().len(), SEED_FEEDS.len());
+        assert!(repo.remove_feed(feed.id).is_err());
+    }
+
+    fn sample_article(id: &str, title: &str, summary: &str, tags: Vec<ArticleCategory>) -> Article {
+        Article {
+            id: id.into(),
+            title: title.into(),
+            summary: summary.into(),
+            url: format!("https://example.com/{}", id),
+            tags,
+            published_at: "2025-01-01".into(),
+            feedback: None,
+            image_url: None,
+            author: Some("Ferris".into()),
+            state: ArticleState::Unread,
+            server_rating: None,
+        }
+    }
+
+    #[test]
+    fn test_search_articles() {
+        let pool = create_test_pool();
+        let repo = SqliteRecommendationRepository::new(pool);
+
+        repo.upsert_articles(vec![
+            sample_article(
+                "a",
+                "Async Rust in practice",
+                "Executors and wakers explained.",
+                vec![ArticleCategory::Rust],
+            ),
+            sample_article(
+                "b",
+                "Weekly roundup",
+                "This week: async closures land in Rust.",
+                vec![ArticleCategory::General],
+            ),
+            sample_article(
+                "c",
+                "React Server Components",
+                "Streaming UI from the server.",
+                vec![ArticleCategory::React],
+            ),
+        ])
+        .unwrap();
+        // Rated articles disappear from get_articles but stay searchable
+        repo.update_feedback("a", true, "great", "2025-01-02")
+            .unwrap();
+
+        let results = repo
+            .search_articles("async", &SearchFilters::default())
+            .unwrap();
+        let ids: Vec<&str> = results.iter().map(|r| r.article.id.as_str()).collect();
+        // Title match ranks above summary-only match
+        assert_eq!(ids, vec!["a", "b"]);
+        assert_eq!(
+            results[0].title_highlight,
+            "<mark>Async</mark> Rust in practice"
+        );
+        assert!(results[1].snippet.contains("<mark>async</mark>"));
+        assert!(results[0].article.feedback.is_some());
+
+        // Prefix match on the last term, author column indexed
+        assert_eq!(
+            repo.search_articles("ferr", &SearchFilters::default())
+                .unwrap()
+                .len(),
+            3
+        );
+
+        // Filters
+        let rust_only = SearchFilters {
+            category: Some(ArticleCategory::Rust),
+            ..Default::default()
+        };
+        let results = repo.search_articles("async", &rust_only).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].article.id, "a");
+
+        let unrated = SearchFilters {
+            rated: Some(false),
+            ..Default::default()
+        };
+        let results = repo.search_articles("async", &unrated).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].article.id, "b");
+
+        // FTS syntax in user input is treated literally instead of failing
+        assert!(repo
+            .search_articles("\"unbalanced AND (", &SearchFilters::default())
+            .is_ok());
+        assert!(repo
+            .search_articles("   ", &SearchFilters::default())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_search_index_follows_updates() {
+        let pool = create_test_pool();
+        let repo = SqliteRecommendationRepository::new(pool.clone());
+
+        repo.upsert_articles(vec![sample_article(
+            "a",
+            "Kotlin coroutines",
+            "Structured concurrency.",
+            vec![ArticleCategory::Kotlin],
+        )])
+        .unwrap();
+
+        let conn = pool.get().unwrap();
+        conn.execute(
+            "UPDATE articles SET title = 'Kotlin flows' WHERE id = 'a'",
+            [],
+        )
+        .unwrap();
+        let filters = SearchFilters::default();
+        assert!(repo
+            .search_articles("coroutines", &filters)
+            .unwrap()
+            .is_empty());
+        assert_eq!(repo.search_articles("flows", &filters).unwrap().len(), 1);
+
+        conn.execute("DELETE FROM articles WHERE id = 'a'", [])
+            .unwrap();
+        assert!(repo.search_articles("flows", &filters).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_article_states() {
+        let pool = create_test_pool();
+        let repo = SqliteRecommendationRepository::new(pool);
+
+        repo.upsert_articles(
+            ["a", "b", "c", "d"]
+                .iter()
+                .map(|id| sample_article(id, "Title", "Summary", vec![ArticleCategory::Rust]))
+                .collect(),
+        )
+        .unwrap();
+
+        // Rating marks an unread article as read instead of losing it
+        repo.update_feedback("a", false, "meh", "2025-01-02")
+            .unwrap();
+        repo.set_article_state("b", ArticleState::Saved, "2025-01-03")
+            .unwrap();
+        repo.set_article_state("c", ArticleState::Archived, "2025-01-04")
+            .unwrap();
+        // Rating a saved article keeps it saved
+        repo.update_feedback("b", true, "good", "2025-01-05")
+            .unwrap();
+        assert!(repo
+            .set_article_state("missing", ArticleState::Read, "2025-01-06")
+            .is_err());
+
+        let read = repo
+            .get_articles_by_state(ArticleState::Read, 10, 0)
+            .unwrap();
+        assert_eq!(read.total, 1);
+        assert_eq!(read.articles[0].id, "a");
+        assert!(read.articles[0].feedback.is_some());
+
+        let saved = repo
+            .get_articles_by_state(ArticleState::Saved, 10, 0)
+            .unwrap();
+        assert_eq!(saved.articles[0].id, "b");
+        assert_eq!(saved.articles[0].state, ArticleState::Saved);
+
+        // Only unread, unrated articles are recommendation candidates
+        let candidates = repo.get_articles().unwrap();
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].id, "d");
+
+        // Pagination
+        for id in ["a", "b", "c"] {
+            repo.set_article_state(id, ArticleState::Unread, "2025-01-07")
+                .unwrap();
+        }
+        let first = repo
+            .get_articles_by_state(ArticleState::Unread, 3, 0)
+            .unwrap();
+        let second = repo
+            .get_articles_by_state(ArticleState::Unread, 3, 3)
+            .unwrap();
+        assert_eq!(first.total, 4);
+        assert_eq!(first.articles.len(), 3);
+        assert_eq!(second.articles.len(), 1);
+    }
+
+    #[test]
+    fn test_recommendation_batches() {
+        let pool = create_test_pool();
+        let repo = SqliteRecommendationRepository::new(pool);
+
+        let batch = |outcome, error: Option<&str>| RecommendationBatch {
+            id: 0,
+            created_at: "2025-01-01T00:00:00+00:00".into(),
+            outcome,
+            provider: Some("gemini".into()),
+            model: Some("gemini-test".into()),
+            attempts: 2,
+            article_ids: vec!["a".into(), "b".into()],
+            error: error.map(|e| e.to_string()),
+        };
+
+        repo.save_recommendation_batch(&batch(RecommendationOutcome::Ai, None))
+            .unwrap();
+        repo.save_recommendation_batch(&batch(RecommendationOutcome::Ai, None))
+            .unwrap();
+        let last_id = repo
+            .save_recommendation_batch(&batch(RecommendationOutcome::Fallback, Some("bad JSON")))
+            .unwrap();
+
+        let batches = repo.get_recommendation_batches(2).unwrap();
+        assert_eq!(batches.len(), 2);
+        assert_eq!(
+            batches[0],
+            RecommendationBatch {
+                id: last_id,
+                ..batch(RecommendationOutcome::Fallback, Some("bad JSON"))
+            }
+        );
+
+        assert_eq!(
+            repo.get_recommendation_stats().unwrap(),
+            RecommendationStats {
+                total: 3,
+                ai: 2,
+                fallback: 1,
+                error: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_keyword_weights_learn_and_reset() {
+        let pool = create_test_pool();
+        let repo = SqliteRecommendationRepository::new(pool);
+        let now = "2025-03-01T00:00:00+00:00";
+
+        let weight = |term: &str, at: &str| {
+            repo.get_keyword_weights(at)
+                .unwrap()
+                .into_iter()
+                .find(|w| w.term == term)
+        };
+
+        // Seeded by the migration
+        let crypto = weight("crypto", now).unwrap();
+        assert_eq!(crypto.weight, -20.0);
+        assert_eq!(crypto.base_weight, -20.0);
+        assert_eq!(crypto.feedback_count, 0);
+
+        let terms = vec!["crypto".to_string(), "zkvm".to_string()];
+        repo.apply_keyword_feedback(&terms, 2.0, now).unwrap();
+        repo.apply_keyword_feedback(&terms, 2.0, now).unwrap();
+
+        let crypto = weight("crypto", now).unwrap();
+        assert_eq!(crypto.weight, -16.0);
+        assert_eq!(crypto.feedback_count, 2);
+        // Unknown terms are learned from scratch
+        assert_eq!(weight("zkvm", now).unwrap().weight, 4.0);
+
+        // One half-life later only half of the learning is left
+        let later = "2025-03-31T00:00:00+00:00";
+        assert_eq!(weight("crypto", later).unwrap().weight, -18.0);
+        assert_eq!(weight("zkvm", later).unwrap().weight, 2.0);
+
+        // New feedback builds on the decayed value; a learned term back at zero is forgotten
+        repo.apply_keyword_feedback(&["zkvm".to_string()], -2.0, later)
+            .unwrap();
+        assert!(weight("zkvm", later).is_none());
+        repo.apply_keyword_feedback(&["zkvm".to_string()], 2.0, later)
+            .unwrap();
+        assert_eq!(weight("zkvm", later).unwrap().weight, 2.0);
+
+        repo.reset_keyword_weights(Some("crypto")).unwrap();
+        assert_eq!(weight("crypto", later).unwrap().weight, -20.0);
+        assert!(weight("zkvm", later).is_some());
+        assert!(repo.reset_keyword_weights(Some("nope")).is_err());
+
+        repo.reset_keyword_weights(None).unwrap();
+        assert!(weight("zkvm", later).is_none());
+        assert_eq!(
+            repo.get_keyword_weights(later).unwrap().len(),
+            crate::db::migrations::SEED_KEYWORD_WEIGHTS.len()
+        );
+    }
+
+    #[test]
+    fn test_learned_keyword_terms_are_capped() {
+        use crate::db::migrations::SEED_KEYWORD_WEIGHTS;
+        use crate::features::recommendation::scoring::MAX_LEARNED_TERMS;
+
+        let pool = create_test_pool();
+        let repo = SqliteRecommendationRepository::new(pool);
+        let now = "2025-03-01T00:00:00+00:00";
+
+        let strong = vec!["zkvm".to_string()];
+        for _ in 0..3 {
+            repo.apply_keyword_feedback(&strong, 2.0, now).unwrap();
+        }
+        let many: Vec<String> = (0..MAX_LEARNED_TERMS + 20)
+            .map(|i| format!("term{}", i))
+            .collect();
+        repo.apply_keyword_feedback(&many, 2.0, now).unwrap();
+
+        let weights = repo.get_keyword_weights(now).unwrap();
+        assert_eq!(
+            weights.len(),
+            SEED_KEYWORD_WEIGHTS.len() + MAX_LEARNED_TERMS
+        );
+        // The strongest learned terms and every seeded one survive
+        assert!(weights.iter().any(|w| w.term == "zkvm"));
+        assert!(weights.iter().any(|w| w.term == "crypto"));
+    }
 }
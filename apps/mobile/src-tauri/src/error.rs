@@ -7,9 +7,71 @@ pub enum AppError {
     Network(String),
     Io(String),
     Sync(String),
+    /// The Brain Server answered with an error status (other than 401)
+    Server {
+        status: u16,
+        body: String,
+    },
+    /// The Brain Server rejected the request's credentials (HTTP 401)
+    Unauthorized(String),
+    /// The Brain Server accepted the request but answered with a body we can't read
+    InvalidResponse(String),
+    Validation(String),
+    NotFound(String),
     Unknown(String),
 }
 
+impl AppError {
+    /// Stable identifier the frontend can match on
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Database(_) => "database",
+            AppError::Network(_) => "network",
+            AppError::Io(_) => "io",
+            AppError::Sync(_) => "sync",
+            AppError::Server { .. } => "server",
+            AppError::Unauthorized(_) => "unauthorized",
+            AppError::InvalidResponse(_) => "invalid_response",
+            AppError::Validation(_) => "validation",
+            AppError::NotFound(_) => "not_found",
+            AppError::Unknown(_) => "unknown",
+        }
+    }
+
+    /// Whether the same call may succeed later without the user changing anything
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            AppError::Network(_) | AppError::Sync(_) => true,
+            AppError::Server { status, .. } => *status >= 500 || *status == 429,
+            _ => false,
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            AppError::Database(msg)
+            | AppError::Network(msg)
+            | AppError::Io(msg)
+            | AppError::Sync(msg)
+            | AppError::Unauthorized(msg)
+            | AppError::InvalidResponse(msg)
+            | AppError::Validation(msg)
+            | AppError::NotFound(msg)
+            | AppError::Unknown(msg) => msg.clone(),
+            AppError::Server { status, .. } => format!("Brain Server returned HTTP {}", status),
+        }
+    }
+
+    fn details(&self) -> Option<serde_json::Value> {
+        match self {
+            AppError::Server { status, body } => {
+                Some(serde_json::json!({ "status": status, "body": body }))
+            }
+            _ => None,
+        }
+    }
+}
+
 impl std::error::Error for AppError {}
 
 impl fmt::Display for AppError {
@@ -19,18 +81,41 @@ impl fmt::Display for AppError {
             AppError::Network(msg) => write!(f, "Network Error: {}", msg),
             AppError::Io(msg) => write!(f, "I/O Error: {}", msg),
             AppError::Sync(msg) => write!(f, "Sync Error: {}", msg),
+            AppError::Server { status, body } => {
+                write!(f, "Server Error: HTTP {}: {}", status, body)
+            }
+            AppError::Unauthorized(msg) => write!(f, "Unauthorized: {}", msg),
+            AppError::InvalidResponse(msg) => write!(f, "Invalid Response: {}", msg),
+            AppError::Validation(msg) => write!(f, "Validation Error: {}", msg),
+            AppError::NotFound(msg) => write!(f, "Not Found: {}", msg),
             AppError::Unknown(msg) => write!(f, "Error: {}", msg),
         }
     }
 }
 
-// Implement Serialize so it can be returned to Tauri frontend as a String or Object
+/// What a failed command returns to the frontend.
+#[derive(Serialize)]
+struct ErrorPayload {
+    code: &'static str,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    details: Option<serde_json::Value>,
+    retryable: bool,
+}
+
+// Commands reject with `{ code, message, details?, retryable }`
 impl Serialize for AppError {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        serializer.serialize_str(&self.to_string())
+        ErrorPayload {
+            code: self.code(),
+            message: self.message(),
+            details: self.details(),
+            retryable: self.is_retryable(),
+        }
+        .serialize(serializer)
     }
 }
 
@@ -69,3 +154,41 @@ impl From<String> for AppError {
         AppError::Unknown(err)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_serializes_as_structured_object() {
+        assert_eq!(
+            serde_json::to_value(AppError::NotFound("Todo 3".into())).unwrap(),
+            json!({"code": "not_found", "message": "Todo 3", "retryable": false})
+        );
+        assert_eq!(
+            serde_json::to_value(AppError::Network("connection refused".into())).unwrap(),
+            json!({"code": "network", "message": "connection refused", "retryable": true})
+        );
+        assert_eq!(
+            serde_json::to_value(AppError::Server {
+                status: 503,
+                body: "maintenance".into()
+            })
+            .unwrap(),
+            json!({
+                "code": "server",
+                "message": "Brain Server returned HTTP 503",
+                "details": {"status": 503, "body": "maintenance"},
+                "retryable": true
+            })
+        );
+        assert!(!AppError::Server {
+            status: 400,
+            body: String::new()
+        }
+        .is_retryable());
+        // The same response would fail to parse again
+        assert!(!AppError::InvalidResponse("missing field `id`".into()).is_retryable());
+    }
+}
@@ -1,22 +1,44 @@
 use crate::error::AppError;
+use crate::features::recommendation::llm::LlmConfig;
 use crate::features::recommendation::system::RecommendationState;
 use tauri::State;
 
 #[tauri::command]
-pub async fn test_ai_connection(
-    _state: State<'_, RecommendationState>,
-) -> Result<String, AppError> {
-    let api_key = std::env::var("GEMINI_API_KEY").unwrap_or_default();
+pub async fn test_ai_connection(state: State<'_, RecommendationState>) -> Result<String, AppError> {
+    let config = match LlmConfig::from_env() {
+        Ok(config) => config,
+        Err(e) => return Ok(format!("❌ {}", e)),
+    };
 
-    if api_key.is_empty() {
-        return Ok("❌ No GEMINI_API_KEY found in environment variables".to_string());
-    }
+    let Some(provider) = &state.llm else {
+        return Ok(format!(
+            "❌ LLM provider {:?} is not configured (set LLM_API_KEY, GEMINI_API_KEY or LLM_BASE_URL)",
+            config.kind
+        ));
+    };
 
-    let masked = if api_key.len() > 8 {
-        format!("{}...{}", &api_key[0..4], &api_key[api_key.len() - 4..])
-    } else {
-        "***".to_string()
+    let key = match config.api_key.as_deref() {
+        Some(api_key) if api_key.len() > 8 => {
+            format!("{}...{}", &api_key[0..4], &api_key[api_key.len() - 4..])
+        }
+        Some(_) => "***".to_string(),
+        None => "none".to_string(),
     };
 
-    Ok(format!("✅ GEMINI_API_KEY detected: {}", masked))
+    match provider.generate("Reply with the single word: pong").await {
+        Ok(reply) => Ok(format!(
+            "✅ {} ({}) responded: {} [key: {}]",
+            provider.name(),
+            provider.model(),
+            reply.trim(),
+            key
+        )),
+        Err(e) => Ok(format!(
+            "❌ {} ({}) request failed: {} [key: {}]",
+            provider.name(),
+            provider.model(),
+            e,
+            key
+        )),
+    }
 }
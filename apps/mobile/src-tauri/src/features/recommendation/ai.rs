@@ -1,14 +1,28 @@
-use super::config::GEMINI_API_URL;
-use super::model::{Article, ArticleCategory, Feedback, UserPersona};
+use super::llm::LlmProvider;
+use super::model::{Article, ArticleCategory, Feedback, RecommendationOutcome, UserPersona};
+use std::collections::{HashMap, HashSet};
+
+/// Number of articles the model is asked to pick.
+pub const AI_PICK_COUNT: usize = 4;
 
 pub struct AIService;
 
+/// Result of `AIService::recommend_with_llm`, including how it was reached.
+#[derive(Debug, Clone)]
+pub struct AiSelection {
+    pub articles: Vec<Article>,
+    /// Article id → the model's reason for picking it (only for AI picks that came with one)
+    pub reasons: HashMap<String, String>,
+    pub outcome: RecommendationOutcome,
+    pub attempts: u32,
+    pub error: Option<String>,
+}
+
 impl AIService {
     pub async fn update_user_persona(
         feedback_history: &[Feedback],
         current_persona: &UserPersona,
-        api_key: &str,
-        client: &reqwest::Client,
+        provider: &dyn LlmProvider,
     ) -> Result<UserPersona, String> {
         if feedback_history.is_empty() {
             return Ok(current_persona.clone());
@@ -41,36 +55,24 @@ impl AIService {
         prompt.push_str("3. Update the description to be specific (e.g., 'User prefers Rust async and Tauri architecture, but dislikes general finance news').\n");
         prompt.push_str("4. Output ONLY the concise description text (2-3 sentences).");
 
-        let res = client
-            .post(format!("{}?key={}", GEMINI_API_URL, api_key))
-            .json(&serde_json::json!({
-                "contents": [{
-                    "parts": [{ "text": prompt }]
-                }]
-            }))
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
-
-        if let Ok(json) = res.json::<serde_json::Value>().await {
-            if let Some(text) = json["candidates"][0]["content"]["parts"][0]["text"].as_str() {
-                return Ok(UserPersona {
-                    description: text.trim().to_string(),
-                    last_updated: chrono::Local::now().to_rfc3339(),
-                });
-            }
+        let text = provider.generate(&prompt).await?;
+        let description = text.trim();
+        if description.is_empty() {
+            return Err("Failed to generate persona".to_string());
         }
 
-        Err("Failed to generate persona".to_string())
+        Ok(UserPersona {
+            description: description.to_string(),
+            last_updated: chrono::Local::now().to_rfc3339(),
+        })
     }
 
-    pub async fn recommend_with_gemini(
+    pub async fn recommend_with_llm(
         candidates: Vec<Article>,
         persona: &UserPersona,
         user_interests: &[ArticleCategory],
-        api_key: String,
-        client: &reqwest::Client,
-    ) -> Vec<Article> {
+        provider: &dyn LlmProvider,
+    ) -> AiSelection {
         // 1. Construct Prompt
         let mut prompt = String::from("You are a tech article recommender. Select the best 4 articles from the CANDIDATES list.\n\n");
 
@@ -100,40 +102,263 @@ impl AIService {
             .collect();
         prompt.push_str(&serde_json::to_string(&simple_candidates).unwrap_or_default());
 
-        prompt
-            .push_str("\n\nRespond ONLY with a JSON array of the IDs of the 4 selected articles.");
-
-        // 2. Call Gemini API
-        let res = client
-            .post(format!("{}?key={}", GEMINI_API_URL, api_key))
-            .json(&serde_json::json!({
-                "contents": [{
-                    "parts": [{ "text": prompt }]
-                }]
-            }))
-            .send()
-            .await;
-
-        // 3. Parse Response
-        if let Ok(response) = res {
-            if let Ok(json) = response.json::<serde_json::Value>().await {
-                if let Some(text) = json["candidates"][0]["content"]["parts"][0]["text"].as_str() {
-                    let clean_text = text
-                        .replace("```json", "")
-                        .replace("```", "")
-                        .trim()
-                        .to_string();
-                    if let Ok(selected_ids) = serde_json::from_str::<Vec<String>>(&clean_text) {
-                        return candidates
-                            .into_iter()
-                            .filter(|a| selected_ids.contains(&a.id))
-                            .collect();
-                    }
+        prompt.push_str(&format!(
+            "\n\nRespond ONLY with a JSON array of the {} selected articles, best first, as \
+             [{{\"id\": \"<article id>\", \"reason\": \"<one short sentence on why it fits the user>\"}}].",
+            AI_PICK_COUNT
+        ));
+
+        // 2. Call the configured LLM, asking once more if the answer can't be used
+        let mut attempts = 0;
+        let mut request = prompt.clone();
+        let (outcome, error) = loop {
+            attempts += 1;
+            let text = match provider.generate(&request).await {
+                Ok(text) => text,
+                Err(e) => break (RecommendationOutcome::Error, e),
+            };
+
+            // 3. Parse Response
+            match parse_selection(&text, &candidates) {
+                Ok(picks) => {
+                    let mut by_id: HashMap<String, Article> =
+                        candidates.into_iter().map(|a| (a.id.clone(), a)).collect();
+                    // Keep the model's ranking
+                    let articles = picks.iter().filter_map(|p| by_id.remove(&p.id)).collect();
+                    let reasons = picks
+                        .into_iter()
+                        .filter_map(|p| Some((p.id, p.reason?)))
+                        .collect();
+                    return AiSelection {
+                        articles,
+                        reasons,
+                        outcome: RecommendationOutcome::Ai,
+                        attempts,
+                        error: None,
+                    };
                 }
+                Err(e) if attempts < 2 => request = corrective_prompt(&prompt, &text, &e),
+                Err(e) => break (RecommendationOutcome::Fallback, e),
             }
-        }
+        };
+
+        eprintln!(
+            "{} recommendation failed after {} attempt(s): {}",
+            provider.name(),
+            attempts,
+            error
+        );
 
         // Fallback or if AI fails
-        candidates.into_iter().take(4).collect()
+        AiSelection {
+            articles: candidates.into_iter().take(AI_PICK_COUNT).collect(),
+            reasons: HashMap::new(),
+            outcome,
+            attempts,
+            error: Some(error),
+        }
+    }
+}
+
+fn corrective_prompt(prompt: &str, previous: &str, problem: &str) -> String {
+    let previous: String = previous.chars().take(500).collect();
+    format!(
+        "{}\n\nYOUR PREVIOUS RESPONSE:\n{}\n\nThat response was rejected: {}.\n\
+         Respond ONLY with a JSON array of {} objects whose ids are copied exactly from the CANDIDATES list, \
+         e.g. [{{\"id\": \"id-1\", \"reason\": \"...\"}}]. No prose, no markdown.",
+        prompt, previous, problem, AI_PICK_COUNT
+    )
+}
+
+/// Finds the first JSON array or object embedded in `text` (prose, markdown fences and
+/// trailing text around it are ignored).
+pub fn extract_json(text: &str) -> Option<serde_json::Value> {
+    text.char_indices()
+        .filter(|(_, c)| *c == '[' || *c == '{')
+        .find_map(|(i, _)| {
+            serde_json::Deserializer::from_str(&text[i..])
+                .into_iter::<serde_json::Value>()
+                .next()
+                .and_then(|r| r.ok())
+        })
+}
+
+/// One article chosen by the model.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AiPick {
+    pub id: String,
+    pub reason: Option<String>,
+}
+
+/// Extracts the selected articles from a model reply and checks their IDs against `candidates`.
+/// Accepts a bare array (`["a", "b"]`, `[{"id": "a", "reason": "..."}]`) or an object wrapping one
+/// (`{"ids": [...]}`, `{"articles": [...]}`, ...). Returns at most `AI_PICK_COUNT` picks in the model's order.
+pub fn parse_selection(text: &str, candidates: &[Article]) -> Result<Vec<AiPick>, String> {
+    let json = extract_json(text).ok_or("no JSON array or object found")?;
+
+    let items = match &json {
+        serde_json::Value::Array(items) => items,
+        serde_json::Value::Object(map) => {
+            const KEYS: &[&str] = &[
+                "ids",
+                "selected_ids",
+                "selected",
+                "articles",
+                "recommendations",
+            ];
+            let arrays: Vec<&Vec<serde_json::Value>> =
+                map.values().filter_map(|v| v.as_array()).collect();
+            KEYS.iter()
+                .find_map(|k| map.get(*k).and_then(|v| v.as_array()))
+                .or(if arrays.len() == 1 {
+                    Some(arrays[0])
+                } else {
+                    None
+                })
+                .ok_or("JSON object does not contain an array of IDs")?
+        }
+        _ => unreachable!("extract_json only returns arrays and objects"),
+    };
+
+    let known: HashSet<&str> = candidates.iter().map(|a| a.id.as_str()).collect();
+    let mut picks: Vec<AiPick> = Vec::new();
+    let mut unknown = Vec::new();
+    for item in items {
+        let (id, reason) = match item {
+            serde_json::Value::String(s) => (s.trim().to_string(), None),
+            serde_json::Value::Number(n) => (n.to_string(), None),
+            serde_json::Value::Object(o) => {
+                let id = match o.get("id") {
+                    Some(serde_json::Value::String(s)) => s.trim().to_string(),
+                    Some(serde_json::Value::Number(n)) => n.to_string(),
+                    _ => return Err("array entry has no \"id\"".to_string()),
+                };
+                let reason = o
+                    .get("reason")
+                    .and_then(|r| r.as_str())
+                    .map(|r| r.trim().to_string())
+                    .filter(|r| !r.is_empty());
+                (id, reason)
+            }
+            other => return Err(format!("unexpected array entry: {}", other)),
+        };
+        if !known.contains(id.as_str()) {
+            unknown.push(id);
+        } else if !picks.iter().any(|p| p.id == id) {
+            picks.push(AiPick { id, reason });
+        }
+    }
+
+    if !unknown.is_empty() {
+        return Err(format!(
+            "IDs not in the candidate list: {}",
+            unknown.join(", ")
+        ));
+    }
+    if picks.is_empty() {
+        return Err("no articles selected".to_string());
+    }
+
+    picks.truncate(AI_PICK_COUNT);
+    Ok(picks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::recommendation::model::ArticleState;
+
+    fn candidates(ids: &[&str]) -> Vec<Article> {
+        ids.iter()
+            .map(|id| Article {
+                id: id.to_string(),
+                title: String::new(),
+                summary: String::new(),
+                url: String::new(),
+                tags: vec![],
+                published_at: String::new(),
+                feedback: None,
+                image_url: None,
+                author: None,
+                state: ArticleState::Unread,
+                server_rating: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_extract_json() {
+        let cases: &[(&str, Option<serde_json::Value>)] = &[
+            (r#"["a","b"]"#, Some(serde_json::json!(["a", "b"]))),
+            ("```json\n[\"a\"]\n```", Some(serde_json::json!(["a"]))),
+            (
+                "Sure! Here are my picks: {\"ids\": [\"a\"]} Hope that helps [1].",
+                Some(serde_json::json!({ "ids": ["a"] })),
+            ),
+            // A bracket in prose that isn't JSON is skipped
+            (
+                "[Note] best picks: [\"a\", \"b\"]",
+                Some(serde_json::json!(["a", "b"])),
+            ),
+            ("I could not decide.", None),
+            ("[\"a\", ", None),
+        ];
+
+        for (text, expected) in cases {
+            assert_eq!(&extract_json(text), expected, "input: {}", text);
+        }
+    }
+
+    #[test]
+    fn test_parse_selection() {
+        let pool = candidates(&["a", "b", "c", "d", "e", "42"]);
+        let ok = |ids: &[&str]| {
+            Ok(ids
+                .iter()
+                .map(|id| AiPick {
+                    id: id.to_string(),
+                    reason: None,
+                })
+                .collect::<Vec<_>>())
+        };
+
+        assert_eq!(parse_selection(r#"["c", "a"]"#, &pool), ok(&["c", "a"]));
+        assert_eq!(
+            parse_selection(r#"{"selected": [{"id": "b"}, {"id": "d"}]}"#, &pool),
+            ok(&["b", "d"])
+        );
+        assert_eq!(
+            parse_selection(
+                r#"[{"id": "e", "reason": " Deep dive on async. "}, {"id": "a", "reason": ""}]"#,
+                &pool
+            ),
+            Ok(vec![
+                AiPick {
+                    id: "e".into(),
+                    reason: Some("Deep dive on async.".into()),
+                },
+                AiPick {
+                    id: "a".into(),
+                    reason: None,
+                },
+            ])
+        );
+        // Single array under an unexpected key, numeric IDs
+        assert_eq!(
+            parse_selection(r#"{"picks": [42, "a"], "reason": "x"}"#, &pool),
+            ok(&["42", "a"])
+        );
+        // Duplicates dropped, capped at AI_PICK_COUNT
+        assert_eq!(
+            parse_selection(r#"["a", "a", "b", "c", "d", "e"]"#, &pool),
+            ok(&["a", "b", "c", "d"])
+        );
+
+        let err = parse_selection(r#"["a", "zzz"]"#, &pool).unwrap_err();
+        assert!(err.contains("zzz"), "{}", err);
+        assert!(parse_selection("[]", &pool).is_err());
+        assert!(parse_selection("nothing here", &pool).is_err());
+        assert!(parse_selection(r#"{"a": [1], "b": [2]}"#, &pool).is_err());
+        assert!(parse_selection("[true]", &pool).is_err());
     }
 }
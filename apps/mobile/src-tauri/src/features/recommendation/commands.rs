@@ -1,42 +1,79 @@
 use crate::error::AppError;
-use crate::features::recommendation::ai::AIService;
-use crate::features::recommendation::config::FEEDS;
-use crate::features::recommendation::model::{Article, ArticleCategory};
-use crate::features::recommendation::scoring::calculate_relevance_score;
+use crate::features::recommendation::ai::{AIService, AiSelection, AI_PICK_COUNT};
+use crate::features::recommendation::model::{
+    Article, ArticleCategory, ArticlePage, ArticleState, Feed, FeedState, KeywordWeight,
+    Recommendation, RecommendationBatch, RecommendationOutcome, RecommendationSource,
+    RecommendationStats, ScoreBreakdown, SearchFilters, SearchResult,
+};
+use crate::features::recommendation::opml::{export_opml, parse_opml};
+use crate::features::recommendation::scoring::{
+    calculate_relevance_score, feedback_delta, feedback_terms,
+};
 use crate::features::recommendation::service::fetch_feed;
 use crate::features::recommendation::system::RecommendationState;
+use crate::features::sync::model::OutboxOperation;
+use crate::features::sync::service::SyncState;
+use std::collections::HashMap;
 use tauri::State;
 
 #[tauri::command]
 pub async fn fetch_articles(state: State<'_, RecommendationState>) -> Result<usize, AppError> {
     let mut all_fetched = Vec::new();
 
+    // Previous ETag/Last-Modified per feed, so unchanged feeds answer with 304
+    let mut feed_states: HashMap<String, FeedState> = state
+        .repo
+        .get_feed_states()?
+        .into_iter()
+        .map(|s| (s.url.clone(), s))
+        .collect();
+
     // Optimization: Reuse client and fetch concurrently
     let mut handles = Vec::new();
 
-    for (url, category) in FEEDS.iter() {
+    for feed in state.repo.get_enabled_feeds()? {
         let client = state.client.clone();
-        let url = url.to_string();
-        let category = category.clone();
-        handles.push(tauri::async_runtime::spawn(async move {
-            fetch_feed(&url, category, &client).await
-        }));
+        let url = feed.url;
+        let category = feed.category;
+        let cached = feed_states.get(&url).cloned();
+        handles.push((
+            url.clone(),
+            tauri::async_runtime::spawn(async move {
+                fetch_feed(&url, category, &client, cached.as_ref()).await
+            }),
+        ));
     }
 
-    for handle in handles {
+    for (url, handle) in handles {
+        let timestamp = chrono::Local::now().to_rfc3339();
+        let feed_state = feed_states
+            .entry(url.clone())
+            .or_insert_with(|| FeedState::new(&url));
+
         match handle.await {
-            Ok(Ok(fetched)) => {
-                all_fetched.extend(fetched);
+            Ok(Ok(response)) => {
+                feed_state.record_success(
+                    response.status,
+                    response.etag,
+                    response.last_modified,
+                    &timestamp,
+                );
+                all_fetched.extend(response.articles);
             }
             Ok(Err(e)) => {
-                eprintln!("Error fetching feed: {}", e);
+                eprintln!("Error fetching feed {}: {}", url, e);
+                feed_state.record_failure(e.status, &e.message, &timestamp);
             }
             Err(e) => {
                 eprintln!("Task failed to complete: {}", e);
+                feed_state.record_failure(None, &e.to_string(), &timestamp);
             }
         }
     }
 
+    let feed_states: Vec<FeedState> = feed_states.into_values().collect();
+    state.repo.save_feed_states(&feed_states)?;
+
     // Deduplication & Merge Logic
     // Optimized: Use batch upsert in a single transaction to reduce database overhead.
     let new_count = state.repo.upsert_articles(all_fetched)?;
@@ -44,122 +81,243 @@ pub async fn fetch_articles(state: State<'_, RecommendationState>) -> Result<usi
     Ok(new_count)
 }
 
+/// Fetch health of every feed (last status, error and failure streak)
+#[tauri::command]
+pub async fn get_feed_states(
+    state: State<'_, RecommendationState>,
+) -> Result<Vec<FeedState>, AppError> {
+    state.repo.get_feed_states()
+}
+
 #[tauri::command]
 pub async fn get_recommended_articles(
     state: State<'_, RecommendationState>,
     app: tauri::AppHandle,
-) -> Result<Vec<Article>, AppError> {
+) -> Result<Vec<Recommendation>, AppError> {
     // DB Access
     let articles = state.repo.get_articles()?;
 
     // 1. Calculate Scores & Sort
     let prefs = state.load_preferences(&app);
-    let mut scored_articles: Vec<(i32, Article)> = articles
+    let weights = state
+        .repo
+        .get_keyword_weights(&chrono::Local::now().to_rfc3339())?;
+    let mut scored_articles: Vec<(ScoreBreakdown, Article)> = articles
         .into_iter()
-        .map(|a| (calculate_relevance_score(&a, &prefs.interested_tags), a))
-        .filter(|(score, _)| *score > -10)
+        .map(|a| {
+            (
+                calculate_relevance_score(&a, &prefs.interested_tags, &weights),
+                a,
+            )
+        })
+        .filter(|(score, _)| score.total > -10)
         .collect();
 
     // Sort by Score DESC, then Date DESC
     scored_articles.sort_by(|(score_a, article_a), (score_b, article_b)| {
         score_b
-            .cmp(score_a)
+            .total
+            .cmp(&score_a.total)
             .then_with(|| article_b.published_at.cmp(&article_a.published_at))
     });
 
-    let top_candidates: Vec<Article> = scored_articles.into_iter().map(|(_, a)| a).collect();
-
     // 2. Rule-based: Top 3 (Highest Scored + Newest)
-    let top_3: Vec<Article> = top_candidates.iter().take(3).cloned().collect();
-    let remaining: Vec<Article> = top_candidates.iter().skip(3).cloned().collect();
+    let mut scored_articles = scored_articles.into_iter();
+    let top_3: Vec<Recommendation> = scored_articles
+        .by_ref()
+        .take(3)
+        .map(|(score, article)| Recommendation {
+            article,
+            score,
+            source: RecommendationSource::RuleBased,
+            ai_reason: None,
+        })
+        .collect();
+    let mut scores: HashMap<String, ScoreBreakdown> = HashMap::new();
+    let remaining: Vec<Article> = scored_articles
+        .map(|(score, article)| {
+            scores.insert(article.id.clone(), score);
+            article
+        })
+        .collect();
 
     // 3. AI-based: Next 4 from remaining
-    let api_key = std::env::var("GEMINI_API_KEY").unwrap_or_default();
-
-    let ai_picks = if !api_key.is_empty() && !remaining.is_empty() {
-        let candidates_for_ai: Vec<Article> = remaining.into_iter().take(20).collect();
-        let persona = state.persona.lock().unwrap().clone();
-
-        AIService::recommend_with_gemini(
-            candidates_for_ai,
-            &persona,
-            &prefs.interested_tags,
-            api_key,
-            &state.client,
-        )
-        .await
-    } else {
-        remaining.into_iter().take(4).collect()
+    let selection = match &state.llm {
+        Some(provider) if !remaining.is_empty() => {
+            let candidates_for_ai: Vec<Article> = remaining.into_iter().take(20).collect();
+            let persona = state.persona.lock().unwrap().clone();
+
+            AIService::recommend_with_llm(
+                candidates_for_ai,
+                &persona,
+                &prefs.interested_tags,
+                provider.as_ref(),
+            )
+            .await
+        }
+        _ => AiSelection {
+            articles: remaining.into_iter().take(AI_PICK_COUNT).collect(),
+            reasons: HashMap::new(),
+            outcome: RecommendationOutcome::Fallback,
+            attempts: 0,
+            error: state
+                .llm
+                .is_none()
+                .then(|| "No LLM provider configured".to_string()),
+        },
     };
 
     // 4. Combine
+    let source = if selection.outcome == RecommendationOutcome::Ai {
+        RecommendationSource::Ai
+    } else {
+        RecommendationSource::Fallback
+    };
+    let mut reasons = selection.reasons;
     let mut result = top_3;
-    result.extend(ai_picks);
+    result.extend(
+        selection
+            .articles
+            .into_iter()
+            .map(|article| Recommendation {
+                score: scores.remove(&article.id).unwrap_or_default(),
+                ai_reason: reasons.remove(&article.id),
+                article,
+                source,
+            }),
+    );
+
+    // Record how the batch was produced
+    let batch = RecommendationBatch {
+        id: 0,
+        created_at: chrono::Local::now().to_rfc3339(),
+        outcome: selection.outcome,
+        provider: state.llm.as_ref().map(|p| p.name().to_string()),
+        model: state.llm.as_ref().map(|p| p.model().to_string()),
+        attempts: selection.attempts,
+        article_ids: result.iter().map(|r| r.article.id.clone()).collect(),
+        error: selection.error,
+    };
+    state.repo.save_recommendation_batch(&batch)?;
 
     Ok(result)
 }
 
+/// Recent recommendation batches with their AI outcome, newest first
+#[tauri::command]
+pub async fn get_recommendation_batches(
+    limit: Option<u32>,
+    state: State<'_, RecommendationState>,
+) -> Result<Vec<RecommendationBatch>, AppError> {
+    state
+        .repo
+        .get_recommendation_batches(limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE))
+}
+
+/// How often the AI path produced the recommendations
+#[tauri::command]
+pub async fn get_recommendation_stats(
+    state: State<'_, RecommendationState>,
+) -> Result<RecommendationStats, AppError> {
+    state.repo.get_recommendation_stats()
+}
+
+/// Stores a rating, learns keyword weights from it and queues it for the Brain Server.
+/// A failed persona refresh is returned as an error; the rating itself is already saved.
 #[tauri::command]
 pub async fn submit_feedback(
     id: String,
     helpful: bool,
     reason: String,
     state: State<'_, RecommendationState>,
+    sync_state: State<'_, SyncState>,
     app: tauri::AppHandle,
 ) -> Result<(), AppError> {
     let timestamp = chrono::Local::now().to_rfc3339();
+    let article = state.repo.get_article(&id)?;
     state
         .repo
         .update_feedback(&id, helpful, &reason, &timestamp)?;
 
-    // Sync feedback to Brain Server if article was synced
-    if let Ok(Some(server_article_id)) = state.repo.get_server_article_id(&id) {
-        let sync_service = crate::features::sync::service::SyncService::new(state.client.clone());
-        let reason_opt = if reason.is_empty() {
-            None
-        } else {
-            Some(reason.as_str())
-        };
-        if let Err(e) = sync_service
-            .submit_feedback(&server_article_id, helpful, reason_opt)
-            .await
-        {
-            eprintln!(
-                "Failed to sync feedback to server for article {}: {}",
-                server_article_id, e
-            );
+    // Learn from the rating: terms in helpful articles gain weight, the rest lose it
+    if let Some(article) = article {
+        let previous = article.feedback.as_ref().map(|f| f.is_helpful);
+        let delta = feedback_delta(previous, helpful);
+        if delta != 0.0 {
+            let known: Vec<String> = state
+                .repo
+                .get_keyword_weights(&timestamp)?
+                .into_iter()
+                .map(|w| w.term)
+                .collect();
+            let terms = feedback_terms(&article, &known);
+            state
+                .repo
+                .apply_keyword_feedback(&terms, delta, &timestamp)?;
         }
     }
 
-    let api_key = std::env::var("GEMINI_API_KEY").unwrap_or_default();
-    if !api_key.is_empty() {
+    // Queue the rating for the Brain Server if the article is (or is about to be) there
+    let create = OutboxOperation::CreateArticle {
+        article_id: id.clone(),
+    };
+    if state.repo.get_server_article_id(&id)?.is_some() || sync_state.outbox.has_pending(&create)? {
+        sync_state.enqueue(&OutboxOperation::SubmitFeedback {
+            article_id: id.clone(),
+            helpful,
+            reason: if reason.is_empty() {
+                None
+            } else {
+                Some(reason.clone())
+            },
+        })?;
+        sync_state.spawn_drain(state.repo.clone(), sync_state.service(state.client.clone()));
+    }
+
+    if let Some(provider) = &state.llm {
         let count = state.repo.get_feedback_count()?;
 
+        // Every third rating refreshes the persona
         if count > 0 && count % 3 == 0 {
-            println!("Triggering Persona Update (Feedback Count: {})", count);
             let all_feedback = state.repo.get_feedback()?;
             let current_persona = state.persona.lock().unwrap().clone();
 
-            let persona_update_result = AIService::update_user_persona(
-                &all_feedback,
-                &current_persona,
-                &api_key,
-                &state.client,
-            )
-            .await;
-
-            if let Ok(new_persona) = persona_update_result {
-                *state.persona.lock().unwrap() = new_persona;
-                state.save_persona(&app);
-            } else if let Err(e) = persona_update_result {
-                eprintln!("Failed to update user persona: {}", e);
-            }
+            let new_persona =
+                AIService::update_user_persona(&all_feedback, &current_persona, provider.as_ref())
+                    .await
+                    .map_err(|e| {
+                        AppError::Unknown(format!("Failed to update user persona: {}", e))
+                    })?;
+            *state.persona.lock().unwrap() = new_persona;
+            state.save_persona(&app);
         }
     }
 
     Ok(())
 }
 
+/// Scoring keywords with their current (decayed) weights, strongest first
+#[tauri::command]
+pub async fn get_keyword_weights(
+    state: State<'_, RecommendationState>,
+) -> Result<Vec<KeywordWeight>, AppError> {
+    state
+        .repo
+        .get_keyword_weights(&chrono::Local::now().to_rfc3339())
+}
+
+/// Forgets learned weights for one term, or for all terms when `term` is omitted
+#[tauri::command]
+pub async fn reset_keyword_weights(
+    term: Option<String>,
+    state: State<'_, RecommendationState>,
+) -> Result<Vec<KeywordWeight>, AppError> {
+    state.repo.reset_keyword_weights(term.as_deref())?;
+    state
+        .repo
+        .get_keyword_weights(&chrono::Local::now().to_rfc3339())
+}
+
 #[tauri::command]
 pub async fn save_user_interests(
     categories: Vec<ArticleCategory>,
@@ -180,3 +338,206 @@ pub async fn get_user_interests(
     let prefs = state.load_preferences(&app);
     Ok(prefs.interested_tags)
 }
+
+#[tauri::command]
+pub async fn get_feeds(state: State<'_, RecommendationState>) -> Result<Vec<Feed>, AppError> {
+    state.repo.get_feeds()
+}
+
+#[tauri::command]
+pub async fn add_feed(
+    url: String,
+    title: Option<String>,
+    category: ArticleCategory,
+    state: State<'_, RecommendationState>,
+) -> Result<Vec<Feed>, AppError> {
+    state
+        .repo
+        .add_feed(url.trim(), title.as_deref(), &category)?;
+    state.repo.get_feeds()
+}
+
+#[tauri::command]
+pub async fn remove_feed(
+    id: i64,
+    state: State<'_, RecommendationState>,
+) -> Result<Vec<Feed>, AppError> {
+    state.repo.remove_feed(id)?;
+    state.repo.get_feeds()
+}
+
+#[tauri::command]
+pub async fn set_feed_enabled(
+    id: i64,
+    enabled: bool,
+    state: State<'_, RecommendationState>,
+) -> Result<Vec<Feed>, AppError> {
+    state.repo.set_feed_enabled(id, enabled)?;
+    state.repo.get_feeds()
+}
+
+#[tauri::command]
+pub async fn rename_feed(
+    id: i64,
+    title: Option<String>,
+    state: State<'_, RecommendationState>,
+) -> Result<Vec<Feed>, AppError> {
+    // An empty title falls back to showing the URL
+    let title = title.filter(|t| !t.trim().is_empty());
+    state.repo.rename_feed(id, title.as_deref())?;
+    state.repo.get_feeds()
+}
+
+#[tauri::command]
+pub async fn set_feed_category(
+    id: i64,
+    category: ArticleCategory,
+    state: State<'_, RecommendationState>,
+) -> Result<Vec<Feed>, AppError> {
+    state.repo.set_feed_category(id, &category)?;
+    state.repo.get_feeds()
+}
+
+/// Adds every feed from an OPML document; already-subscribed or invalid URLs are skipped.
+/// Feeds `export_opml` marked as disabled are added disabled.
+/// Returns the number of newly added subscriptions.
+#[tauri::command]
+pub async fn import_opml(
+    content: String,
+    state: State<'_, RecommendationState>,
+) -> Result<usize, AppError> {
+    let feeds = parse_opml(&content).map_err(AppError::Validation)?;
+
+    let mut added = 0;
+    for feed in feeds {
+        match state
+            .repo
+            .add_feed(&feed.url, feed.title.as_deref(), &feed.category)
+        {
+            Ok(stored) => {
+                if !feed.enabled {
+                    state.repo.set_feed_enabled(stored.id, false)?;
+                }
+                added += 1;
+            }
+            Err(AppError::Validation(msg)) => eprintln!("Skipping OPML outline: {}", msg),
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(added)
+}
+
+#[tauri::command]
+pub async fn export_opml_document(
+    state: State<'_, RecommendationState>,
+) -> Result<String, AppError> {
+    let feeds = state.repo.get_feeds()?;
+    Ok(export_opml(&feeds))
+}
+
+/// Ranked full-text search over title, summary and author, across all stored articles.
+#[tauri::command]
+pub async fn search_articles(
+    query: String,
+    filters: Option<SearchFilters>,
+    state: State<'_, RecommendationState>,
+) -> Result<Vec<SearchResult>, AppError> {
+    state
+        .repo
+        .search_articles(&query, &filters.unwrap_or_default())
+}
+
+const DEFAULT_PAGE_SIZE: u32 = 20;
+const MAX_PAGE_SIZE: u32 = 100;
+
+fn change_article_state(
+    state: &RecommendationState,
+    id: &str,
+    article_state: ArticleState,
+) -> Result<(), AppError> {
+    let timestamp = chrono::Local::now().to_rfc3339();
+    state.repo.set_article_state(id, article_state, &timestamp)
+}
+
+fn list_articles(
+    state: &RecommendationState,
+    article_state: ArticleState,
+    limit: Option<u32>,
+    offset: Option<u32>,
+) -> Result<ArticlePage, AppError> {
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+    state
+        .repo
+        .get_articles_by_state(article_state, limit, offset.unwrap_or(0))
+}
+
+#[tauri::command]
+pub async fn mark_article_read(
+    id: String,
+    state: State<'_, RecommendationState>,
+) -> Result<(), AppError> {
+    change_article_state(&state, &id, ArticleState::Read)
+}
+
+#[tauri::command]
+pub async fn mark_article_unread(
+    id: String,
+    state: State<'_, RecommendationState>,
+) -> Result<(), AppError> {
+    change_article_state(&state, &id, ArticleState::Unread)
+}
+
+/// Bookmark an article for later reading
+#[tauri::command]
+pub async fn save_article_for_later(
+    id: String,
+    state: State<'_, RecommendationState>,
+) -> Result<(), AppError> {
+    change_article_state(&state, &id, ArticleState::Saved)
+}
+
+#[tauri::command]
+pub async fn archive_article(
+    id: String,
+    state: State<'_, RecommendationState>,
+) -> Result<(), AppError> {
+    change_article_state(&state, &id, ArticleState::Archived)
+}
+
+#[tauri::command]
+pub async fn get_unread_articles(
+    limit: Option<u32>,
+    offset: Option<u32>,
+    state: State<'_, RecommendationState>,
+) -> Result<ArticlePage, AppError> {
+    list_articles(&state, ArticleState::Unread, limit, offset)
+}
+
+/// Reading history, including rated articles
+#[tauri::command]
+pub async fn get_read_articles(
+    limit: Option<u32>,
+    offset: Option<u32>,
+    state: State<'_, RecommendationState>,
+) -> Result<ArticlePage, AppError> {
+    list_articles(&state, ArticleState::Read, limit, offset)
+}
+
+#[tauri::command]
+pub async fn get_saved_articles(
+    limit: Option<u32>,
+    offset: Option<u32>,
+    state: State<'_, RecommendationState>,
+) -> Result<ArticlePage, AppError> {
+    list_articles(&state, ArticleState::Saved, limit, offset)
+}
+
+#[tauri::command]
+pub async fn get_archived_articles(
+    limit: Option<u32>,
+    offset: Option<u32>,
+    state: State<'_, RecommendationState>,
+) -> Result<ArticlePage, AppError> {
+    list_articles(&state, ArticleState::Archived, limit, offset)
+}
@@ -0,0 +1,430 @@
+use super::config::{
+    DEFAULT_GEMINI_MODEL, DEFAULT_OLLAMA_MODEL, DEFAULT_OLLAMA_URL, DEFAULT_OPENAI_MODEL,
+    DEFAULT_OPENAI_URL, GEMINI_API_URL,
+};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// A text-completion backend used by `AIService`.
+/// Implementations turn a single prompt into the model's raw text reply.
+#[async_trait]
+pub trait LlmProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn model(&self) -> &str;
+    async fn generate(&self, prompt: &str) -> Result<String, String>;
+}
+
+/// Google Gemini `generateContent` API.
+/// The key goes in the `x-goog-api-key` header, never the URL, so it can't end up in error messages.
+pub struct GeminiProvider {
+    client: reqwest::Client,
+    base_url: String,
+    model: String,
+    api_key: String,
+}
+
+impl GeminiProvider {
+    pub fn new(client: reqwest::Client, base_url: &str, model: &str, api_key: &str) -> Self {
+        Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model: model.to_string(),
+            api_key: api_key.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for GeminiProvider {
+    fn name(&self) -> &'static str {
+        "gemini"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn generate(&self, prompt: &str) -> Result<String, String> {
+        let url = format!("{}/models/{}:generateContent", self.base_url, self.model);
+        let json = post_json(
+            self.client
+                .post(url)
+                .header("x-goog-api-key", &self.api_key),
+            serde_json::json!({
+                "contents": [{
+                    "parts": [{ "text": prompt }]
+                }]
+            }),
+        )
+        .await?;
+
+        json["candidates"][0]["content"]["parts"][0]["text"]
+            .as_str()
+            .map(|s| s.to_string())
+            .ok_or_else(|| "Gemini response did not contain any text".to_string())
+    }
+}
+
+/// Any server speaking the OpenAI chat-completions protocol (OpenAI, OpenRouter, LM Studio, vLLM, ...).
+pub struct OpenAiCompatibleProvider {
+    client: reqwest::Client,
+    base_url: String,
+    model: String,
+    api_key: Option<String>,
+}
+
+impl OpenAiCompatibleProvider {
+    pub fn new(
+        client: reqwest::Client,
+        base_url: &str,
+        model: &str,
+        api_key: Option<&str>,
+    ) -> Self {
+        Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model: model.to_string(),
+            api_key: api_key.map(|k| k.to_string()),
+        }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for OpenAiCompatibleProvider {
+    fn name(&self) -> &'static str {
+        "openai"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn generate(&self, prompt: &str) -> Result<String, String> {
+        let mut request = self
+            .client
+            .post(format!("{}/chat/completions", self.base_url));
+        // Local servers usually don't need a key
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+        let json = post_json(
+            request,
+            serde_json::json!({
+                "model": self.model,
+                "messages": [{ "role": "user", "content": prompt }]
+            }),
+        )
+        .await?;
+
+        json["choices"][0]["message"]["content"]
+            .as_str()
+            .map(|s| s.to_string())
+            .ok_or_else(|| "Chat completion response did not contain any text".to_string())
+    }
+}
+
+/// A local Ollama server (`/api/generate`, non-streaming).
+pub struct OllamaProvider {
+    client: reqwest::Client,
+    base_url: String,
+    model: String,
+}
+
+impl OllamaProvider {
+    pub fn new(client: reqwest::Client, base_url: &str, model: &str) -> Self {
+        Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model: model.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for OllamaProvider {
+    fn name(&self) -> &'static str {
+        "ollama"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn generate(&self, prompt: &str) -> Result<String, String> {
+        let json = post_json(
+            self.client.post(format!("{}/api/generate", self.base_url)),
+            serde_json::json!({
+                "model": self.model,
+                "prompt": prompt,
+                "stream": false
+            }),
+        )
+        .await?;
+
+        json["response"]
+            .as_str()
+            .map(|s| s.to_string())
+            .ok_or_else(|| "Ollama response did not contain any text".to_string())
+    }
+}
+
+/// Errors leave out the request URL, which may carry credentials; they are stored and shown in the UI.
+async fn post_json(
+    request: reqwest::RequestBuilder,
+    body: serde_json::Value,
+) -> Result<serde_json::Value, String> {
+    let res = request
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| e.without_url().to_string())?;
+
+    let status = res.status();
+    if !status.is_success() {
+        let text = res.text().await.unwrap_or_default();
+        return Err(format!("LLM request failed with HTTP {}: {}", status, text));
+    }
+
+    res.json::<serde_json::Value>()
+        .await
+        .map_err(|e| e.without_url().to_string())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LlmProviderKind {
+    Gemini,
+    OpenAi,
+    Ollama,
+}
+
+impl std::str::FromStr for LlmProviderKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "gemini" => Ok(Self::Gemini),
+            "openai" | "openai-compatible" => Ok(Self::OpenAi),
+            "ollama" => Ok(Self::Ollama),
+            other => Err(format!("Unknown LLM provider: {}", other)),
+        }
+    }
+}
+
+/// Provider selection, read from the environment (`.env`).
+///
+/// - `LLM_PROVIDER`: `gemini` (default), `openai` or `ollama`
+/// - `LLM_API_KEY`: API key; `GEMINI_API_KEY` is still honoured for Gemini
+/// - `LLM_BASE_URL` / `LLM_MODEL`: override the provider's default endpoint and model
+#[derive(Debug, Clone, PartialEq)]
+pub struct LlmConfig {
+    pub kind: LlmProviderKind,
+    pub base_url: Option<String>,
+    pub model: Option<String>,
+    pub api_key: Option<String>,
+}
+
+impl LlmConfig {
+    pub fn from_env() -> Result<Self, String> {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self, String> {
+        let get = |key: &str| lookup(key).filter(|v| !v.trim().is_empty());
+
+        let kind = match get("LLM_PROVIDER") {
+            Some(value) => value.parse()?,
+            None => LlmProviderKind::Gemini,
+        };
+        let api_key = match kind {
+            LlmProviderKind::Gemini => get("LLM_API_KEY").or_else(|| get("GEMINI_API_KEY")),
+            _ => get("LLM_API_KEY"),
+        };
+
+        Ok(Self {
+            kind,
+            base_url: get("LLM_BASE_URL"),
+            model: get("LLM_MODEL"),
+            api_key,
+        })
+    }
+
+    /// Builds the configured provider, or `None` when it can't be used (e.g. Gemini without a key).
+    pub fn build(&self, client: reqwest::Client) -> Option<Arc<dyn LlmProvider>> {
+        let base_url = self.base_url.as_deref();
+        let model = self.model.as_deref();
+
+        match self.kind {
+            LlmProviderKind::Gemini => {
+                let api_key = self.api_key.as_deref()?;
+                Some(Arc::new(GeminiProvider::new(
+                    client,
+                    base_url.unwrap_or(GEMINI_API_URL),
+                    model.unwrap_or(DEFAULT_GEMINI_MODEL),
+                    api_key,
+                )))
+            }
+            LlmProviderKind::OpenAi => {
+                // The hosted API needs a key; custom endpoints may not
+                if self.api_key.is_none() && base_url.is_none() {
+                    return None;
+                }
+                Some(Arc::new(OpenAiCompatibleProvider::new(
+                    client,
+                    base_url.unwrap_or(DEFAULT_OPENAI_URL),
+                    model.unwrap_or(DEFAULT_OPENAI_MODEL),
+                    self.api_key.as_deref(),
+                )))
+            }
+            LlmProviderKind::Ollama => Some(Arc::new(OllamaProvider::new(
+                client,
+                base_url.unwrap_or(DEFAULT_OLLAMA_URL),
+                model.unwrap_or(DEFAULT_OLLAMA_MODEL),
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{MockResponse, MockServer};
+    use std::collections::HashMap;
+
+    fn config(vars: &[(&str, &str)]) -> Result<LlmConfig, String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        LlmConfig::from_lookup(|key| vars.get(key).cloned())
+    }
+
+    #[test]
+    fn test_gemini_provider() {
+        let server = MockServer::start(|_| {
+            MockResponse::json(
+                200,
+                r#"{"candidates":[{"content":{"parts":[{"text":"hello from gemini"}]}}]}"#,
+            )
+        });
+        let provider =
+            GeminiProvider::new(reqwest::Client::new(), &server.url, "gemini-test", "secret");
+
+        let text = tauri::async_runtime::block_on(provider.generate("Say hi")).unwrap();
+        assert_eq!(text, "hello from gemini");
+
+        let requests = server.requests();
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].path, "/models/gemini-test:generateContent");
+        assert_eq!(requests[0].header("x-goog-api-key"), Some("secret"));
+        assert_eq!(
+            requests[0].json()["contents"][0]["parts"][0]["text"],
+            "Say hi"
+        );
+    }
+
+    #[test]
+    fn test_openai_compatible_provider() {
+        let server = MockServer::start(|_| {
+            MockResponse::json(
+                200,
+                r#"{"choices":[{"message":{"role":"assistant","content":"hello from openai"}}]}"#,
+            )
+        });
+        let provider = OpenAiCompatibleProvider::new(
+            reqwest::Client::new(),
+            &format!("{}/v1/", server.url),
+            "gpt-test",
+            Some("sk-test"),
+        );
+
+        let text = tauri::async_runtime::block_on(provider.generate("Say hi")).unwrap();
+        assert_eq!(text, "hello from openai");
+
+        let request = &server.requests()[0];
+        assert_eq!(request.path, "/v1/chat/completions");
+        assert_eq!(request.header("Authorization"), Some("Bearer sk-test"));
+        let body = request.json();
+        assert_eq!(body["model"], "gpt-test");
+        assert_eq!(body["messages"][0]["role"], "user");
+        assert_eq!(body["messages"][0]["content"], "Say hi");
+    }
+
+    #[test]
+    fn test_ollama_provider() {
+        let server = MockServer::start(|_| {
+            MockResponse::json(
+                200,
+                r#"{"model":"llama-test","response":"hello from ollama","done":true}"#,
+            )
+        });
+        let provider = OllamaProvider::new(reqwest::Client::new(), &server.url, "llama-test");
+
+        let text = tauri::async_runtime::block_on(provider.generate("Say hi")).unwrap();
+        assert_eq!(text, "hello from ollama");
+
+        let request = &server.requests()[0];
+        assert_eq!(request.path, "/api/generate");
+        assert_eq!(request.header("Authorization"), None);
+        let body = request.json();
+        assert_eq!(body["model"], "llama-test");
+        assert_eq!(body["prompt"], "Say hi");
+        assert_eq!(body["stream"], false);
+    }
+
+    #[test]
+    fn test_provider_error_status() {
+        let server = MockServer::start(|_| MockResponse::new(429, "rate limited"));
+        let provider = OllamaProvider::new(reqwest::Client::new(), &server.url, "llama-test");
+
+        let err = tauri::async_runtime::block_on(provider.generate("Say hi")).unwrap_err();
+        assert!(err.contains("429"), "{}", err);
+        assert!(err.contains("rate limited"), "{}", err);
+    }
+
+    #[test]
+    fn test_provider_unexpected_shape() {
+        let server = MockServer::start(|_| MockResponse::json(200, r#"{"candidates":[]}"#));
+        let provider =
+            GeminiProvider::new(reqwest::Client::new(), &server.url, "gemini-test", "secret");
+
+        assert!(tauri::async_runtime::block_on(provider.generate("Say hi")).is_err());
+    }
+
+    #[test]
+    fn test_config_from_env() {
+        // Legacy setup: only a Gemini key
+        let cfg = config(&[("GEMINI_API_KEY", "g-key")]).unwrap();
+        assert_eq!(cfg.kind, LlmProviderKind::Gemini);
+        assert_eq!(cfg.api_key.as_deref(), Some("g-key"));
+        let provider = cfg.build(reqwest::Client::new()).unwrap();
+        assert_eq!(provider.name(), "gemini");
+        assert_eq!(provider.model(), DEFAULT_GEMINI_MODEL);
+
+        // Nothing configured: Gemini has no key, so there's no provider
+        assert!(config(&[]).unwrap().build(reqwest::Client::new()).is_none());
+
+        // The Gemini key isn't sent to other providers
+        let cfg = config(&[("LLM_PROVIDER", "OpenAI"), ("GEMINI_API_KEY", "g-key")]).unwrap();
+        assert_eq!(cfg.api_key, None);
+        assert!(cfg.build(reqwest::Client::new()).is_none());
+
+        let cfg = config(&[
+            ("LLM_PROVIDER", "openai"),
+            ("LLM_BASE_URL", "http://localhost:1234/v1"),
+            ("LLM_MODEL", "qwen"),
+        ])
+        .unwrap();
+        let provider = cfg.build(reqwest::Client::new()).unwrap();
+        assert_eq!(provider.name(), "openai");
+        assert_eq!(provider.model(), "qwen");
+
+        let provider = config(&[("LLM_PROVIDER", "ollama")])
+            .unwrap()
+            .build(reqwest::Client::new())
+            .unwrap();
+        assert_eq!(provider.name(), "ollama");
+        assert_eq!(provider.model(), DEFAULT_OLLAMA_MODEL);
+
+        assert!(config(&[("LLM_PROVIDER", "claude-local")]).is_err());
+    }
+}
@@ -1,7 +1,10 @@
 pub mod ai;
 pub mod commands;
 pub mod config;
+pub mod llm;
 pub mod model;
+pub mod opml;
+pub mod parser;
 pub mod repository;
 pub mod scoring;
 pub mod service;
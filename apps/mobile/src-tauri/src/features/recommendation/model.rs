@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::str::FromStr;
 
 #[derive(Serialize, Deserialize, Default, Clone, Debug)]
 pub struct UserPersona {
@@ -26,6 +27,26 @@ impl fmt::Display for ArticleCategory {
     }
 }
 
+impl FromStr for ArticleCategory {
+    type Err = String;
+
+    /// Case-insensitive inverse of `Display`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "rust" => Ok(ArticleCategory::Rust),
+            "tauri" => Ok(ArticleCategory::Tauri),
+            "react" => Ok(ArticleCategory::React),
+            "typescript" => Ok(ArticleCategory::TypeScript),
+            "android" => Ok(ArticleCategory::Android),
+            "kotlin" => Ok(ArticleCategory::Kotlin),
+            "web" => Ok(ArticleCategory::Web),
+            "ai" => Ok(ArticleCategory::AI),
+            "general" => Ok(ArticleCategory::General),
+            _ => Err(format!("Unknown category: {}", s)),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Feedback {
     pub is_helpful: bool,
@@ -33,6 +54,42 @@ pub struct Feedback {
     pub created_at: String,
 }
 
+/// Reading state of an article. Rating an unread article moves it to `Read`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ArticleState {
+    #[default]
+    Unread,
+    Read,
+    Saved,
+    Archived,
+}
+
+impl ArticleState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ArticleState::Unread => "unread",
+            ArticleState::Read => "read",
+            ArticleState::Saved => "saved",
+            ArticleState::Archived => "archived",
+        }
+    }
+}
+
+impl FromStr for ArticleState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "unread" => Ok(ArticleState::Unread),
+            "read" => Ok(ArticleState::Read),
+            "saved" => Ok(ArticleState::Saved),
+            "archived" => Ok(ArticleState::Archived),
+            _ => Err(format!("Unknown article state: {}", s)),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Article {
     pub id: String,
@@ -44,9 +101,233 @@ pub struct Article {
     pub feedback: Option<Feedback>,
     pub image_url: Option<String>,
     pub author: Option<String>,
+    #[serde(default)]
+    pub state: ArticleState,
+    /// Ratings from every device, as last pulled from the Brain Server
+    #[serde(default)]
+    pub server_rating: Option<ServerRating>,
+}
+
+/// Aggregate feedback for an article on the Brain Server.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ServerRating {
+    /// 0.0 to 2.0; 1.0 until someone rates the article
+    pub rating: f64,
+    pub positive_count: i64,
+    pub negative_count: i64,
+    /// Server-side `updatedAt` these values were read at
+    pub updated_at: String,
+}
+
+/// One page of a state listing (`get_saved_articles`, ...).
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ArticlePage {
+    pub articles: Vec<Article>,
+    pub total: i64,
+    pub offset: u32,
+    pub limit: u32,
+}
+
+/// Optional filters for `search_articles`. Unset fields don't restrict the search.
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct SearchFilters {
+    pub category: Option<ArticleCategory>,
+    /// `Some(true)` = rated helpful, `Some(false)` = rated not helpful
+    pub helpful: Option<bool>,
+    /// `Some(true)` = only rated articles, `Some(false)` = only unrated
+    pub rated: Option<bool>,
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SearchResult {
+    pub article: Article,
+    /// Title with matches wrapped in `<mark>` tags
+    pub title_highlight: String,
+    /// Short excerpt of the summary around the matches, also `<mark>`-highlighted
+    pub snippet: String,
+    /// BM25 score (lower is better)
+    pub rank: f64,
+}
+
+/// A scoring keyword from `keyword_weights`: the seeded weight plus what was learned from feedback.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct KeywordWeight {
+    pub term: String,
+    /// Effective weight used for scoring (`base_weight` + decayed `adjustment`)
+    pub weight: f64,
+    /// Weight from the built-in keyword lists; 0 for terms learned from feedback
+    pub base_weight: f64,
+    /// Learned adjustment as of `updated_at`, before decay
+    pub adjustment: f64,
+    pub feedback_count: u32,
+    pub updated_at: Option<String>,
+}
+
+/// Every contribution to an article's relevance score (see `scoring::calculate_relevance_score`).
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub struct ScoreBreakdown {
+    pub total: i32,
+    pub high_keywords: Vec<String>,
+    pub medium_keywords: Vec<String>,
+    pub negative_keywords: Vec<String>,
+    /// Sum of the keyword contributions above
+    pub keyword_score: i32,
+    /// Bonus for tags the user explicitly selected
+    pub interest_bonus: i32,
+    /// Bonus for tech categories, independent of the user's selection
+    pub category_bonus: i32,
+    /// Applied to rated articles so they drop out of recommendations
+    pub feedback_penalty: i32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RecommendationSource {
+    /// One of the top-scored articles
+    RuleBased,
+    /// Picked by the LLM
+    Ai,
+    /// Filled in by score order because the AI pick failed or no LLM is configured
+    Fallback,
+}
+
+/// An article returned by `get_recommended_articles`, with why it was picked.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Recommendation {
+    pub article: Article,
+    pub score: ScoreBreakdown,
+    pub source: RecommendationSource,
+    /// The model's one-line justification, when it gave one
+    pub ai_reason: Option<String>,
+}
+
+/// How the AI part of a recommendation batch was produced.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RecommendationOutcome {
+    /// The model returned a valid selection
+    Ai,
+    /// No usable answer (no provider configured, or invalid output after the retry); score order was used
+    Fallback,
+    /// The provider request itself failed; score order was used
+    Error,
+}
+
+impl RecommendationOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecommendationOutcome::Ai => "ai",
+            RecommendationOutcome::Fallback => "fallback",
+            RecommendationOutcome::Error => "error",
+        }
+    }
+}
+
+impl FromStr for RecommendationOutcome {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ai" => Ok(RecommendationOutcome::Ai),
+            "fallback" => Ok(RecommendationOutcome::Fallback),
+            "error" => Ok(RecommendationOutcome::Error),
+            _ => Err(format!("Unknown recommendation outcome: {}", s)),
+        }
+    }
+}
+
+/// One call to `get_recommended_articles`, persisted in `recommendation_batches`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RecommendationBatch {
+    pub id: i64,
+    pub created_at: String,
+    pub outcome: RecommendationOutcome,
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    /// LLM calls made for this batch (0 when no provider is configured)
+    pub attempts: u32,
+    pub article_ids: Vec<String>,
+    pub error: Option<String>,
+}
+
+/// Outcome counts over all recorded batches.
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub struct RecommendationStats {
+    pub total: i64,
+    pub ai: i64,
+    pub fallback: i64,
+    pub error: i64,
 }
 
 #[derive(Serialize, Deserialize, Default, Clone, Debug)]
 pub struct UserPreferences {
     pub interested_tags: Vec<ArticleCategory>,
 }
+
+/// A feed subscription from the `feeds` table.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Feed {
+    pub id: i64,
+    pub url: String,
+    pub title: Option<String>,
+    pub category: ArticleCategory,
+    pub enabled: bool,
+}
+
+/// Per-feed HTTP cache validators and fetch health, persisted in `feed_state`.
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub struct FeedState {
+    pub url: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub last_status: Option<u16>,
+    pub last_error: Option<String>,
+    pub last_fetched_at: Option<String>,
+    pub last_success_at: Option<String>,
+    pub consecutive_failures: u32,
+}
+
+impl FeedState {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// 2xx or 304. A full response replaces both validators with whatever it carried, even
+    /// nothing; a 304 only updates the ones the server sent again.
+    pub fn record_success(
+        &mut self,
+        status: u16,
+        etag: Option<String>,
+        last_modified: Option<String>,
+        timestamp: &str,
+    ) {
+        if status != 304 {
+            self.etag = etag;
+            self.last_modified = last_modified;
+        } else {
+            if etag.is_some() {
+                self.etag = etag;
+            }
+            if last_modified.is_some() {
+                self.last_modified = last_modified;
+            }
+        }
+        self.last_status = Some(status);
+        self.last_error = None;
+        self.last_fetched_at = Some(timestamp.to_string());
+        self.last_success_at = Some(timestamp.to_string());
+        self.consecutive_failures = 0;
+    }
+
+    pub fn record_failure(&mut self, status: Option<u16>, error: &str, timestamp: &str) {
+        self.last_status = status;
+        self.last_error = Some(error.to_string());
+        self.last_fetched_at = Some(timestamp.to_string());
+        self.consecutive_failures += 1;
+    }
+}
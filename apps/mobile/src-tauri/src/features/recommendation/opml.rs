@@ -0,0 +1,307 @@
+use super::model::{ArticleCategory, Feed};
+use quick_xml::encoding::Decoder;
+use quick_xml::escape::escape;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+/// A subscription read from an OPML document, ready to be added to the `feeds` table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpmlFeed {
+    pub url: String,
+    pub title: Option<String>,
+    pub category: ArticleCategory,
+    /// False for outlines marked `disabled="true"` by `export_opml`
+    pub enabled: bool,
+}
+
+/// Parses an OPML 1.0/2.0 subscription list.
+/// The category comes from the outline's `category` attribute, then from the enclosing folder outlines,
+/// and falls back to `General` when nothing maps to an `ArticleCategory`.
+pub fn parse_opml(content: &str) -> Result<Vec<OpmlFeed>, String> {
+    let mut reader = Reader::from_str(content);
+    reader.config_mut().trim_text(true);
+
+    let mut feeds = Vec::new();
+    // Titles of the folder outlines we're currently nested in (None for feed outlines that have children)
+    let mut folders: Vec<Option<String>> = Vec::new();
+    let mut seen_opml = false;
+
+    loop {
+        match reader.read_event().map_err(|e| e.to_string())? {
+            Event::Start(e) if e.local_name().as_ref() == b"opml" => seen_opml = true,
+            Event::Start(e) if e.local_name().as_ref() == b"outline" => {
+                let outline = read_outline(&e, reader.decoder())?;
+                let folder_title = if outline.xml_url.is_none() {
+                    outline.text.clone()
+                } else {
+                    None
+                };
+                if let Some(feed) = outline.into_feed(&folders) {
+                    feeds.push(feed);
+                }
+                folders.push(folder_title);
+            }
+            Event::Empty(e) if e.local_name().as_ref() == b"outline" => {
+                if let Some(feed) = read_outline(&e, reader.decoder())?.into_feed(&folders) {
+                    feeds.push(feed);
+                }
+            }
+            Event::End(e) if e.local_name().as_ref() == b"outline" => {
+                folders.pop();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if !seen_opml {
+        return Err("Not an OPML document".to_string());
+    }
+
+    Ok(feeds)
+}
+
+/// Writes subscriptions as an OPML 2.0 document, grouping feeds into one folder per category.
+/// Disabled feeds are kept but marked with a `disabled="true"` attribute, which `parse_opml`
+/// reads back so they stay disabled on import; other readers ignore it.
+pub fn export_opml(feeds: &[Feed]) -> String {
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<opml version=\"2.0\">\n");
+    out.push_str("  <head>\n");
+    out.push_str("    <title>Z-Project Subscriptions</title>\n");
+    out.push_str(&format!(
+        "    <dateCreated>{}</dateCreated>\n",
+        chrono::Local::now().to_rfc2822()
+    ));
+    out.push_str("  </head>\n");
+    out.push_str("  <body>\n");
+
+    let mut categories: Vec<&ArticleCategory> = Vec::new();
+    for feed in feeds {
+        if !categories.contains(&&feed.category) {
+            categories.push(&feed.category);
+        }
+    }
+
+    for category in categories {
+        let name = category.to_string();
+        out.push_str(&format!(
+            "    <outline text=\"{0}\" title=\"{0}\">\n",
+            escape(name.as_str())
+        ));
+        for feed in feeds.iter().filter(|f| &f.category == category) {
+            let title = feed.title.as_deref().unwrap_or(&feed.url);
+            out.push_str(&format!(
+                "      <outline type=\"rss\" text=\"{0}\" title=\"{0}\" xmlUrl=\"{1}\" category=\"{2}\"{3}/>\n",
+                escape(title),
+                escape(feed.url.as_str()),
+                escape(name.as_str()),
+                if feed.enabled { "" } else { " disabled=\"true\"" }
+            ));
+        }
+        out.push_str("    </outline>\n");
+    }
+
+    out.push_str("  </body>\n");
+    out.push_str("</opml>\n");
+    out
+}
+
+struct Outline {
+    text: Option<String>,
+    xml_url: Option<String>,
+    category: Option<String>,
+    disabled: bool,
+}
+
+impl Outline {
+    fn into_feed(self, folders: &[Option<String>]) -> Option<OpmlFeed> {
+        let url = self.xml_url?;
+
+        // `category` is a comma-separated list of slash-delimited paths, e.g. "/Tech/Rust,Blogs"
+        let from_attr = self.category.iter().flat_map(|c| {
+            c.split(',')
+                .flat_map(|path| path.split('/'))
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>()
+        });
+        // Innermost folder wins
+        let from_folders = folders.iter().rev().flatten().cloned();
+
+        let category = from_attr
+            .chain(from_folders)
+            .find_map(|name| name.parse::<ArticleCategory>().ok())
+            .unwrap_or(ArticleCategory::General);
+
+        Some(OpmlFeed {
+            url,
+            title: self.text,
+            category,
+            enabled: !self.disabled,
+        })
+    }
+}
+
+fn read_outline(e: &BytesStart, decoder: Decoder) -> Result<Outline, String> {
+    let mut outline = Outline {
+        text: None,
+        xml_url: None,
+        category: None,
+        disabled: false,
+    };
+    let mut title = None;
+
+    for attr in e.attributes() {
+        let attr = attr.map_err(|e| e.to_string())?;
+        let value = attr
+            .decode_and_unescape_value(decoder)
+            .map_err(|e| e.to_string())?
+            .trim()
+            .to_string();
+        if value.is_empty() {
+            continue;
+        }
+        match attr.key.as_ref() {
+            b"text" => outline.text = Some(value),
+            b"title" => title = Some(value),
+            b"xmlUrl" | b"xmlurl" => outline.xml_url = Some(value),
+            b"category" => outline.category = Some(value),
+            b"disabled" => outline.disabled = value.eq_ignore_ascii_case("true"),
+            _ => {}
+        }
+    }
+
+    // `text` is required by the spec, but some exporters only write `title`
+    if outline.text.is_none() {
+        outline.text = title;
+    }
+
+    Ok(outline)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_OPML: &str = include_str!("fixtures/subscriptions.opml");
+
+    #[test]
+    fn test_parse_sample_opml() {
+        let feeds = parse_opml(SAMPLE_OPML).unwrap();
+
+        assert_eq!(
+            feeds,
+            vec![
+                OpmlFeed {
+                    url: "https://blog.rust-lang.org/feed.xml".into(),
+                    title: Some("Rust Blog".into()),
+                    category: ArticleCategory::Rust,
+                    enabled: true,
+                },
+                OpmlFeed {
+                    url: "https://this-week-in-rust.org/rss.xml".into(),
+                    title: Some("This Week in Rust".into()),
+                    category: ArticleCategory::Rust,
+                    enabled: true,
+                },
+                // `category` attribute beats the enclosing folder
+                OpmlFeed {
+                    url: "https://tauri.app/blog/rss.xml".into(),
+                    title: Some("Tauri & Friends".into()),
+                    category: ArticleCategory::Tauri,
+                    enabled: true,
+                },
+                // Nested folder: inner "Web" wins over outer "Reading"
+                OpmlFeed {
+                    url: "https://css-tricks.com/feed/".into(),
+                    title: Some("CSS-Tricks".into()),
+                    category: ArticleCategory::Web,
+                    enabled: true,
+                },
+                // Unknown folder name falls back to General
+                OpmlFeed {
+                    url: "https://news.ycombinator.com/rss".into(),
+                    title: Some("Hacker News".into()),
+                    category: ArticleCategory::General,
+                    enabled: true,
+                },
+                // Top-level feed with only a title attribute
+                OpmlFeed {
+                    url: "https://openai.com/blog/rss.xml".into(),
+                    title: Some("OpenAI".into()),
+                    category: ArticleCategory::General,
+                    enabled: true,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_opml_round_trip() {
+        let feeds = vec![
+            Feed {
+                id: 1,
+                url: "https://blog.rust-lang.org/feed.xml".into(),
+                title: Some("Rust <Blog> & \"News\"".into()),
+                category: ArticleCategory::Rust,
+                enabled: true,
+            },
+            Feed {
+                id: 2,
+                url: "https://example.com/feed?format=rss&lang=en".into(),
+                title: None,
+                category: ArticleCategory::TypeScript,
+                enabled: true,
+            },
+            Feed {
+                id: 3,
+                url: "https://news.ycombinator.com/rss".into(),
+                title: Some("HN".into()),
+                category: ArticleCategory::General,
+                enabled: false,
+            },
+        ];
+
+        let parsed = parse_opml(&export_opml(&feeds)).unwrap();
+
+        assert_eq!(parsed.len(), feeds.len());
+        for (feed, opml) in feeds.iter().zip(parsed.iter()) {
+            assert_eq!(opml.url, feed.url);
+            assert_eq!(opml.category, feed.category);
+            assert_eq!(opml.enabled, feed.enabled);
+            assert_eq!(
+                opml.title.as_deref(),
+                Some(feed.title.as_deref().unwrap_or(&feed.url))
+            );
+        }
+    }
+
+    #[test]
+    fn test_sample_opml_survives_export() {
+        let imported = parse_opml(SAMPLE_OPML).unwrap();
+        let feeds: Vec<Feed> = imported
+            .iter()
+            .enumerate()
+            .map(|(i, f)| Feed {
+                id: i as i64,
+                url: f.url.clone(),
+                title: f.title.clone(),
+                category: f.category.clone(),
+                enabled: f.enabled,
+            })
+            .collect();
+
+        let mut reimported = parse_opml(&export_opml(&feeds)).unwrap();
+        let mut expected = imported;
+        // Export groups by category, so compare independent of order
+        reimported.sort_by(|a, b| a.url.cmp(&b.url));
+        expected.sort_by(|a, b| a.url.cmp(&b.url));
+        assert_eq!(reimported, expected);
+    }
+
+    #[test]
+    fn test_parse_rejects_non_opml() {
+        assert!(parse_opml("<rss version=\"2.0\"><channel></channel></rss>").is_err());
+        assert!(parse_opml("<opml><body><outline").is_err());
+    }
+}
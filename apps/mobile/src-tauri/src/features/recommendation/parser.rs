@@ -0,0 +1,568 @@
+use super::model::{Article, ArticleCategory, ArticleState};
+use serde::Deserialize;
+use std::io::Cursor;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FeedFormat {
+    Rss,
+    Atom,
+    JsonFeed,
+}
+
+/// Format-independent view of a feed item, filled in by each parser before mapping to `Article`.
+#[derive(Default)]
+struct FeedEntry {
+    id: String,
+    title: String,
+    link: String,
+    description: String,
+    content: String,
+    published_at: String,
+    author: Option<String>,
+    image_url: Option<String>,
+    categories: Vec<String>,
+}
+
+/// Sniffs the document root to decide which parser to use.
+/// Looks at the first element only, so it works regardless of the server's Content-Type.
+pub fn detect_format(content: &[u8]) -> Option<FeedFormat> {
+    let text = String::from_utf8_lossy(&content[..content.len().min(4096)]);
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+
+    if text.starts_with('{') {
+        return Some(FeedFormat::JsonFeed);
+    }
+
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        if rest.starts_with("!--") {
+            // Skip comments
+            rest = &rest[rest.find("-->")? + 3..];
+            continue;
+        }
+        if rest.starts_with('?') || rest.starts_with('!') {
+            // Skip XML declaration, processing instructions and DOCTYPE
+            rest = &rest[rest.find('>')? + 1..];
+            continue;
+        }
+
+        let name_end = rest
+            .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+            .unwrap_or(rest.len());
+        let name = &rest[..name_end];
+        // Ignore namespace prefixes (e.g. `atom:feed`, `rdf:RDF`)
+        let local_name = name.rsplit(':').next().unwrap_or(name);
+
+        return match local_name {
+            "rss" | "RDF" => Some(FeedFormat::Rss),
+            "feed" => Some(FeedFormat::Atom),
+            _ => None,
+        };
+    }
+
+    None
+}
+
+/// Parses RSS 2.0/1.0, Atom 1.0 or JSON Feed 1.x into articles.
+pub fn parse_feed(
+    content: &[u8],
+    source_category: ArticleCategory,
+) -> Result<Vec<Article>, String> {
+    let entries = match detect_format(content) {
+        Some(FeedFormat::Rss) => parse_rss(content)?,
+        Some(FeedFormat::Atom) => parse_atom(content)?,
+        Some(FeedFormat::JsonFeed) => parse_json_feed(content)?,
+        None => return Err("Unrecognized feed format".to_string()),
+    };
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| to_article(entry, &source_category))
+        .collect())
+}
+
+fn parse_rss(content: &[u8]) -> Result<Vec<FeedEntry>, String> {
+    let channel = rss::Channel::read_from(Cursor::new(content)).map_err(|e| e.to_string())?;
+
+    let entries = channel
+        .items()
+        .iter()
+        .map(|item| {
+            // Extract image URL
+            let mut image_url = None;
+
+            // 1. Check <enclosure>
+            if let Some(enclosure) = item.enclosure() {
+                if enclosure.mime_type().starts_with("image") {
+                    image_url = Some(enclosure.url().to_string());
+                }
+            }
+
+            // 2. Check <media:content> (extensions)
+            if image_url.is_none() {
+                if let Some(media_ext) = item.extensions().get("media") {
+                    if let Some(contents) = media_ext.get("content") {
+                        if let Some(first_content) = contents.first() {
+                            if let Some(url) = first_content.attrs().get("url") {
+                                image_url = Some(url.to_string());
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Extract Author
+            let author = item.author().map(|a| a.to_string()).or_else(|| {
+                item.dublin_core_ext()
+                    .and_then(|dc| dc.creators.first().cloned())
+            });
+
+            FeedEntry {
+                id: item
+                    .guid()
+                    .map(|g| g.value())
+                    .or(item.link())
+                    .unwrap_or("")
+                    .to_string(),
+                title: item.title().unwrap_or("").to_string(),
+                link: item.link().unwrap_or("").to_string(),
+                description: item.description().unwrap_or("").to_string(),
+                content: item.content().unwrap_or("").to_string(),
+                published_at: item.pub_date().unwrap_or("").to_string(),
+                author,
+                image_url,
+                categories: item
+                    .categories()
+                    .iter()
+                    .map(|c| c.name().to_string())
+                    .collect(),
+            }
+        })
+        .collect();
+
+    Ok(entries)
+}
+
+fn parse_atom(content: &[u8]) -> Result<Vec<FeedEntry>, String> {
+    let feed =
+        atom_syndication::Feed::read_from(Cursor::new(content)).map_err(|e| e.to_string())?;
+    let feed_author = feed.authors().first().map(|p| p.name().to_string());
+
+    let entries = feed
+        .entries()
+        .iter()
+        .map(|entry| {
+            // Prefer the HTML alternate link; Atom allows several <link> elements per entry
+            let link = entry
+                .links()
+                .iter()
+                .find(|l| l.rel() == "alternate")
+                .or_else(|| entry.links().first())
+                .map(|l| l.href().to_string())
+                .unwrap_or_default();
+
+            // Extract image URL
+            // 1. Check <link rel="enclosure" type="image/...">
+            let mut image_url = entry
+                .links()
+                .iter()
+                .find(|l| {
+                    l.rel() == "enclosure" && l.mime_type().is_some_and(|m| m.starts_with("image"))
+                })
+                .map(|l| l.href().to_string());
+
+            // 2. Check <media:thumbnail> / <media:content> (extensions)
+            if image_url.is_none() {
+                if let Some(media_ext) = entry.extensions().get("media") {
+                    image_url = ["thumbnail", "content"]
+                        .iter()
+                        .filter_map(|name| media_ext.get(*name))
+                        .filter_map(|exts| exts.first())
+                        .find_map(|ext| ext.attrs().get("url").cloned());
+                }
+            }
+
+            let summary = entry.summary().map(|s| s.as_str()).unwrap_or("");
+            let body = entry.content().and_then(|c| c.value()).unwrap_or("");
+            // Many Atom feeds only ship <content>, so use it as the description when <summary> is missing
+            let description = if summary.is_empty() { body } else { summary };
+
+            // Atom requires <updated>; <published> is optional
+            let published = entry.published().unwrap_or(entry.updated());
+
+            FeedEntry {
+                id: if entry.id().is_empty() {
+                    link.clone()
+                } else {
+                    entry.id().to_string()
+                },
+                title: entry.title().as_str().to_string(),
+                link,
+                description: description.to_string(),
+                content: body.to_string(),
+                published_at: published.to_rfc2822(),
+                author: entry
+                    .authors()
+                    .first()
+                    .map(|p| p.name().to_string())
+                    .or_else(|| feed_author.clone()),
+                image_url,
+                categories: entry
+                    .categories()
+                    .iter()
+                    .map(|c| c.label().unwrap_or(c.term()).to_string())
+                    .collect(),
+            }
+        })
+        .collect();
+
+    Ok(entries)
+}
+
+#[derive(Deserialize)]
+struct JsonFeed {
+    version: String,
+    #[serde(default)]
+    items: Vec<JsonFeedItem>,
+    #[serde(default)]
+    authors: Vec<JsonFeedAuthor>,
+    // JSON Feed 1.0 used a single `author` object
+    author: Option<JsonFeedAuthor>,
+}
+
+#[derive(Deserialize)]
+struct JsonFeedItem {
+    // The spec requires a string, but some generators emit numbers
+    id: serde_json::Value,
+    url: Option<String>,
+    external_url: Option<String>,
+    title: Option<String>,
+    content_html: Option<String>,
+    content_text: Option<String>,
+    summary: Option<String>,
+    image: Option<String>,
+    banner_image: Option<String>,
+    date_published: Option<String>,
+    date_modified: Option<String>,
+    #[serde(default)]
+    authors: Vec<JsonFeedAuthor>,
+    author: Option<JsonFeedAuthor>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    attachments: Vec<JsonFeedAttachment>,
+}
+
+#[derive(Deserialize)]
+struct JsonFeedAuthor {
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct JsonFeedAttachment {
+    url: String,
+    mime_type: String,
+}
+
+fn parse_json_feed(content: &[u8]) -> Result<Vec<FeedEntry>, String> {
+    let feed: JsonFeed = serde_json::from_slice(content).map_err(|e| e.to_string())?;
+
+    if !feed.version.starts_with("https://jsonfeed.org/version/") {
+        return Err(format!("Unsupported JSON Feed version: {}", feed.version));
+    }
+
+    let first_name = |authors: &[JsonFeedAuthor], author: &Option<JsonFeedAuthor>| {
+        authors
+            .iter()
+            .chain(author.iter())
+            .find_map(|a| a.name.clone())
+    };
+    let feed_author = first_name(&feed.authors, &feed.author);
+
+    let entries = feed
+        .items
+        .iter()
+        .map(|item| {
+            let link = item
+                .url
+                .clone()
+                .or_else(|| item.external_url.clone())
+                .unwrap_or_default();
+
+            let id = match &item.id {
+                serde_json::Value::String(s) if !s.is_empty() => s.clone(),
+                serde_json::Value::Number(n) => n.to_string(),
+                _ => link.clone(),
+            };
+
+            // Extract image URL: explicit image, then banner, then image attachments
+            let image_url = item
+                .image
+                .clone()
+                .or_else(|| item.banner_image.clone())
+                .or_else(|| {
+                    item.attachments
+                        .iter()
+                        .find(|a| a.mime_type.starts_with("image"))
+                        .map(|a| a.url.clone())
+                });
+
+            let content = item
+                .content_html
+                .clone()
+                .or_else(|| item.content_text.clone())
+                .unwrap_or_default();
+
+            let published_at = item
+                .date_published
+                .as_deref()
+                .or(item.date_modified.as_deref())
+                .map(|d| {
+                    chrono::DateTime::parse_from_rfc3339(d)
+                        .map(|dt| dt.to_rfc2822())
+                        .unwrap_or_else(|_| d.to_string())
+                })
+                .unwrap_or_default();
+
+            FeedEntry {
+                id,
+                title: item.title.clone().unwrap_or_default(),
+                link,
+                description: item.summary.clone().unwrap_or_else(|| content.clone()),
+                content,
+                published_at,
+                author: first_name(&item.authors, &item.author).or_else(|| feed_author.clone()),
+                image_url,
+                categories: item.tags.clone(),
+            }
+        })
+        .collect();
+
+    Ok(entries)
+}
+
+/// Shared mapping from a parsed entry to the `Article` model (image fallback, tagging, truncation).
+fn to_article(entry: FeedEntry, source_category: &ArticleCategory) -> Article {
+    // Optimized: Use OnceLock to compile regexes only once
+    static RE_IMG: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
+    let re_img =
+        RE_IMG.get_or_init(|| regex::Regex::new(r#"<img[^>]+src=["']([^"']+)["']"#).unwrap());
+
+    static RE_RUST: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
+    let re_rust = RE_RUST.get_or_init(|| regex::Regex::new(r"(?i)\brust\b").unwrap());
+
+    static RE_REACT: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
+    let re_react = RE_REACT.get_or_init(|| regex::Regex::new(r"(?i)\breact\b").unwrap());
+
+    static RE_ANDROID: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
+    let re_android = RE_ANDROID.get_or_init(|| regex::Regex::new(r"(?i)\bandroid\b").unwrap());
+
+    static RE_TAURI: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
+    let re_tauri = RE_TAURI.get_or_init(|| regex::Regex::new(r"(?i)\btauri\b").unwrap());
+
+    static RE_AI: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
+    let re_ai =
+        RE_AI.get_or_init(|| regex::Regex::new(r"(?i)\b(ai|llm|gpt|generative)\b").unwrap());
+
+    // Fallback: Regex match <img src="..."> in description or content
+    // Optimization: Truncate content to avoid scanning huge strings
+    let desc = entry.description.as_str();
+    let content = entry.content.as_str();
+
+    // Use char_indices to find the byte offset for the char limit without allocating new strings
+    let desc_limit = desc
+        .char_indices()
+        .map(|(i, _)| i)
+        .nth(5000)
+        .unwrap_or(desc.len());
+    let desc_trunc = &desc[..desc_limit];
+
+    let content_limit = content
+        .char_indices()
+        .map(|(i, _)| i)
+        .nth(5000)
+        .unwrap_or(content.len());
+    let content_trunc = &content[..content_limit];
+
+    let mut image_url = entry.image_url;
+    if image_url.is_none() {
+        if let Some(caps) = re_img.captures(desc_trunc) {
+            image_url = Some(caps[1].to_string());
+        } else if let Some(caps) = re_img.captures(content_trunc) {
+            image_url = Some(caps[1].to_string());
+        }
+    }
+
+    // Tags Logic
+    let mut tags = vec![source_category.clone()];
+    let text_to_check = format!(
+        "{} {} {}",
+        entry.title,
+        desc_trunc,
+        entry.categories.join(" ")
+    );
+
+    // Keyword based expansion
+    if re_rust.is_match(&text_to_check) && !tags.contains(&ArticleCategory::Rust) {
+        tags.push(ArticleCategory::Rust);
+    }
+    if re_react.is_match(&text_to_check) && !tags.contains(&ArticleCategory::React) {
+        tags.push(ArticleCategory::React);
+    }
+    if re_android.is_match(&text_to_check) && !tags.contains(&ArticleCategory::Android) {
+        tags.push(ArticleCategory::Android);
+    }
+    if re_tauri.is_match(&text_to_check) && !tags.contains(&ArticleCategory::Tauri) {
+        tags.push(ArticleCategory::Tauri);
+    }
+    if re_ai.is_match(&text_to_check) && !tags.contains(&ArticleCategory::AI) {
+        tags.push(ArticleCategory::AI);
+    }
+
+    // Remove General if specialized tag exists
+    if tags.len() > 1 && tags[0] == ArticleCategory::General {
+        tags.remove(0);
+    }
+
+    Article {
+        id: entry.id,
+        title: entry.title,
+        summary: desc.chars().take(250).collect(),
+        url: entry.link,
+        tags,
+        published_at: entry.published_at,
+        feedback: None,
+        image_url,
+        author: entry.author,
+        state: ArticleState::Unread,
+        server_rating: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RSS_FIXTURE: &str = include_str!("fixtures/rss.xml");
+    const ATOM_FIXTURE: &str = include_str!("fixtures/atom.xml");
+    const JSON_FEED_FIXTURE: &str = include_str!("fixtures/feed.json");
+
+    #[test]
+    fn test_detect_format() {
+        assert_eq!(detect_format(RSS_FIXTURE.as_bytes()), Some(FeedFormat::Rss));
+        assert_eq!(
+            detect_format(ATOM_FIXTURE.as_bytes()),
+            Some(FeedFormat::Atom)
+        );
+        assert_eq!(
+            detect_format(JSON_FEED_FIXTURE.as_bytes()),
+            Some(FeedFormat::JsonFeed)
+        );
+        assert_eq!(
+            detect_format(b"\xEF\xBB\xBF<!-- generated --><rdf:RDF></rdf:RDF>"),
+            Some(FeedFormat::Rss)
+        );
+        assert_eq!(detect_format(b"<html><body></body></html>"), None);
+    }
+
+    #[test]
+    fn test_parse_rss_fixture() {
+        let articles = parse_feed(RSS_FIXTURE.as_bytes(), ArticleCategory::Rust).unwrap();
+        assert_eq!(articles.len(), 2);
+
+        let first = &articles[0];
+        assert_eq!(first.id, "https://blog.example.com/posts/async-closures");
+        assert_eq!(first.title, "Async closures are stable");
+        assert_eq!(first.url, "https://blog.example.com/posts/async-closures");
+        assert_eq!(first.published_at, "Tue, 07 Jan 2025 10:00:00 +0000");
+        assert_eq!(first.author.as_deref(), Some("Rust Team"));
+        assert_eq!(
+            first.image_url.as_deref(),
+            Some("https://blog.example.com/img/closures.png")
+        );
+        assert_eq!(first.tags, vec![ArticleCategory::Rust]);
+
+        // Image from inline <img>, React tag from <category>
+        let second = &articles[1];
+        assert_eq!(
+            second.image_url.as_deref(),
+            Some("https://blog.example.com/img/inline.jpg")
+        );
+        assert!(second.tags.contains(&ArticleCategory::React));
+    }
+
+    #[test]
+    fn test_parse_atom_fixture() {
+        let articles = parse_feed(ATOM_FIXTURE.as_bytes(), ArticleCategory::General).unwrap();
+        assert_eq!(articles.len(), 2);
+
+        let first = &articles[0];
+        assert_eq!(first.id, "tag:blog.example.org,2025:tauri-2");
+        assert_eq!(first.title, "Shipping Tauri 2 on Android");
+        assert_eq!(first.url, "https://blog.example.org/tauri-2");
+        assert_eq!(first.published_at, "Mon, 3 Feb 2025 09:30:00 +0000");
+        assert_eq!(first.author.as_deref(), Some("Jane Doe"));
+        assert_eq!(
+            first.image_url.as_deref(),
+            Some("https://blog.example.org/tauri-2/cover.png")
+        );
+        // General is replaced by the specialized tags
+        assert!(first.tags.contains(&ArticleCategory::Tauri));
+        assert!(first.tags.contains(&ArticleCategory::Android));
+        assert!(!first.tags.contains(&ArticleCategory::General));
+
+        // No <published>/<author>/<summary>: falls back to <updated>, the feed author and <content>
+        let second = &articles[1];
+        assert_eq!(second.published_at, "Sat, 1 Feb 2025 12:00:00 +0000");
+        assert_eq!(second.author.as_deref(), Some("Example Org"));
+        assert!(second.summary.starts_with("Notes on"));
+        assert_eq!(
+            second.image_url.as_deref(),
+            Some("https://blog.example.org/img/notes.webp")
+        );
+        assert_eq!(second.tags, vec![ArticleCategory::General]);
+    }
+
+    #[test]
+    fn test_parse_json_feed_fixture() {
+        let articles = parse_feed(JSON_FEED_FIXTURE.as_bytes(), ArticleCategory::Web).unwrap();
+        assert_eq!(articles.len(), 2);
+
+        let first = &articles[0];
+        assert_eq!(first.id, "https://example.net/posts/llm-tooling");
+        assert_eq!(first.title, "LLM tooling for web developers");
+        assert_eq!(first.url, "https://example.net/posts/llm-tooling");
+        assert_eq!(
+            first.summary,
+            "How we use LLM assistants in a web codebase."
+        );
+        assert_eq!(first.published_at, "Sat, 15 Mar 2025 08:00:00 +0000");
+        assert_eq!(first.author.as_deref(), Some("Alex Kim"));
+        assert_eq!(
+            first.image_url.as_deref(),
+            Some("https://example.net/img/llm.png")
+        );
+        assert!(first.tags.contains(&ArticleCategory::Web));
+        assert!(first.tags.contains(&ArticleCategory::AI));
+
+        // Numeric id, feed-level author, image from attachments, Rust tag from `tags`
+        let second = &articles[1];
+        assert_eq!(second.id, "42");
+        assert_eq!(second.author.as_deref(), Some("Example Net"));
+        assert_eq!(
+            second.image_url.as_deref(),
+            Some("https://example.net/img/attachment.jpg")
+        );
+        assert!(second.tags.contains(&ArticleCategory::Rust));
+    }
+
+    #[test]
+    fn test_parse_json_feed_rejects_unknown_version() {
+        let result = parse_feed(
+            br#"{"version": "1.0", "items": []}"#,
+            ArticleCategory::General,
+        );
+        assert!(result.is_err());
+    }
+}
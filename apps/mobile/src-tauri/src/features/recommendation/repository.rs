@@ -1,11 +1,20 @@
 use crate::db::DbPool;
 use crate::error::AppError;
-use crate::features::recommendation::model::{Article, ArticleCategory, Feedback};
+use crate::features::recommendation::model::{
+    Article, ArticleCategory, ArticlePage, ArticleState, Feed, FeedState, Feedback, KeywordWeight,
+    RecommendationBatch, RecommendationOutcome, RecommendationStats, SearchFilters, SearchResult,
+    ServerRating,
+};
+use crate::features::recommendation::scoring::{
+    decay_factor, MAX_ADJUSTMENT, MAX_LEARNED_TERMS, NEGLIGIBLE_WEIGHT,
+};
+use crate::features::sync::model::SyncPolicy;
 use rusqlite::OptionalExtension;
 use std::collections::HashMap;
 
 pub trait RecommendationRepository: Send + Sync {
     fn get_articles(&self) -> Result<Vec<Article>, AppError>;
+    fn get_article(&self, id: &str) -> Result<Option<Article>, AppError>;
     fn get_feedback(&self) -> Result<Vec<Feedback>, AppError>;
     fn check_article_exists(&self, url: &str) -> Result<Option<String>, AppError>;
     fn save_article(&self, article: Article) -> Result<(), AppError>;
@@ -26,6 +35,162 @@ pub trait RecommendationRepository: Send + Sync {
         server_article_id: &str,
     ) -> Result<(), AppError>;
     fn get_server_article_id(&self, article_id: &str) -> Result<Option<String>, AppError>;
+    /// Articles without a `server_article_id` that match any rule of `policy`, oldest first
+    fn get_unsynced_articles(&self, policy: &SyncPolicy) -> Result<Vec<Article>, AppError>;
+    // Feed fetch state (conditional GET validators and health)
+    fn get_feed_states(&self) -> Result<Vec<FeedState>, AppError>;
+    fn save_feed_states(&self, states: &[FeedState]) -> Result<(), AppError>;
+    // Feed subscriptions
+    fn get_feeds(&self) -> Result<Vec<Feed>, AppError>;
+    fn get_enabled_feeds(&self) -> Result<Vec<Feed>, AppError>;
+    fn add_feed(
+        &self,
+        url: &str,
+        title: Option<&str>,
+        category: &ArticleCategory,
+    ) -> Result<Feed, AppError>;
+    fn remove_feed(&self, id: i64) -> Result<(), AppError>;
+    fn set_feed_enabled(&self, id: i64, enabled: bool) -> Result<(), AppError>;
+    fn rename_feed(&self, id: i64, title: Option<&str>) -> Result<(), AppError>;
+    fn set_feed_category(&self, id: i64, category: &ArticleCategory) -> Result<(), AppError>;
+    // Reading state
+    fn set_article_state(
+        &self,
+        id: &str,
+        state: ArticleState,
+        timestamp: &str,
+    ) -> Result<(), AppError>;
+    fn get_articles_by_state(
+        &self,
+        state: ArticleState,
+        limit: u32,
+        offset: u32,
+    ) -> Result<ArticlePage, AppError>;
+    // Full-text search (includes read and rated articles)
+    fn search_articles(
+        &self,
+        query: &str,
+        filters: &SearchFilters,
+    ) -> Result<Vec<SearchResult>, AppError>;
+
+    /// Stores the batch and returns its id (`batch.id` is ignored)
+    fn save_recommendation_batch(&self, batch: &RecommendationBatch) -> Result<i64, AppError>;
+    /// Most recent first
+    fn get_recommendation_batches(&self, limit: u32) -> Result<Vec<RecommendationBatch>, AppError>;
+    fn get_recommendation_stats(&self) -> Result<RecommendationStats, AppError>;
+
+    /// All keywords with their effective weight at `now`, strongest first
+    fn get_keyword_weights(&self, now: &str) -> Result<Vec<KeywordWeight>, AppError>;
+    /// Moves each term's learned adjustment by `delta` (after decaying it to `timestamp`),
+    /// creating terms that aren't known yet. Learned terms that have faded to nothing are
+    /// forgotten, and only the strongest `MAX_LEARNED_TERMS` are kept.
+    fn apply_keyword_feedback(
+        &self,
+        terms: &[String],
+        delta: f64,
+        timestamp: &str,
+    ) -> Result<(), AppError>;
+    /// Forgets what was learned for `term`, or for every term when `None`.
+    /// Seeded terms go back to their built-in weight; learned terms are removed.
+    fn reset_keyword_weights(&self, term: Option<&str>) -> Result<(), AppError>;
+}
+
+const ARTICLE_COLUMNS: &str = "id, title, summary, url, tags, published_at, image_url, author, feedback_helpful, feedback_reason, feedback_at, state, server_rating, server_positive_count, server_negative_count, server_updated_at";
+
+/// Maps the first 16 columns of a row selected as `ARTICLE_COLUMNS`.
+fn article_from_row(row: &rusqlite::Row) -> rusqlite::Result<Article> {
+    let tags_str: String = row.get(4)?;
+    let tags: Vec<ArticleCategory> = serde_json::from_str(&tags_str).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(4, rusqlite::types::Type::Text, Box::new(e))
+    })?;
+
+    let image_url: Option<String> = row.get(6).ok();
+    let author: Option<String> = row.get(7).ok();
+    let feedback_helpful: Option<bool> = row.get(8).ok();
+    let feedback_reason: Option<String> = row.get(9).ok();
+    let feedback_at: Option<String> = row.get(10).ok();
+    let state_str: String = row.get(11)?;
+    let state = state_str.parse::<ArticleState>().map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(11, rusqlite::types::Type::Text, e.into())
+    })?;
+
+    let server_rating = match row.get::<_, Option<String>>(15)? {
+        Some(updated_at) => Some(ServerRating {
+            rating: row.get(12)?,
+            positive_count: row.get(13)?,
+            negative_count: row.get(14)?,
+            updated_at,
+        }),
+        None => None,
+    };
+
+    let feedback =
+        if let (Some(h), Some(r), Some(t)) = (feedback_helpful, feedback_reason, feedback_at) {
+            Some(Feedback {
+                is_helpful: h,
+                reason: r,
+                created_at: t,
+            })
+        } else {
+            None
+        };
+
+    Ok(Article {
+        id: row.get(0)?,
+        title: row.get(1)?,
+        summary: row.get(2)?,
+        url: row.get(3)?,
+        tags,
+        published_at: row.get(5)?,
+        image_url,
+        author,
+        feedback,
+        state,
+        server_rating,
+    })
+}
+
+/// Forgets learned terms that have faded to nothing, then the weakest beyond `MAX_LEARNED_TERMS`.
+fn prune_learned_terms(tx: &rusqlite::Transaction, now: &str) -> Result<(), AppError> {
+    let mut learned: Vec<(String, f64)> = {
+        let mut stmt = tx
+            .prepare("SELECT term, adjustment, updated_at FROM keyword_weights WHERE seeded = 0")?;
+        let rows = stmt.query_map([], |row| {
+            let adjustment: f64 = row.get(1)?;
+            let updated_at: Option<String> = row.get(2)?;
+            Ok((
+                row.get(0)?,
+                (adjustment * decay_factor(updated_at.as_deref(), now)).abs(),
+            ))
+        })?;
+        rows.collect::<rusqlite::Result<_>>()?
+    };
+    learned.sort_by(|a, b| {
+        b.1.partial_cmp(&a.1)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.0.cmp(&b.0))
+    });
+
+    let mut delete = tx.prepare("DELETE FROM keyword_weights WHERE term = ?1")?;
+    for (i, (term, strength)) in learned.iter().enumerate() {
+        if i >= MAX_LEARNED_TERMS || *strength < NEGLIGIBLE_WEIGHT {
+            delete.execute(rusqlite::params![term])?;
+        }
+    }
+    Ok(())
+}
+
+/// Turns free-form user input into a safe FTS5 query: every word is quoted (so operators and
+/// punctuation can't cause syntax errors) and the last one is a prefix match for search-as-you-type.
+fn build_fts_query(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split_whitespace()
+        .map(|t| format!("\"{}\"", t.replace('"', "\"\"")))
+        .collect();
+    if terms.is_empty() {
+        return None;
+    }
+    Some(format!("{}*", terms.join(" ")))
 }
 
 pub struct SqliteRecommendationRepository {
@@ -38,54 +203,64 @@ impl SqliteRecommendationRepository {
     }
 }
 
+impl SqliteRecommendationRepository {
+    fn query_feeds(&self, where_clause: &str) -> Result<Vec<Feed>, AppError> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, url, title, category, enabled FROM feeds {} ORDER BY id ASC",
+            where_clause
+        ))?;
+
+        let feed_iter = stmt.query_map([], |row| {
+            let category_str: String = row.get(3)?;
+            let category = category_str.parse::<ArticleCategory>().map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(3, rusqlite::types::Type::Text, e.into())
+            })?;
+
+            Ok(Feed {
+                id: row.get(0)?,
+                url: row.get(1)?,
+                title: row.get(2)?,
+                category,
+                enabled: row.get(4)?,
+            })
+        })?;
+
+        let mut feeds = Vec::new();
+        for f in feed_iter {
+            feeds.push(f?);
+        }
+        Ok(feeds)
+    }
+
+    fn update_feed<P: rusqlite::Params>(
+        &self,
+        id: i64,
+        sql: &str,
+        params: P,
+    ) -> Result<(), AppError> {
+        let conn = self.pool.get()?;
+        let updated = conn.execute(sql, params)?;
+        if updated == 0 {
+            return Err(AppError::NotFound(format!("Feed {}", id)));
+        }
+        Ok(())
+    }
+}
+
 impl RecommendationRepository for SqliteRecommendationRepository {
     fn get_articles(&self) -> Result<Vec<Article>, AppError> {
         let conn = self.pool.get()?;
         // Optimization: Filter out articles that already have feedback (Read/Processed)
         // This prevents loading thousands of old articles into memory only to filter them out in Rust.
         // Added ORDER BY rowid DESC LIMIT 500 to prioritize recent content (by insertion/discovery time) and cap memory usage.
-        let mut stmt = conn.prepare("SELECT id, title, summary, url, tags, published_at, image_url, author, feedback_helpful, feedback_reason, feedback_at FROM articles WHERE feedback_helpful IS NULL ORDER BY rowid DESC LIMIT 500")?;
-
-        let articles_iter = stmt.query_map([], |row| {
-            let tags_str: String = row.get(4)?;
-            let tags: Vec<ArticleCategory> = serde_json::from_str(&tags_str).map_err(|e| {
-                rusqlite::Error::FromSqlConversionFailure(
-                    4,
-                    rusqlite::types::Type::Text,
-                    Box::new(e),
-                )
-            })?;
-
-            let image_url: Option<String> = row.get(6).ok();
-            let author: Option<String> = row.get(7).ok();
-            let feedback_helpful: Option<bool> = row.get(8).ok();
-            let feedback_reason: Option<String> = row.get(9).ok();
-            let feedback_at: Option<String> = row.get(10).ok();
-
-            let feedback = if let (Some(h), Some(r), Some(t)) =
-                (feedback_helpful, feedback_reason, feedback_at)
-            {
-                Some(Feedback {
-                    is_helpful: h,
-                    reason: r,
-                    created_at: t,
-                })
-            } else {
-                None
-            };
+        // Read, saved and archived articles are reachable through the state listings instead.
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM articles WHERE feedback_helpful IS NULL AND state = 'unread' ORDER BY rowid DESC LIMIT 500",
+            ARTICLE_COLUMNS
+        ))?;
 
-            Ok(Article {
-                id: row.get(0)?,
-                title: row.get(1)?,
-                summary: row.get(2)?,
-                url: row.get(3)?,
-                tags,
-                published_at: row.get(5)?,
-                image_url,
-                author,
-                feedback,
-            })
-        })?;
+        let articles_iter = stmt.query_map([], article_from_row)?;
 
         let mut articles = Vec::new();
         for a in articles_iter {
@@ -94,6 +269,18 @@ impl RecommendationRepository for SqliteRecommendationRepository {
         Ok(articles)
     }
 
+    fn get_article(&self, id: &str) -> Result<Option<Article>, AppError> {
+        let conn = self.pool.get()?;
+        let article = conn
+            .query_row(
+                &format!("SELECT {} FROM articles WHERE id = ?1", ARTICLE_COLUMNS),
+                rusqlite::params![id],
+                article_from_row,
+            )
+            .optional()?;
+        Ok(article)
+    }
+
     fn get_feedback(&self) -> Result<Vec<Feedback>, AppError> {
         let conn = self.pool.get()?;
         let mut stmt = conn.prepare("SELECT feedback_helpful, feedback_reason, feedback_at FROM articles WHERE feedback_helpful IS NOT NULL")?;
@@ -195,7 +382,8 @@ impl RecommendationRepository for SqliteRecommendationRepository {
             let mut stmt_insert = tx.prepare_cached(
                 "INSERT INTO articles (id, title, summary, url, tags, published_at, image_url, author, feedback_helpful, feedback_reason, feedback_at)
                  VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
-                 ON CONFLICT(url) DO UPDATE SET tags = ?5, published_at = ?6, image_url = ?7, author = ?8"
+                 ON CONFLICT(url) DO UPDATE SET tags = ?5, published_at = ?6, image_url = ?7, author = ?8,
+                    summary = CASE WHEN summary = '' THEN ?3 ELSE summary END"
             )?;
 
             for article in articles {
@@ -247,7 +435,10 @@ impl RecommendationRepository for SqliteRecommendationRepository {
     ) -> Result<(), AppError> {
         let conn = self.pool.get()?;
         conn.execute(
-            "UPDATE articles SET feedback_helpful = ?1, feedback_reason = ?2, feedback_at = ?3 WHERE id = ?4",
+            "UPDATE articles SET feedback_helpful = ?1, feedback_reason = ?2, feedback_at = ?3,
+                state = CASE WHEN state = 'unread' THEN 'read' ELSE state END,
+                state_changed_at = CASE WHEN state = 'unread' THEN ?3 ELSE state_changed_at END
+             WHERE id = ?4",
             rusqlite::params![helpful, reason, timestamp, id],
         )?;
         Ok(())
@@ -291,13 +482,454 @@ impl RecommendationRepository for SqliteRecommendationRepository {
 
     fn get_server_article_id(&self, article_id: &str) -> Result<Option<String>, AppError> {
         let conn = self.pool.get()?;
-        let server_id: Option<String> = conn
+        // Outer None: no such article; inner None: not synced yet
+        let server_id: Option<Option<String>> = conn
             .query_row(
                 "SELECT server_article_id FROM articles WHERE id = ?1",
                 rusqlite::params![article_id],
                 |row| row.get(0),
             )
             .optional()?;
-        Ok(server_id)
+        Ok(server_id.flatten())
+    }
+
+    fn get_unsynced_articles(&self, policy: &SyncPolicy) -> Result<Vec<Article>, AppError> {
+        let mut rules = Vec::new();
+        if policy.helpful {
+            rules.push("feedback_helpful = 1");
+        }
+        if policy.rated {
+            rules.push("feedback_helpful IS NOT NULL");
+        }
+        if policy.saved {
+            rules.push("state = 'saved'");
+        }
+        if rules.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM articles WHERE server_article_id IS NULL AND ({}) ORDER BY rowid",
+            ARTICLE_COLUMNS,
+            rules.join(" OR ")
+        ))?;
+        let articles_iter = stmt.query_map([], article_from_row)?;
+
+        let mut articles = Vec::new();
+        for a in articles_iter {
+            articles.push(a?);
+        }
+        Ok(articles)
+    }
+
+    fn get_feed_states(&self) -> Result<Vec<FeedState>, AppError> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT url, etag, last_modified, last_status, last_error, last_fetched_at, last_success_at, consecutive_failures
+             FROM feed_state ORDER BY url",
+        )?;
+
+        let state_iter = stmt.query_map([], |row| {
+            Ok(FeedState {
+                url: row.get(0)?,
+                etag: row.get(1)?,
+                last_modified: row.get(2)?,
+                last_status: row.get(3)?,
+                last_error: row.get(4)?,
+                last_fetched_at: row.get(5)?,
+                last_success_at: row.get(6)?,
+                consecutive_failures: row.get(7)?,
+            })
+        })?;
+
+        let mut states = Vec::new();
+        for s in state_iter {
+            states.push(s?);
+        }
+        Ok(states)
+    }
+
+    fn save_feed_states(&self, states: &[FeedState]) -> Result<(), AppError> {
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare_cached(
+                "INSERT INTO feed_state (url, etag, last_modified, last_status, last_error, last_fetched_at, last_success_at, consecutive_failures)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                 ON CONFLICT(url) DO UPDATE SET etag = ?2, last_modified = ?3, last_status = ?4, last_error = ?5,
+                    last_fetched_at = ?6, last_success_at = ?7, consecutive_failures = ?8",
+            )?;
+            for state in states {
+                stmt.execute(rusqlite::params![
+                    state.url,
+                    state.etag,
+                    state.last_modified,
+                    state.last_status,
+                    state.last_error,
+                    state.last_fetched_at,
+                    state.last_success_at,
+                    state.consecutive_failures
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn get_feeds(&self) -> Result<Vec<Feed>, AppError> {
+        self.query_feeds("")
+    }
+
+    fn get_enabled_feeds(&self) -> Result<Vec<Feed>, AppError> {
+        self.query_feeds("WHERE enabled = 1")
+    }
+
+    fn add_feed(
+        &self,
+        url: &str,
+        title: Option<&str>,
+        category: &ArticleCategory,
+    ) -> Result<Feed, AppError> {
+        let parsed = reqwest::Url::parse(url)
+            .map_err(|e| AppError::Validation(format!("Invalid feed URL '{}': {}", url, e)))?;
+        if parsed.scheme() != "http" && parsed.scheme() != "https" {
+            return Err(AppError::Validation(format!(
+                "Feed URL must use http or https: {}",
+                url
+            )));
+        }
+
+        let conn = self.pool.get()?;
+        let result = conn.execute(
+            "INSERT INTO feeds (url, title, category, enabled) VALUES (?1, ?2, ?3, 1)",
+            rusqlite::params![url, title, category.to_string()],
+        );
+        match result {
+            Err(rusqlite::Error::SqliteFailure(e, _))
+                if e.code == rusqlite::ErrorCode::ConstraintViolation =>
+            {
+                return Err(AppError::Validation(format!(
+                    "Already subscribed to {}",
+                    url
+                )));
+            }
+            other => {
+                other?;
+            }
+        }
+
+        Ok(Feed {
+            id: conn.last_insert_rowid(),
+            url: url.to_string(),
+            title: title.map(|t| t.to_string()),
+            category: category.clone(),
+            enabled: true,
+        })
+    }
+
+    fn remove_feed(&self, id: i64) -> Result<(), AppError> {
+        self.update_feed(id, "DELETE FROM feeds WHERE id = ?1", rusqlite::params![id])
+    }
+
+    fn set_feed_enabled(&self, id: i64, enabled: bool) -> Result<(), AppError> {
+        self.update_feed(
+            id,
+            "UPDATE feeds SET enabled = ?1 WHERE id = ?2",
+            rusqlite::params![enabled, id],
+        )
+    }
+
+    fn rename_feed(&self, id: i64, title: Option<&str>) -> Result<(), AppError> {
+        self.update_feed(
+            id,
+            "UPDATE feeds SET title = ?1 WHERE id = ?2",
+            rusqlite::params![title, id],
+        )
+    }
+
+    fn set_feed_category(&self, id: i64, category: &ArticleCategory) -> Result<(), AppError> {
+        self.update_feed(
+            id,
+            "UPDATE feeds SET category = ?1 WHERE id = ?2",
+            rusqlite::params![category.to_string(), id],
+        )
+    }
+
+    fn search_articles(
+        &self,
+        query: &str,
+        filters: &SearchFilters,
+    ) -> Result<Vec<SearchResult>, AppError> {
+        let Some(fts_query) = build_fts_query(query) else {
+            return Ok(Vec::new());
+        };
+
+        let mut sql = String::from(
+            "SELECT a.id, a.title, a.summary, a.url, a.tags, a.published_at, a.image_url, a.author,
+                    a.feedback_helpful, a.feedback_reason, a.feedback_at, a.state,
+                    a.server_rating, a.server_positive_count, a.server_negative_count,
+                    a.server_updated_at,
+                    highlight(articles_fts, 0, '<mark>', '</mark>'),
+                    snippet(articles_fts, 1, '<mark>', '</mark>', '…', 16),
+                    bm25(articles_fts, 10.0, 3.0, 1.0) AS rank
+             FROM articles_fts
+             JOIN articles a ON a.rowid = articles_fts.rowid
+             WHERE articles_fts MATCH ?",
+        );
+        let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(fts_query)];
+
+        if let Some(category) = &filters.category {
+            sql.push_str(" AND EXISTS (SELECT 1 FROM json_each(a.tags) WHERE json_each.value = ?)");
+            params.push(Box::new(category.to_string()));
+        }
+        if let Some(helpful) = filters.helpful {
+            sql.push_str(" AND a.feedback_helpful = ?");
+            params.push(Box::new(helpful));
+        }
+        match filters.rated {
+            Some(true) => sql.push_str(" AND a.feedback_helpful IS NOT NULL"),
+            Some(false) => sql.push_str(" AND a.feedback_helpful IS NULL"),
+            None => {}
+        }
+
+        sql.push_str(" ORDER BY rank LIMIT ? OFFSET ?");
+        params.push(Box::new(filters.limit.unwrap_or(50).min(200)));
+        params.push(Box::new(filters.offset.unwrap_or(0)));
+
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(&sql)?;
+        let result_iter = stmt.query_map(
+            rusqlite::params_from_iter(params.iter().map(|p| p.as_ref())),
+            |row| {
+                Ok(SearchResult {
+                    article: article_from_row(row)?,
+                    title_highlight: row.get::<_, Option<String>>(16)?.unwrap_or_default(),
+                    snippet: row.get::<_, Option<String>>(17)?.unwrap_or_default(),
+                    rank: row.get(18)?,
+                })
+            },
+        )?;
+
+        let mut results = Vec::new();
+        for r in result_iter {
+            results.push(r?);
+        }
+        Ok(results)
+    }
+
+    fn set_article_state(
+        &self,
+        id: &str,
+        state: ArticleState,
+        timestamp: &str,
+    ) -> Result<(), AppError> {
+        let conn = self.pool.get()?;
+        let updated = conn.execute(
+            "UPDATE articles SET state = ?1, state_changed_at = ?2 WHERE id = ?3",
+            rusqlite::params![state.as_str(), timestamp, id],
+        )?;
+        if updated == 0 {
+            return Err(AppError::NotFound(format!("Article {}", id)));
+        }
+        Ok(())
+    }
+
+    fn get_articles_by_state(
+        &self,
+        state: ArticleState,
+        limit: u32,
+        offset: u32,
+    ) -> Result<ArticlePage, AppError> {
+        let conn = self.pool.get()?;
+        let total: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM articles WHERE state = ?1",
+            rusqlite::params![state.as_str()],
+            |row| row.get(0),
+        )?;
+
+        // Most recently changed first; unread articles have no change time, so fall back to discovery order
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM articles WHERE state = ?1
+             ORDER BY state_changed_at DESC, rowid DESC LIMIT ?2 OFFSET ?3",
+            ARTICLE_COLUMNS
+        ))?;
+        let articles_iter = stmt.query_map(
+            rusqlite::params![state.as_str(), limit, offset],
+            article_from_row,
+        )?;
+
+        let mut articles = Vec::new();
+        for a in articles_iter {
+            articles.push(a?);
+        }
+        Ok(ArticlePage {
+            articles,
+            total,
+            offset,
+            limit,
+        })
+    }
+
+    fn save_recommendation_batch(&self, batch: &RecommendationBatch) -> Result<i64, AppError> {
+        let conn = self.pool.get()?;
+        conn.execute(
+            "INSERT INTO recommendation_batches
+                (created_at, outcome, provider, model, attempts, article_ids, error)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            rusqlite::params![
+                batch.created_at,
+                batch.outcome.as_str(),
+                batch.provider,
+                batch.model,
+                batch.attempts,
+                serde_json::to_string(&batch.article_ids).unwrap_or_default(),
+                batch.error,
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    fn get_recommendation_batches(&self, limit: u32) -> Result<Vec<RecommendationBatch>, AppError> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, created_at, outcome, provider, model, attempts, article_ids, error
+             FROM recommendation_batches ORDER BY id DESC LIMIT ?1",
+        )?;
+        let batch_iter = stmt.query_map([limit], |row| {
+            let outcome: String = row.get(2)?;
+            let article_ids: String = row.get(6)?;
+            Ok(RecommendationBatch {
+                id: row.get(0)?,
+                created_at: row.get(1)?,
+                outcome: outcome.parse().unwrap_or(RecommendationOutcome::Fallback),
+                provider: row.get(3)?,
+                model: row.get(4)?,
+                attempts: row.get(5)?,
+                article_ids: serde_json::from_str(&article_ids).unwrap_or_default(),
+                error: row.get(7)?,
+            })
+        })?;
+
+        let mut batches = Vec::new();
+        for b in batch_iter {
+            batches.push(b?);
+        }
+        Ok(batches)
+    }
+
+    fn get_recommendation_stats(&self) -> Result<RecommendationStats, AppError> {
+        let conn = self.pool.get()?;
+        let mut stmt =
+            conn.prepare("SELECT outcome, COUNT(*) FROM recommendation_batches GROUP BY outcome")?;
+        let mut rows = stmt.query([])?;
+
+        let mut stats = RecommendationStats::default();
+        while let Some(row) = rows.next()? {
+            let outcome: String = row.get(0)?;
+            let count: i64 = row.get(1)?;
+            match outcome.parse() {
+                Ok(RecommendationOutcome::Ai) => stats.ai += count,
+                Ok(RecommendationOutcome::Fallback) => stats.fallback += count,
+                Ok(RecommendationOutcome::Error) => stats.error += count,
+                Err(_) => {}
+            }
+            stats.total += count;
+        }
+        Ok(stats)
+    }
+
+    fn get_keyword_weights(&self, now: &str) -> Result<Vec<KeywordWeight>, AppError> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT term, base_weight, adjustment, feedback_count, updated_at FROM keyword_weights",
+        )?;
+        let weight_iter = stmt.query_map([], |row| {
+            let base_weight: f64 = row.get(1)?;
+            let adjustment: f64 = row.get(2)?;
+            let updated_at: Option<String> = row.get(4)?;
+            Ok(KeywordWeight {
+                term: row.get(0)?,
+                weight: base_weight + adjustment * decay_factor(updated_at.as_deref(), now),
+                base_weight,
+                adjustment,
+                feedback_count: row.get(3)?,
+                updated_at,
+            })
+        })?;
+
+        let mut weights = Vec::new();
+        for w in weight_iter {
+            weights.push(w?);
+        }
+        weights.sort_by(|a, b| {
+            b.weight
+                .partial_cmp(&a.weight)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.term.cmp(&b.term))
+        });
+        Ok(weights)
+    }
+
+    fn apply_keyword_feedback(
+        &self,
+        terms: &[String],
+        delta: f64,
+        timestamp: &str,
+    ) -> Result<(), AppError> {
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+        {
+            let mut select =
+                tx.prepare("SELECT adjustment, updated_at FROM keyword_weights WHERE term = ?1")?;
+            let mut upsert = tx.prepare(
+                "INSERT INTO keyword_weights (term, adjustment, feedback_count, updated_at)
+                 VALUES (?1, ?2, 1, ?3)
+                 ON CONFLICT(term) DO UPDATE SET
+                    adjustment = excluded.adjustment,
+                    feedback_count = feedback_count + 1,
+                    updated_at = excluded.updated_at",
+            )?;
+
+            for term in terms {
+                let existing: Option<(f64, Option<String>)> = select
+                    .query_row(rusqlite::params![term], |row| {
+                        Ok((row.get(0)?, row.get(1)?))
+                    })
+                    .optional()?;
+                let decayed = existing
+                    .map(|(adjustment, updated_at)| {
+                        adjustment * decay_factor(updated_at.as_deref(), timestamp)
+                    })
+                    .unwrap_or(0.0);
+                let adjustment = (decayed + delta).clamp(-MAX_ADJUSTMENT, MAX_ADJUSTMENT);
+                upsert.execute(rusqlite::params![term, adjustment, timestamp])?;
+            }
+        }
+        prune_learned_terms(&tx, timestamp)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn reset_keyword_weights(&self, term: Option<&str>) -> Result<(), AppError> {
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+        // `?1 IS NULL` matches every term
+        let deleted = tx.execute(
+            "DELETE FROM keyword_weights WHERE seeded = 0 AND (?1 IS NULL OR term = ?1)",
+            rusqlite::params![term],
+        )?;
+        let updated = tx.execute(
+            "UPDATE keyword_weights SET adjustment = 0, feedback_count = 0, updated_at = NULL
+             WHERE seeded = 1 AND (?1 IS NULL OR term = ?1)",
+            rusqlite::params![term],
+        )?;
+        if let Some(term) = term {
+            if deleted + updated == 0 {
+                return Err(AppError::NotFound(format!("Keyword {}", term)));
+            }
+        }
+        tx.commit()?;
+        Ok(())
     }
 }
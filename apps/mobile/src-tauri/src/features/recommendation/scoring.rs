@@ -1,35 +1,174 @@
-use super::config::{HIGH_IMPACT_KEYWORDS, MEDIUM_IMPACT_KEYWORDS, NEGATIVE_KEYWORDS};
-use super::model::{Article, ArticleCategory};
+use super::model::{Article, ArticleCategory, KeywordWeight, ScoreBreakdown};
 
-/// Calculates a relevance score for an article to filter out noise (e.g., Finance, Politics).
-/// Positive score: Keep/Promote. Negative score: Demote/Discard.
-pub fn calculate_relevance_score(article: &Article, user_interests: &[ArticleCategory]) -> i32 {
-    let mut score = 0;
-    // Optimization: Avoid allocating a new concatenated string. Check title and summary individually.
-    let title_lower = article.title.to_lowercase();
-    let summary_lower = article.summary.to_lowercase();
-
-    for word in HIGH_IMPACT_KEYWORDS.iter() {
-        if title_lower.contains(word) || summary_lower.contains(word) {
-            score += 10;
+// Keyword weights live in the `keyword_weights` table (see `SEED_KEYWORD_WEIGHTS`)
+const INTEREST_BONUS: i32 = 50; // Huge boost for explicit selection
+const CORE_CATEGORY_BONUS: i32 = 5;
+const OTHER_CATEGORY_BONUS: i32 = 2;
+const FEEDBACK_PENALTY: i32 = -1000;
+
+/// Weight change per rated article for every term it contains
+pub const FEEDBACK_STEP: f64 = 2.0;
+/// Learned adjustments lose half their strength after this many days without feedback
+pub const DECAY_HALF_LIFE_DAYS: f64 = 30.0;
+/// Bound for learned adjustments, so a single topic can't dominate forever
+pub const MAX_ADJUSTMENT: f64 = 40.0;
+/// Learned terms kept at most; the weakest are forgotten first
+pub const MAX_LEARNED_TERMS: usize = 500;
+/// Weights closer to zero than this don't count, and learned terms this weak are forgotten
+pub const NEGLIGIBLE_WEIGHT: f64 = 0.5;
+/// Effective weights at or above this are reported as high-impact keywords
+const HIGH_IMPACT_THRESHOLD: f64 = 7.5;
+
+const STOPWORDS: &[&str] = &[
+    "about", "after", "and", "are", "but", "can", "for", "from", "have", "how", "into", "its",
+    "new", "not", "now", "our", "out", "the", "this", "that", "what", "when", "why", "will",
+    "with", "you", "your",
+];
+
+/// Endings a word may add to a term and still count as it (e.g. "investing" for "invest")
+const INFLECTIONS: &[&str] = &[
+    "s", "es", "ed", "ing", "er", "ers", "or", "ors", "ment", "ments",
+];
+
+/// Lowercase words of `text`, split on anything that isn't a letter or digit
+fn words(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn word_matches(word: &str, term_word: &str) -> bool {
+    word.strip_prefix(term_word)
+        .is_some_and(|rest| rest.is_empty() || INFLECTIONS.contains(&rest))
+}
+
+/// Whether `term` occurs in `words` as whole words, so "art" matches "arts" but not "start"
+fn mentions(words: &[String], term: &str) -> bool {
+    let term_words: Vec<&str> = term
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+    !term_words.is_empty()
+        && words.windows(term_words.len()).any(|window| {
+            window
+                .iter()
+                .zip(&term_words)
+                .all(|(word, term_word)| word_matches(word, term_word))
+        })
+}
+
+/// The seeded weights from `SEED_KEYWORD_WEIGHTS` without any learning, for scoring outside
+/// the database
+#[cfg(test)]
+pub fn default_weights() -> Vec<KeywordWeight> {
+    crate::db::migrations::SEED_KEYWORD_WEIGHTS
+        .iter()
+        .map(|&(term, weight)| KeywordWeight {
+            term: term.to_string(),
+            weight,
+            base_weight: weight,
+            adjustment: 0.0,
+            feedback_count: 0,
+            updated_at: None,
+        })
+        .collect()
+}
+
+/// How much of a learned adjustment made at `updated_at` is left at `now` (exponential half-life decay).
+pub fn decay_factor(updated_at: Option<&str>, now: &str) -> f64 {
+    let parse = |s: &str| chrono::DateTime::parse_from_rfc3339(s).ok();
+    match (updated_at.and_then(parse), parse(now)) {
+        (Some(then), Some(now)) => {
+            let days = (now - then).num_seconds().max(0) as f64 / 86_400.0;
+            0.5_f64.powf(days / DECAY_HALF_LIFE_DAYS)
         }
+        _ => 1.0,
     }
-    for word in MEDIUM_IMPACT_KEYWORDS.iter() {
-        if title_lower.contains(word) || summary_lower.contains(word) {
-            score += 3;
+}
+
+/// Terms whose weights a rating of `article` should move: every known term it mentions,
+/// plus the significant words of its title so new interests can be learned.
+pub fn feedback_terms(article: &Article, known_terms: &[String]) -> Vec<String> {
+    let title_words = words(&article.title);
+    let summary_words = words(&article.summary);
+
+    let mut terms: Vec<String> = known_terms
+        .iter()
+        .filter(|t| mentions(&title_words, t) || mentions(&summary_words, t))
+        .cloned()
+        .collect();
+
+    for word in title_words {
+        if word.chars().count() < 3
+            || word.chars().all(|c| c.is_ascii_digit())
+            || STOPWORDS.contains(&word.as_str())
+        {
+            continue;
         }
+        // Skip words already covered by a term (e.g. "investing" via "invest")
+        if terms.iter().any(|t| word_matches(&word, t)) {
+            continue;
+        }
+        terms.push(word);
     }
-    for word in NEGATIVE_KEYWORDS.iter() {
-        if title_lower.contains(word) || summary_lower.contains(word) {
-            score -= 20; // Strong penalty
+
+    terms
+}
+
+/// How far a rating moves its terms. Rating an article the same way again moves nothing;
+/// changing a rating first takes back the step of the old one.
+pub fn feedback_delta(previous: Option<bool>, helpful: bool) -> f64 {
+    let step = |helpful: bool| {
+        if helpful {
+            FEEDBACK_STEP
+        } else {
+            -FEEDBACK_STEP
+        }
+    };
+    step(helpful) - previous.map_or(0.0, step)
+}
+
+/// Calculates a relevance score for an article to filter out noise (e.g., Finance, Politics).
+/// Positive score: Keep/Promote. Negative score: Demote/Discard.
+/// Keyword contributions come from `weights` (seeded from `config`, then learned from feedback);
+/// the breakdown lists every keyword and bonus that contributed to `total`.
+pub fn calculate_relevance_score(
+    article: &Article,
+    user_interests: &[ArticleCategory],
+    weights: &[KeywordWeight],
+) -> ScoreBreakdown {
+    let mut breakdown = ScoreBreakdown::default();
+    // Title and summary are matched separately so a phrase can't span the two
+    let title_words = words(&article.title);
+    let summary_words = words(&article.summary);
+
+    let mut keyword_score = 0.0;
+    for keyword in weights {
+        let term = keyword.term.as_str();
+        // Terms that decayed back to nothing don't count either way
+        if keyword.weight.abs() < NEGLIGIBLE_WEIGHT
+            || !(mentions(&title_words, term) || mentions(&summary_words, term))
+        {
+            continue;
+        }
+        keyword_score += keyword.weight;
+        if keyword.weight >= HIGH_IMPACT_THRESHOLD {
+            breakdown.high_keywords.push(keyword.term.clone());
+        } else if keyword.weight > 0.0 {
+            breakdown.medium_keywords.push(keyword.term.clone());
+        } else {
+            breakdown.negative_keywords.push(keyword.term.clone());
         }
     }
+    breakdown.keyword_score = keyword_score.round() as i32;
 
     // Category Bonus using Tags
     for tag in &article.tags {
         // 1. Explicit User Interest Bonus (Primary Filter)
         if user_interests.contains(tag) {
-            score += 50; // Huge boost for explicit selection
+            breakdown.interest_bonus += INTEREST_BONUS;
         }
 
         // 2. General Tech Bonus
@@ -38,13 +177,13 @@ pub fn calculate_relevance_score(article: &Article, user_interests: &[ArticleCat
             | ArticleCategory::Tauri
             | ArticleCategory::React
             | ArticleCategory::Android => {
-                score += 5;
+                breakdown.category_bonus += CORE_CATEGORY_BONUS;
             }
             ArticleCategory::General => {
                 // No bonus
             }
             _ => {
-                score += 2;
+                breakdown.category_bonus += OTHER_CATEGORY_BONUS;
             }
         }
     }
@@ -52,16 +191,20 @@ pub fn calculate_relevance_score(article: &Article, user_interests: &[ArticleCat
     // Feedback Logic (User Override)
     // If feedback exists (Positive or Negative), consider it "Read/Processed" and remove from recommendations.
     if article.feedback.is_some() {
-        score -= 1000;
+        breakdown.feedback_penalty = FEEDBACK_PENALTY;
     }
 
-    score
+    breakdown.total = breakdown.keyword_score
+        + breakdown.interest_bonus
+        + breakdown.category_bonus
+        + breakdown.feedback_penalty;
+    breakdown
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::features::recommendation::model::Feedback;
+    use crate::features::recommendation::model::{ArticleState, Feedback};
 
     #[test]
     fn test_feedback_scoring_internal() {
@@ -80,6 +223,8 @@ mod tests {
             }),
             image_url: None,
             author: None,
+            state: ArticleState::Unread,
+            server_rating: None,
         };
 
         // Case: Upvoted (Already Read) article
@@ -97,10 +242,12 @@ mod tests {
             }),
             image_url: None,
             author: None,
+            state: ArticleState::Unread,
+            server_rating: None,
         };
 
-        let s1 = calculate_relevance_score(&downvoted_article, &[]);
-        let s2 = calculate_relevance_score(&upvoted_article, &[]);
+        let s1 = calculate_relevance_score(&downvoted_article, &[], &default_weights()).total;
+        let s2 = calculate_relevance_score(&upvoted_article, &[], &default_weights()).total;
 
         assert!(
             s1 < -500,
@@ -111,4 +258,157 @@ mod tests {
             "Upvoted article should also be hidden (treated as read)"
         );
     }
+
+    #[test]
+    fn test_score_breakdown() {
+        let article = Article {
+            id: "1".into(),
+            title: "Rust and WebAssembly in the browser".into(),
+            summary: "A developer guide, plus a word on crypto.".into(),
+            url: "".into(),
+            tags: vec![ArticleCategory::Rust, ArticleCategory::Web],
+            published_at: "".into(),
+            feedback: None,
+            image_url: None,
+            author: None,
+            state: ArticleState::Unread,
+            server_rating: None,
+        };
+
+        let score =
+            calculate_relevance_score(&article, &[ArticleCategory::Web], &default_weights());
+
+        assert_eq!(score.high_keywords, vec!["rust", "webassembly"]);
+        assert_eq!(score.medium_keywords, vec!["developer"]);
+        assert_eq!(score.negative_keywords, vec!["crypto"]);
+        assert_eq!(score.keyword_score, 10 + 10 + 3 - 20);
+        assert_eq!(score.interest_bonus, 50);
+        assert_eq!(score.category_bonus, 5 + 2);
+        assert_eq!(score.feedback_penalty, 0);
+        assert_eq!(score.total, 3 + 50 + 7);
+    }
+
+    fn article(title: &str, summary: &str) -> Article {
+        Article {
+            id: "1".into(),
+            title: title.into(),
+            summary: summary.into(),
+            url: "".into(),
+            tags: vec![],
+            published_at: "".into(),
+            feedback: None,
+            image_url: None,
+            author: None,
+            state: ArticleState::Unread,
+            server_rating: None,
+        }
+    }
+
+    #[test]
+    fn test_learned_weights_override_seed() {
+        let crypto = article("Building a crypto wallet engine", "Zero-copy parsing.");
+        assert_eq!(
+            calculate_relevance_score(&crypto, &[], &default_weights()).total,
+            -20
+        );
+
+        // A crypto-engineering fan has pushed the penalty past zero
+        let mut weights = default_weights();
+        let entry = weights.iter_mut().find(|w| w.term == "crypto").unwrap();
+        entry.adjustment = 28.0;
+        entry.weight = 8.0;
+        weights.push(KeywordWeight {
+            term: "wallet".into(),
+            weight: 4.0,
+            base_weight: 0.0,
+            adjustment: 4.0,
+            feedback_count: 2,
+            updated_at: None,
+        });
+
+        let score = calculate_relevance_score(&crypto, &[], &weights);
+        assert_eq!(score.high_keywords, vec!["crypto"]);
+        assert_eq!(score.medium_keywords, vec!["wallet"]);
+        assert!(score.negative_keywords.is_empty());
+        assert_eq!(score.total, 12);
+    }
+
+    #[test]
+    fn test_decay_factor() {
+        let now = "2025-03-01T00:00:00+00:00";
+        assert_eq!(decay_factor(Some(now), now), 1.0);
+        assert!((decay_factor(Some("2025-01-30T00:00:00+00:00"), now) - 0.5).abs() < 1e-9);
+        assert!((decay_factor(Some("2024-12-31T00:00:00+00:00"), now) - 0.25).abs() < 1e-9);
+        // Never updated or unparsable timestamps don't decay
+        assert_eq!(decay_factor(None, now), 1.0);
+        assert_eq!(decay_factor(Some("yesterday"), now), 1.0);
+    }
+
+    #[test]
+    fn test_feedback_terms() {
+        let known: Vec<String> = vec!["rust".into(), "invest".into(), "compiler".into()];
+        let a = article(
+            "Investing in the Rust 2024 compiler: what's new",
+            "Rust compiler internals.",
+        );
+
+        assert_eq!(
+            feedback_terms(&a, &known),
+            vec!["rust", "invest", "compiler"]
+        );
+
+        let a = article("Zero-knowledge proofs with Halo2", "");
+        assert_eq!(
+            feedback_terms(&a, &known),
+            vec!["zero", "knowledge", "proofs", "halo2"]
+        );
+
+        // Known terms only match whole words
+        let known: Vec<String> = vec!["art".into(), "design pattern".into()];
+        let a = article("Starting a party", "Design patterns for arts");
+        assert_eq!(
+            feedback_terms(&a, &known),
+            vec!["art", "design pattern", "starting", "party"]
+        );
+    }
+
+    #[test]
+    fn test_keywords_match_whole_words() {
+        let mut weights = default_weights();
+        weights.push(KeywordWeight {
+            term: "art".into(),
+            weight: 4.0,
+            base_weight: 0.0,
+            adjustment: 4.0,
+            feedback_count: 2,
+            updated_at: None,
+        });
+
+        let score = calculate_relevance_score(
+            &article("Start the party", "Encoded codecs and restocking"),
+            &[],
+            &weights,
+        );
+        assert_eq!(score.keyword_score, 0);
+
+        let score = calculate_relevance_score(
+            &article("Investing in arts", "Stocks, code and design patterns"),
+            &[],
+            &weights,
+        );
+        assert_eq!(score.medium_keywords, vec!["code", "design pattern", "art"]);
+        assert_eq!(score.negative_keywords, vec!["stock", "invest"]);
+    }
+
+    #[test]
+    fn test_feedback_delta() {
+        assert_eq!(feedback_delta(None, true), FEEDBACK_STEP);
+        assert_eq!(feedback_delta(None, false), -FEEDBACK_STEP);
+        // Rating again the same way changes nothing
+        assert_eq!(feedback_delta(Some(true), true), 0.0);
+        assert_eq!(feedback_delta(Some(false), false), 0.0);
+        // Changing a rating undoes the old step
+        assert_eq!(feedback_delta(Some(true), false), -2.0 * FEEDBACK_STEP);
+        assert_eq!(feedback_delta(Some(false), true), 2.0 * FEEDBACK_STEP);
+    }
 }
@@ -1,3 +1,4 @@
+use super::llm::{LlmConfig, LlmProvider};
 use super::model::{UserPersona, UserPreferences};
 use super::repository::RecommendationRepository;
 use std::fs;
@@ -9,14 +10,26 @@ pub struct RecommendationState {
     pub repo: Arc<dyn RecommendationRepository>,
     pub persona: Mutex<UserPersona>,
     pub client: reqwest::Client,
+    /// `None` when no LLM is configured; recommendations then fall back to scoring only
+    pub llm: Option<Arc<dyn LlmProvider>>,
 }
 
 impl RecommendationState {
     pub fn new(repo: Arc<dyn RecommendationRepository>) -> Self {
+        let client = reqwest::Client::new();
+        let llm = match LlmConfig::from_env() {
+            Ok(config) => config.build(client.clone()),
+            Err(e) => {
+                eprintln!("Invalid LLM configuration: {}", e);
+                None
+            }
+        };
+
         Self {
             repo,
             persona: Mutex::new(UserPersona::default()),
-            client: reqwest::Client::new(),
+            client,
+            llm,
         }
     }
 
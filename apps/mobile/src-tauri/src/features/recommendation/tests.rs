@@ -1,9 +1,16 @@
 #![cfg(test)]
 
 use crate::features::recommendation::{
-    model::{Article, ArticleCategory},
-    scoring::calculate_relevance_score,
+    ai::AIService,
+    llm::{GeminiProvider, LlmProvider, OllamaProvider},
+    model::{
+        Article, ArticleCategory, ArticleState, FeedState, Feedback, RecommendationOutcome,
+        UserPersona,
+    },
+    scoring::{calculate_relevance_score, default_weights},
+    service::fetch_feed,
 };
+use crate::test_utils::{MockResponse, MockServer};
 
 #[test]
 fn test_scoring_system() {
@@ -18,6 +25,8 @@ fn test_scoring_system() {
         feedback: None,
         image_url: None,
         author: None,
+        state: ArticleState::Unread,
+        server_rating: None,
     };
 
     // Case 2: Noise (Finance/Stock)
@@ -31,6 +40,8 @@ fn test_scoring_system() {
         feedback: None,
         image_url: None,
         author: None,
+        state: ArticleState::Unread,
+        server_rating: None,
     };
 
     // Case 3: Mixed (General Tech)
@@ -44,16 +55,21 @@ fn test_scoring_system() {
         feedback: None,
         image_url: None,
         author: None,
+        state: ArticleState::Unread,
+        server_rating: None,
     };
 
-    let s1 = calculate_relevance_score(&rust_article, &[]);
-    let s2 = calculate_relevance_score(&stock_article, &[]);
-    let s3 = calculate_relevance_score(&tech_article, &[]);
-
-    println!("Rust Score: {}", s1);
-    println!("Stock Score: {}", s2);
-    println!("Tech Score: {}", s3);
+    let weights = default_weights();
+    let s1 = calculate_relevance_score(&rust_article, &[], &weights).total;
+    let s2 = calculate_relevance_score(&stock_article, &[], &weights).total;
+    let s3 = calculate_relevance_score(&tech_article, &[], &weights).total;
 
+    // rust + core category bonus
+    assert_eq!(s1, 10 + 5);
+    // buffett, berkshire, stock and market
+    assert_eq!(s2, -4 * 20);
+    // code, developer and refactoring
+    assert_eq!(s3, 3 * 3);
     assert!(
         s1 > s3,
         "Rust specific should score higher than general code"
@@ -64,3 +80,295 @@ fn test_scoring_system() {
     );
     assert!(s2 < 0, "Stock market noise should have negative score");
 }
+
+#[test]
+fn test_fetch_feed_conditional_get() {
+    let feed = include_str!("fixtures/rss.xml");
+    let server = MockServer::start(move |req| {
+        if req.header("If-None-Match") == Some("\"v1\"") {
+            MockResponse::new(304, "")
+        } else {
+            MockResponse::new(200, feed)
+                .with_header("ETag", "\"v1\"")
+                .with_header("Last-Modified", "Tue, 07 Jan 2025 10:00:00 GMT")
+        }
+    });
+    let url = format!("{}/feed.xml", server.url);
+    let client = reqwest::Client::new();
+
+    tauri::async_runtime::block_on(async {
+        // First fetch: full body, validators returned
+        let first = fetch_feed(&url, ArticleCategory::Rust, &client, None)
+            .await
+            .unwrap();
+        assert_eq!(first.status, 200);
+        assert_eq!(first.articles.len(), 2);
+
+        let mut state = FeedState::new(&url);
+        state.record_success(first.status, first.etag, first.last_modified, "t1");
+        assert_eq!(state.etag.as_deref(), Some("\"v1\""));
+
+        // Second fetch: validators sent, 304 means nothing new
+        let second = fetch_feed(&url, ArticleCategory::Rust, &client, Some(&state))
+            .await
+            .unwrap();
+        assert_eq!(second.status, 304);
+        assert!(second.articles.is_empty());
+
+        // 304 without validators keeps the stored ones
+        state.record_success(second.status, second.etag, second.last_modified, "t2");
+        assert_eq!(state.etag.as_deref(), Some("\"v1\""));
+        assert_eq!(state.last_success_at.as_deref(), Some("t2"));
+
+        // A full response without validators drops the stale ones
+        state.record_success(200, None, None, "t3");
+        assert_eq!(state.etag, None);
+        assert_eq!(state.last_modified, None);
+    });
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].method, "GET");
+    assert_eq!(requests[0].path, "/feed.xml");
+    assert_eq!(requests[0].header("If-None-Match"), None);
+    assert_eq!(
+        requests[1].header("If-Modified-Since"),
+        Some("Tue, 07 Jan 2025 10:00:00 GMT")
+    );
+}
+
+#[test]
+fn test_fetch_feed_error_status() {
+    let server = MockServer::start(|_| MockResponse::new(503, "down"));
+    let client = reqwest::Client::new();
+
+    let result = tauri::async_runtime::block_on(fetch_feed(
+        &server.url,
+        ArticleCategory::General,
+        &client,
+        None,
+    ));
+    let err = result.unwrap_err();
+    assert_eq!(err.status, Some(503));
+
+    let mut state = FeedState::new(&server.url);
+    state.record_failure(err.status, &err.message, "t1");
+    state.record_failure(err.status, &err.message, "t2");
+    assert_eq!(state.consecutive_failures, 2);
+    assert!(state.last_success_at.is_none());
+
+    state.record_success(200, None, None, "t3");
+    assert_eq!(state.consecutive_failures, 0);
+    assert!(state.last_error.is_none());
+}
+
+/// Replies with canned answers (or errors) in order, repeating the last one, and keeps the prompts it was given.
+struct FakeProvider {
+    replies: Vec<Result<String, String>>,
+    prompts: std::sync::Mutex<Vec<String>>,
+}
+
+impl FakeProvider {
+    fn new(reply: Result<&str, &str>) -> Self {
+        Self::sequence(&[reply])
+    }
+
+    fn sequence(replies: &[Result<&str, &str>]) -> Self {
+        Self {
+            replies: replies
+                .iter()
+                .map(|r| r.map(|s| s.to_string()).map_err(|e| e.to_string()))
+                .collect(),
+            prompts: std::sync::Mutex::new(Vec::new()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl LlmProvider for FakeProvider {
+    fn name(&self) -> &'static str {
+        "fake"
+    }
+
+    fn model(&self) -> &str {
+        "fake-model"
+    }
+
+    async fn generate(&self, prompt: &str) -> Result<String, String> {
+        let mut prompts = self.prompts.lock().unwrap();
+        prompts.push(prompt.to_string());
+        let i = (prompts.len() - 1).min(self.replies.len() - 1);
+        self.replies[i].clone()
+    }
+}
+
+fn candidate(id: &str) -> Article {
+    Article {
+        id: id.into(),
+        title: format!("Article {}", id),
+        summary: "Summary".into(),
+        url: format!("https://example.com/{}", id),
+        tags: vec![ArticleCategory::Rust],
+        published_at: "".into(),
+        feedback: None,
+        image_url: None,
+        author: None,
+        state: ArticleState::Unread,
+        server_rating: None,
+    }
+}
+
+#[test]
+fn test_recommend_with_llm_uses_selected_ids() {
+    let candidates: Vec<Article> = (1..=6).map(|i| candidate(&i.to_string())).collect();
+    let provider = FakeProvider::new(Ok(
+        "```json\n[{\"id\": \"5\", \"reason\": \"Matches the Rust tag\"}, \"2\"]\n```",
+    ));
+
+    let selection = tauri::async_runtime::block_on(AIService::recommend_with_llm(
+        candidates,
+        &UserPersona::default(),
+        &[ArticleCategory::Rust],
+        &provider,
+    ));
+
+    let ids: Vec<&str> = selection.articles.iter().map(|a| a.id.as_str()).collect();
+    assert_eq!(ids, vec!["5", "2"]);
+    assert_eq!(selection.outcome, RecommendationOutcome::Ai);
+    assert_eq!(selection.attempts, 1);
+    assert_eq!(selection.reasons.len(), 1);
+    assert_eq!(selection.reasons["5"], "Matches the Rust tag");
+    let prompts = provider.prompts.lock().unwrap();
+    assert_eq!(prompts.len(), 1);
+    assert!(prompts[0].contains("USER SELECTED TAGS"));
+}
+
+#[test]
+fn test_recommend_with_llm_falls_back_on_error() {
+    let candidates: Vec<Article> = (1..=6).map(|i| candidate(&i.to_string())).collect();
+    let provider = FakeProvider::new(Err("timeout"));
+
+    let selection = tauri::async_runtime::block_on(AIService::recommend_with_llm(
+        candidates,
+        &UserPersona::default(),
+        &[],
+        &provider,
+    ));
+
+    let ids: Vec<&str> = selection.articles.iter().map(|a| a.id.as_str()).collect();
+    assert_eq!(ids, vec!["1", "2", "3", "4"]);
+    assert_eq!(selection.outcome, RecommendationOutcome::Error);
+    // Transport errors aren't retried
+    assert_eq!(selection.attempts, 1);
+    assert_eq!(selection.error.as_deref(), Some("timeout"));
+}
+
+#[test]
+fn test_recommend_with_llm_error_never_contains_api_key() {
+    let offline = {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        format!("http://{}", listener.local_addr().unwrap())
+    };
+    let garbled = MockServer::start(|_| MockResponse::json(200, "{not json"));
+
+    for url in [offline, garbled.url.clone()] {
+        let provider = GeminiProvider::new(
+            reqwest::Client::new(),
+            &url,
+            "gemini-test",
+            "secret-api-key",
+        );
+        let candidates: Vec<Article> = (1..=6).map(|i| candidate(&i.to_string())).collect();
+        let selection = tauri::async_runtime::block_on(AIService::recommend_with_llm(
+            candidates,
+            &UserPersona::default(),
+            &[],
+            &provider,
+        ));
+
+        assert_eq!(selection.outcome, RecommendationOutcome::Error);
+        let error = selection.error.unwrap();
+        assert!(!error.is_empty());
+        assert!(!error.contains("secret-api-key"), "{}", error);
+    }
+}
+
+#[test]
+fn test_recommend_with_llm_retries_with_corrective_prompt() {
+    let candidates: Vec<Article> = (1..=6).map(|i| candidate(&i.to_string())).collect();
+    let provider = FakeProvider::sequence(&[
+        Ok("I'd recommend articles 2 and 9!"),
+        Ok(r#"Here you go: {"ids": ["3", "1"]}"#),
+    ]);
+
+    let selection = tauri::async_runtime::block_on(AIService::recommend_with_llm(
+        candidates,
+        &UserPersona::default(),
+        &[],
+        &provider,
+    ));
+
+    let ids: Vec<&str> = selection.articles.iter().map(|a| a.id.as_str()).collect();
+    assert_eq!(ids, vec!["3", "1"]);
+    assert_eq!(selection.outcome, RecommendationOutcome::Ai);
+    assert_eq!(selection.attempts, 2);
+
+    let prompts = provider.prompts.lock().unwrap();
+    assert!(prompts[1].starts_with(prompts[0].as_str()));
+    assert!(prompts[1].contains("I'd recommend articles 2 and 9!"));
+    assert!(prompts[1].contains("rejected"));
+}
+
+#[test]
+fn test_recommend_with_llm_falls_back_after_invalid_retry() {
+    let candidates: Vec<Article> = (1..=6).map(|i| candidate(&i.to_string())).collect();
+    // Second answer references an article that isn't a candidate
+    let provider = FakeProvider::sequence(&[Ok("no idea"), Ok(r#"["1", "99"]"#)]);
+
+    let selection = tauri::async_runtime::block_on(AIService::recommend_with_llm(
+        candidates,
+        &UserPersona::default(),
+        &[],
+        &provider,
+    ));
+
+    let ids: Vec<&str> = selection.articles.iter().map(|a| a.id.as_str()).collect();
+    assert_eq!(ids, vec!["1", "2", "3", "4"]);
+    assert_eq!(selection.outcome, RecommendationOutcome::Fallback);
+    assert_eq!(selection.attempts, 2);
+    assert!(selection.error.unwrap().contains("99"));
+    assert_eq!(provider.prompts.lock().unwrap().len(), 2);
+}
+
+#[test]
+fn test_update_user_persona_through_provider() {
+    let server = MockServer::start(|_| {
+        MockResponse::json(
+            200,
+            r#"{"response":"  Prefers Rust async internals over finance news.  ","done":true}"#,
+        )
+    });
+    let provider = OllamaProvider::new(reqwest::Client::new(), &server.url, "llama-test");
+    let feedback = vec![Feedback {
+        is_helpful: true,
+        reason: "Loved the async deep dive".into(),
+        created_at: "2024-01-01T00:00:00Z".into(),
+    }];
+
+    let persona = tauri::async_runtime::block_on(AIService::update_user_persona(
+        &feedback,
+        &UserPersona::default(),
+        &provider,
+    ))
+    .unwrap();
+
+    assert_eq!(
+        persona.description,
+        "Prefers Rust async internals over finance news."
+    );
+    let prompt = server.requests()[0].json()["prompt"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert!(prompt.contains("Loved the async deep dive"));
+}
@@ -0,0 +1,99 @@
+use crate::error::AppError;
+use crate::features::recommendation::model::Article;
+use crate::features::recommendation::repository::RecommendationRepository;
+use crate::features::sync::model::{BulkSyncReport, OutboxOperation, SyncFailure, SyncProgress};
+use crate::features::sync::outbox::{next_attempt_at, outbox_timestamp};
+use crate::features::sync::repository::SyncRepository;
+use crate::features::sync::service::SyncService;
+use futures::stream::{self, StreamExt};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Uploads running at the same time during `sync_all`
+pub const SYNC_ALL_CONCURRENCY: usize = 4;
+pub const SYNC_PROGRESS_EVENT: &str = "sync-all-progress";
+
+/// Uploads `articles` with at most `SYNC_ALL_CONCURRENCY` requests in flight, calling
+/// `on_progress` as each one finishes.
+///
+/// Setting `cancel` stops new uploads from starting; those already in flight still complete.
+/// A network or auth error stops the run the same way, since every other upload would fail too.
+/// Failed uploads are queued in the outbox and count as a failed attempt there, so they are
+/// retried with the same backoff and attempt limit as a failed replay.
+pub async fn upload_articles(
+    articles: Vec<Article>,
+    repo: &dyn RecommendationRepository,
+    outbox: &dyn SyncRepository,
+    service: &SyncService,
+    cancel: &AtomicBool,
+    on_progress: impl Fn(SyncProgress),
+) -> Result<BulkSyncReport, AppError> {
+    let mut report = BulkSyncReport {
+        total: articles.len() as u32,
+        ..Default::default()
+    };
+    let unreachable = AtomicBool::new(false);
+    let unreachable = &unreachable;
+
+    let mut uploads = stream::iter(articles)
+        .map(|article| async move {
+            if cancel.load(Ordering::SeqCst) || unreachable.load(Ordering::SeqCst) {
+                return (article, None);
+            }
+            let result = service.sync_article(&article).await;
+            if matches!(
+                result,
+                Err(AppError::Network(_) | AppError::Unauthorized(_))
+            ) {
+                unreachable.store(true, Ordering::SeqCst);
+            }
+            (article, Some(result))
+        })
+        .buffer_unordered(SYNC_ALL_CONCURRENCY);
+
+    while let Some((article, result)) = uploads.next().await {
+        match result {
+            None => report.skipped += 1,
+            Some(Ok(server_article_id)) => {
+                repo.mark_article_synced(&article.id, &server_article_id)?;
+                report.succeeded += 1;
+            }
+            Some(Err(e)) => {
+                record_upload_failure(outbox, &article.id, &e)?;
+                report.failed += 1;
+                report.failures.push(SyncFailure {
+                    article_id: article.id,
+                    error: e.to_string(),
+                });
+            }
+        }
+
+        on_progress(SyncProgress {
+            done: report.succeeded + report.failed + report.skipped,
+            total: report.total,
+            failed: report.failed,
+        });
+    }
+
+    report.cancelled = cancel.load(Ordering::SeqCst);
+    report.interrupted = unreachable.load(Ordering::SeqCst);
+    Ok(report)
+}
+
+/// Counts a failed upload against the article's outbox entry, adding one if it has none.
+/// An entry that is already pending keeps its attempt count instead of being reset.
+fn record_upload_failure(
+    outbox: &dyn SyncRepository,
+    article_id: &str,
+    error: &AppError,
+) -> Result<(), AppError> {
+    let now = chrono::Utc::now();
+    let operation = OutboxOperation::CreateArticle {
+        article_id: article_id.to_string(),
+    };
+    let (id, attempts) = match outbox.get_pending(&operation)? {
+        Some(entry) => (entry.id, entry.attempts),
+        None => (outbox.enqueue(&operation, &outbox_timestamp(now))?, 0),
+    };
+    let next_attempt_at = next_attempt_at(attempts + 1, error, now);
+    outbox.record_failure(id, &error.to_string(), next_attempt_at.as_deref())
+}
@@ -1,9 +1,11 @@
-use reqwest::Client;
+use crate::error::AppError;
+use reqwest::{Client, Method, RequestBuilder, Response, StatusCode};
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
-const DEFAULT_SERVER_URL: &str = "http://localhost:3000";
 const REQUEST_TIMEOUT_SECS: u64 = 5;
+/// Lets the server recognise a retried request whose first attempt it already applied
+const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
 
 #[derive(Debug, Serialize)]
 pub struct CreateArticleRequest {
@@ -30,6 +32,37 @@ pub struct CreateArticleResponse {
     pub negative_count: i32,
 }
 
+/// `ArticleMetadata` as listed by `GET /articles`
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerArticle {
+    pub id: String,
+    pub title: String,
+    pub url: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub rating: f64,
+    pub positive_count: i64,
+    pub negative_count: i64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Pagination {
+    pub page: u32,
+    pub limit: u32,
+    pub total: u32,
+    pub total_pages: u32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ArticleListResponse {
+    pub articles: Vec<ServerArticle>,
+    pub pagination: Pagination,
+}
+
 #[derive(Debug, Serialize)]
 pub struct SubmitFeedbackRequest {
     #[serde(rename = "type")]
@@ -42,42 +75,73 @@ pub struct SubmitFeedbackRequest {
 pub struct BrainServerClient {
     client: Client,
     base_url: String,
+    token: Option<String>,
 }
 
 impl BrainServerClient {
-    pub fn new(client: Client) -> Self {
-        let base_url =
-            std::env::var("BRAIN_SERVER_URL").unwrap_or_else(|_| DEFAULT_SERVER_URL.to_string());
+    pub fn with_base_url(client: Client, base_url: &str) -> Self {
+        Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token: None,
+        }
+    }
+
+    /// Sends `Authorization: Bearer <token>` with every API request
+    pub fn with_token(mut self, token: Option<String>) -> Self {
+        self.token = token;
+        self
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let builder = self
+            .client
+            .request(method, format!("{}{}", self.base_url, path))
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS));
+        match &self.token {
+            Some(token) => builder.bearer_auth(token),
+            None => builder,
+        }
+    }
 
-        Self { client, base_url }
+    /// Turns error statuses into `AppError`s; 401 means the token is missing or wrong
+    async fn check_status(response: Response) -> Result<Response, AppError> {
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let body = response.text().await.unwrap_or_default();
+        if status == StatusCode::UNAUTHORIZED {
+            return Err(AppError::Unauthorized(format!(
+                "Brain Server rejected the API token: {}",
+                body
+            )));
+        }
+        Err(AppError::Server {
+            status: status.as_u16(),
+            body,
+        })
     }
 
     /// Create an article on the Brain Server
     pub async fn create_article(
         &self,
         request: CreateArticleRequest,
-    ) -> Result<CreateArticleResponse, String> {
-        let url = format!("{}/articles", self.base_url);
-
+        idempotency_key: &str,
+    ) -> Result<CreateArticleResponse, AppError> {
         let response = self
-            .client
-            .post(&url)
-            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .request(Method::POST, "/articles")
+            .header(IDEMPOTENCY_KEY_HEADER, idempotency_key)
             .json(&request)
             .send()
             .await
-            .map_err(|e| format!("Failed to send request: {}", e))?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            return Err(format!("Server returned error {}: {}", status, body));
-        }
+            .map_err(|e| AppError::Network(format!("Failed to send request: {}", e)))?;
 
-        response
+        Self::check_status(response)
+            .await?
             .json::<CreateArticleResponse>()
             .await
-            .map_err(|e| format!("Failed to parse response: {}", e))
+            .map_err(|e| AppError::InvalidResponse(format!("Failed to parse response: {}", e)))
     }
 
     /// Submit feedback for an article on the Brain Server
@@ -86,9 +150,8 @@ impl BrainServerClient {
         server_article_id: &str,
         helpful: bool,
         reason: Option<&str>,
-    ) -> Result<(), String> {
-        let url = format!("{}/articles/{}/feedback", self.base_url, server_article_id);
-
+        idempotency_key: &str,
+    ) -> Result<(), AppError> {
         let request = SubmitFeedbackRequest {
             feedback_type: if helpful {
                 "positive".to_string()
@@ -99,34 +162,49 @@ impl BrainServerClient {
         };
 
         let response = self
-            .client
-            .post(&url)
-            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .request(
+                Method::POST,
+                &format!("/articles/{}/feedback", server_article_id),
+            )
+            .header(IDEMPOTENCY_KEY_HEADER, idempotency_key)
             .json(&request)
             .send()
             .await
-            .map_err(|e| format!("Failed to send feedback: {}", e))?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            return Err(format!("Server returned error {}: {}", status, body));
-        }
+            .map_err(|e| AppError::Network(format!("Failed to send feedback: {}", e)))?;
 
+        Self::check_status(response).await?;
         Ok(())
     }
 
-    /// Check if the Brain Server is healthy
-    pub async fn health_check(&self) -> bool {
-        let url = format!("{}/", self.base_url);
+    /// List one page of articles, only those changed after `updated_since` when given
+    pub async fn list_articles(
+        &self,
+        updated_since: Option<&str>,
+        page: u32,
+        limit: u32,
+    ) -> Result<ArticleListResponse, AppError> {
+        let mut query = vec![("page", page.to_string()), ("limit", limit.to_string())];
+        if let Some(since) = updated_since {
+            query.push(("updatedSince", since.to_string()));
+        }
 
-        match self
-            .client
-            .get(&url)
-            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        let response = self
+            .request(Method::GET, "/articles")
+            .query(&query)
             .send()
             .await
-        {
+            .map_err(|e| AppError::Network(format!("Failed to list articles: {}", e)))?;
+
+        Self::check_status(response)
+            .await?
+            .json::<ArticleListResponse>()
+            .await
+            .map_err(|e| AppError::InvalidResponse(format!("Failed to parse response: {}", e)))
+    }
+
+    /// Check if the Brain Server is healthy
+    pub async fn health_check(&self) -> bool {
+        match self.request(Method::GET, "/").send().await {
             Ok(response) => response.status().is_success(),
             Err(_) => false,
         }
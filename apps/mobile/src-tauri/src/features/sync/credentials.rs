@@ -0,0 +1,202 @@
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+pub const DEFAULT_SERVER_URL: &str = "http://localhost:3000";
+pub const CREDENTIALS_FILE: &str = "brain_server.json";
+
+/// Where the Brain Server is and how to authenticate with it.
+/// Unset fields fall back to `BRAIN_SERVER_URL` / `BRAIN_SERVER_TOKEN`.
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub struct ServerCredentials {
+    pub server_url: Option<String>,
+    pub api_token: Option<String>,
+}
+
+impl ServerCredentials {
+    pub fn base_url(&self) -> String {
+        self.server_url
+            .clone()
+            .or_else(|| std::env::var("BRAIN_SERVER_URL").ok())
+            .unwrap_or_else(|| DEFAULT_SERVER_URL.to_string())
+    }
+
+    pub fn token(&self) -> Option<String> {
+        self.api_token
+            .clone()
+            .or_else(|| std::env::var("BRAIN_SERVER_TOKEN").ok())
+            .filter(|t| !t.is_empty())
+    }
+
+    /// What the frontend may see: the URL in use and whether a token is set, never the token itself
+    pub fn info(&self) -> CredentialsInfo {
+        CredentialsInfo {
+            server_url: self.base_url(),
+            token_hint: self.token().map(|t| mask_token(&t)),
+            stored: self.api_token.is_some(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CredentialsInfo {
+    pub server_url: String,
+    /// Last characters of the token in use, e.g. `…f3a9`
+    pub token_hint: Option<String>,
+    /// The token comes from app config rather than the environment
+    pub stored: bool,
+}
+
+/// Checks a user-entered server URL. The token is sent with every request, so plain `http`
+/// is only allowed for a server on this device.
+pub fn validate_server_url(url: &str) -> Result<(), AppError> {
+    let parsed = reqwest::Url::parse(url)
+        .map_err(|_| AppError::Validation(format!("Invalid server URL: {}", url)))?;
+    let host = parsed.host_str().unwrap_or_default();
+    let local = host.eq_ignore_ascii_case("localhost")
+        || host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback());
+    match parsed.scheme() {
+        "https" => Ok(()),
+        "http" if local => Ok(()),
+        _ => Err(AppError::Validation(format!(
+            "Server URL must use https unless the server runs on this device: {}",
+            url
+        ))),
+    }
+}
+
+fn mask_token(token: &str) -> String {
+    let chars: Vec<char> = token.chars().collect();
+    if chars.len() <= 8 {
+        return "***".to_string();
+    }
+    format!("…{}", chars[chars.len() - 4..].iter().collect::<String>())
+}
+
+/// Credentials persisted as JSON in the app data directory, next to the user preferences.
+/// The token is stored in plain text, so the file is readable by its owner only (0600 on Unix);
+/// on Android and iOS the app data directory is private to the app as well.
+pub struct CredentialStore {
+    path: PathBuf,
+    current: RwLock<ServerCredentials>,
+}
+
+impl CredentialStore {
+    /// Reads `path` if it exists; a missing or unreadable file means no stored credentials
+    pub fn load(path: PathBuf) -> Self {
+        let current = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            current: RwLock::new(current),
+        }
+    }
+
+    pub fn get(&self) -> ServerCredentials {
+        self.current.read().unwrap().clone()
+    }
+
+    pub fn save(&self, credentials: ServerCredentials) -> Result<(), AppError> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        write_private(&self.path, &serde_json::to_string(&credentials)?)?;
+        *self.current.write().unwrap() = credentials;
+        Ok(())
+    }
+
+    pub fn clear(&self) -> Result<(), AppError> {
+        if self.path.exists() {
+            fs::remove_file(&self.path)?;
+        }
+        *self.current.write().unwrap() = ServerCredentials::default();
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+fn write_private(path: &Path, contents: &str) -> std::io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?;
+    // `mode` only applies when the file is created
+    file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    file.write_all(contents.as_bytes())
+}
+
+#[cfg(not(unix))]
+fn write_private(path: &Path, contents: &str) -> std::io::Result<()> {
+    fs::write(path, contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_credentials_persist_and_clear() {
+        let dir = std::env::temp_dir().join(format!("brain-credentials-{}", std::process::id()));
+        let path = dir.join(CREDENTIALS_FILE);
+        let _ = fs::remove_dir_all(&dir);
+
+        let store = CredentialStore::load(path.clone());
+        assert_eq!(store.get(), ServerCredentials::default());
+
+        let credentials = ServerCredentials {
+            server_url: Some("https://brain.example.com".into()),
+            api_token: Some("tok_1234567890abcdef".into()),
+        };
+        store.save(credentials.clone()).unwrap();
+        assert_eq!(CredentialStore::load(path.clone()).get(), credentials);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let info = credentials.info();
+        assert_eq!(info.server_url, "https://brain.example.com");
+        assert_eq!(info.token_hint.as_deref(), Some("…cdef"));
+        assert!(info.stored);
+
+        store.clear().unwrap();
+        assert!(!path.exists());
+        assert_eq!(store.get(), ServerCredentials::default());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_server_url_needs_https_off_device() {
+        for ok in [
+            "https://brain.example.com",
+            "http://localhost:3000",
+            "http://127.0.0.1:3000",
+            "http://[::1]:3000",
+        ] {
+            assert!(validate_server_url(ok).is_ok(), "{}", ok);
+        }
+        for bad in [
+            "http://brain.example.com",
+            "http://192.168.0.10:3000",
+            "ftp://brain.example.com",
+            "not a url",
+        ] {
+            assert!(validate_server_url(bad).is_err(), "{}", bad);
+        }
+    }
+}
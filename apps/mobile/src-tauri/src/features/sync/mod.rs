@@ -1,45 +1,206 @@
+pub mod bulk;
 pub mod client;
+pub mod credentials;
+pub mod model;
+pub mod outbox;
+pub mod pull;
+pub mod repository;
 pub mod service;
+#[cfg(test)]
+mod tests;
 
 use crate::error::AppError;
 use crate::features::recommendation::model::Article;
 use crate::features::recommendation::system::RecommendationState;
-use service::SyncService;
-use tauri::State;
+use credentials::{validate_server_url, CredentialsInfo, ServerCredentials};
+use model::{
+    BulkSyncReport, DrainReport, OutboxEntry, OutboxOperation, PullReport, SyncPolicy, SyncStatus,
+};
+use service::SyncState;
+use tauri::{Emitter, State};
 
-/// Sync an article to the Brain Server
+/// Sync an article to the Brain Server.
+/// The upload goes through the outbox, so an unreachable server leaves it `Queued` for a later retry.
 #[tauri::command]
 pub async fn sync_article_to_server(
     article: Article,
     state: State<'_, RecommendationState>,
-) -> Result<(), AppError> {
-    let sync_service = SyncService::new(state.client.clone());
-
+    sync_state: State<'_, SyncState>,
+) -> Result<SyncStatus, AppError> {
     // Check if already synced
-    if let Ok(true) = state.repo.is_article_synced(&article.id) {
-        return Ok(());
+    if state.repo.is_article_synced(&article.id)? {
+        return Ok(SyncStatus::Synced);
+    }
+    // Replays read the stored article, so it has to exist locally
+    if state.repo.get_article(&article.id)?.is_none() {
+        return Err(AppError::NotFound(format!("Article {}", article.id)));
+    }
+
+    sync_state.enqueue(&OutboxOperation::CreateArticle {
+        article_id: article.id.clone(),
+    })?;
+
+    let sync_service = sync_state.service(state.client.clone());
+    if let Err(e) = sync_state.drain(state.repo.as_ref(), &sync_service).await {
+        eprintln!("Failed to sync article to server: {}", e);
+    }
+
+    if state.repo.is_article_synced(&article.id)? {
+        Ok(SyncStatus::Synced)
+    } else {
+        Ok(SyncStatus::Queued)
+    }
+}
+
+/// Uploads every article not on the server yet that matches `policy` (default: rated helpful or saved).
+/// Emits `sync-all-progress` after each article and returns a summary once all are done.
+#[tauri::command]
+pub async fn sync_all(
+    policy: Option<SyncPolicy>,
+    app: tauri::AppHandle,
+    state: State<'_, RecommendationState>,
+    sync_state: State<'_, SyncState>,
+) -> Result<BulkSyncReport, AppError> {
+    let policy = policy.unwrap_or_default();
+    if policy.is_empty() {
+        return Err(AppError::Validation(
+            "Sync policy must include at least one rule".to_string(),
+        ));
     }
 
-    // Sync to server
-    match sync_service.sync_article(&article).await {
-        Ok(server_article_id) => {
-            // Mark as synced in local DB
-            let _ = state
-                .repo
-                .mark_article_synced(&article.id, &server_article_id);
-            Ok(())
-        }
-        Err(e) => {
-            // Log error but return Err for the frontend to handle if needed
-            eprintln!("Failed to sync article to server: {}", e);
-            Err(AppError::Sync(e))
-        }
+    let articles = state.repo.get_unsynced_articles(&policy)?;
+    let sync_service = sync_state.service(state.client.clone());
+    sync_state
+        .sync_all(articles, state.repo.as_ref(), &sync_service, |progress| {
+            if let Err(e) = app.emit(bulk::SYNC_PROGRESS_EVENT, progress) {
+                eprintln!("Failed to emit sync progress: {}", e);
+            }
+        })
+        .await
+}
+
+/// Stops a running `sync_all`; uploads already in flight still finish
+#[tauri::command]
+pub async fn cancel_sync_all(sync_state: State<'_, SyncState>) -> Result<(), AppError> {
+    sync_state.cancel_sync_all();
+    Ok(())
+}
+
+/// Check if the Brain Server is healthy.
+/// When it is, pending outbox entries are replayed in the background.
+#[tauri::command]
+pub async fn check_server_health(
+    state: State<'_, RecommendationState>,
+    sync_state: State<'_, SyncState>,
+) -> Result<bool, AppError> {
+    let sync_service = sync_state.service(state.client.clone());
+    let healthy = sync_service.health_check().await;
+    if healthy {
+        sync_state.spawn_drain(state.repo.clone(), sync_service);
+    }
+    Ok(healthy)
+}
+
+/// Operations still waiting to reach the Brain Server
+#[tauri::command]
+pub async fn get_sync_outbox(
+    sync_state: State<'_, SyncState>,
+) -> Result<Vec<OutboxEntry>, AppError> {
+    sync_state.outbox.get_all()
+}
+
+/// Retries every outbox entry now, including ones that gave up after too many failures
+#[tauri::command]
+pub async fn retry_sync_outbox(
+    state: State<'_, RecommendationState>,
+    sync_state: State<'_, SyncState>,
+) -> Result<DrainReport, AppError> {
+    sync_state
+        .outbox
+        .reschedule_all(&outbox::outbox_timestamp(chrono::Utc::now()))?;
+
+    let sync_service = sync_state.service(state.client.clone());
+    sync_state
+        .drain(state.repo.as_ref(), &sync_service)
+        .await?
+        .ok_or_else(|| AppError::Sync("A sync is already in progress".to_string()))
+}
+
+/// Pulls ratings and tags other devices sent to the Brain Server since the last pull
+#[tauri::command]
+pub async fn pull_from_server(
+    state: State<'_, RecommendationState>,
+    sync_state: State<'_, SyncState>,
+) -> Result<PullReport, AppError> {
+    let sync_service = sync_state.service(state.client.clone());
+    pull::pull_changes(
+        sync_state.outbox.as_ref(),
+        &sync_service,
+        chrono::Utc::now(),
+    )
+    .await
+}
+
+/// The server URL in use and a hint of the token, for the settings screen
+#[tauri::command]
+pub async fn get_server_credentials(
+    sync_state: State<'_, SyncState>,
+) -> Result<CredentialsInfo, AppError> {
+    Ok(sync_state.credentials.get().info())
+}
+
+/// Stores the Brain Server URL and API token in app config.
+/// `server_url: None` keeps using `BRAIN_SERVER_URL`; a URL must use https unless the server
+/// runs on this device, since the token goes with every request.
+#[tauri::command]
+pub async fn set_server_credentials(
+    server_url: Option<String>,
+    api_token: String,
+    sync_state: State<'_, SyncState>,
+) -> Result<CredentialsInfo, AppError> {
+    let api_token = api_token.trim();
+    if api_token.is_empty() {
+        return Err(AppError::Validation(
+            "API token cannot be empty".to_string(),
+        ));
+    }
+    let server_url = server_url
+        .map(|url| url.trim().trim_end_matches('/').to_string())
+        .filter(|url| !url.is_empty());
+    if let Some(url) = &server_url {
+        validate_server_url(url)?;
     }
+
+    let credentials = ServerCredentials {
+        server_url,
+        api_token: Some(api_token.to_string()),
+    };
+    sync_state.credentials.save(credentials.clone())?;
+    // Entries parked by a rejected token get another chance
+    sync_state
+        .outbox
+        .reschedule_all(&outbox::outbox_timestamp(chrono::Utc::now()))?;
+    Ok(credentials.info())
+}
+
+/// Checks the configured token against the server; `Unauthorized` if it is rejected
+#[tauri::command]
+pub async fn test_server_credentials(
+    state: State<'_, RecommendationState>,
+    sync_state: State<'_, SyncState>,
+) -> Result<CredentialsInfo, AppError> {
+    sync_state
+        .service(state.client.clone())
+        .verify_credentials()
+        .await?;
+    Ok(sync_state.credentials.get().info())
 }
 
-/// Check if the Brain Server is healthy
+/// Forgets the stored URL and token; the environment defaults apply again
 #[tauri::command]
-pub async fn check_server_health(state: State<'_, RecommendationState>) -> Result<bool, AppError> {
-    let sync_service = SyncService::new(state.client.clone());
-    Ok(sync_service.health_check().await)
+pub async fn clear_server_credentials(
+    sync_state: State<'_, SyncState>,
+) -> Result<CredentialsInfo, AppError> {
+    sync_state.credentials.clear()?;
+    Ok(sync_state.credentials.get().info())
 }
@@ -0,0 +1,171 @@
+use serde::{Deserialize, Serialize};
+
+/// A Brain Server call waiting in the outbox.
+/// Operations reference local article ids and are resolved when replayed, so they always send current data.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OutboxOperation {
+    CreateArticle {
+        article_id: String,
+    },
+    SubmitFeedback {
+        article_id: String,
+        helpful: bool,
+        reason: Option<String>,
+    },
+}
+
+impl OutboxOperation {
+    pub fn op_type(&self) -> &'static str {
+        match self {
+            OutboxOperation::CreateArticle { .. } => "create_article",
+            OutboxOperation::SubmitFeedback { .. } => "submit_feedback",
+        }
+    }
+
+    /// At most one pending entry exists per key; enqueueing again replaces the payload
+    /// (e.g. re-rating an article before the first rating was sent).
+    pub fn dedupe_key(&self) -> String {
+        match self {
+            OutboxOperation::CreateArticle { article_id }
+            | OutboxOperation::SubmitFeedback { article_id, .. } => {
+                format!("{}:{}", self.op_type(), article_id)
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct OutboxEntry {
+    pub id: i64,
+    pub operation: OutboxOperation,
+    pub attempts: u32,
+    /// `None` once the entry has given up, after `MAX_ATTEMPTS` or an error that isn't retryable
+    pub next_attempt_at: Option<String>,
+    pub last_error: Option<String>,
+    pub created_at: String,
+    /// How many times the payload was replaced by a different one
+    pub revision: u32,
+}
+
+impl OutboxEntry {
+    /// Sent as `Idempotency-Key`: the same for every replay of this payload, so the server
+    /// applies a request whose response was lost only once, and new for a replaced payload.
+    pub fn idempotency_key(&self) -> String {
+        format!("outbox-{}-{}", self.id, self.revision)
+    }
+}
+
+/// What one pass over the outbox did.
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub struct DrainReport {
+    pub succeeded: u32,
+    pub failed: u32,
+    /// Waiting on another operation (feedback for an article that isn't on the server yet)
+    pub deferred: u32,
+    /// Entries still in the outbox afterwards
+    pub remaining: u32,
+    /// The server became unreachable, so the pass stopped early
+    pub interrupted: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncStatus {
+    Synced,
+    /// Stored in the outbox and retried when the server is reachable
+    Queued,
+}
+
+/// How one server article was merged into the local database.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MergeOutcome {
+    /// Matched a local article by URL that wasn't linked to the server yet
+    Linked,
+    /// Refreshed an article that was already linked
+    Updated,
+    /// No local copy existed, so one was created
+    Imported,
+    /// Linked to a different server record, or older than what is stored
+    Skipped,
+}
+
+/// What one pull from the Brain Server did.
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub struct PullReport {
+    /// Changed server articles received
+    pub fetched: u32,
+    pub linked: u32,
+    pub updated: u32,
+    pub imported: u32,
+    pub skipped: u32,
+    /// Server `updatedAt` of the newest change seen; the next pull starts after it
+    pub cursor: Option<String>,
+}
+
+impl PullReport {
+    pub fn record(&mut self, outcome: MergeOutcome) {
+        match outcome {
+            MergeOutcome::Linked => self.linked += 1,
+            MergeOutcome::Updated => self.updated += 1,
+            MergeOutcome::Imported => self.imported += 1,
+            MergeOutcome::Skipped => self.skipped += 1,
+        }
+    }
+}
+
+/// Which unsynced articles `sync_all` uploads; an article matching any enabled rule is included.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct SyncPolicy {
+    /// Rated helpful
+    pub helpful: bool,
+    /// Rated either way
+    pub rated: bool,
+    /// Saved for later
+    pub saved: bool,
+}
+
+impl Default for SyncPolicy {
+    fn default() -> Self {
+        Self {
+            helpful: true,
+            rated: false,
+            saved: true,
+        }
+    }
+}
+
+impl SyncPolicy {
+    pub fn is_empty(&self) -> bool {
+        !(self.helpful || self.rated || self.saved)
+    }
+}
+
+/// Payload of the `sync-all-progress` event, sent after each article finishes.
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub struct SyncProgress {
+    pub done: u32,
+    pub total: u32,
+    pub failed: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SyncFailure {
+    pub article_id: String,
+    pub error: String,
+}
+
+/// Summary returned by `sync_all`.
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub struct BulkSyncReport {
+    pub total: u32,
+    pub succeeded: u32,
+    /// Failed uploads; they stay in the outbox and are retried like any other queued upload
+    pub failed: u32,
+    /// Never attempted because the run was cancelled or the server became unreachable
+    pub skipped: u32,
+    pub cancelled: bool,
+    pub interrupted: bool,
+    pub failures: Vec<SyncFailure>,
+}
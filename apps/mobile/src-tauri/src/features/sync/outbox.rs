@@ -0,0 +1,143 @@
+use crate::error::AppError;
+use crate::features::recommendation::repository::RecommendationRepository;
+use crate::features::sync::model::{DrainReport, OutboxEntry, OutboxOperation};
+use crate::features::sync::repository::SyncRepository;
+use crate::features::sync::service::SyncService;
+use chrono::{DateTime, SecondsFormat, Utc};
+
+/// Failed attempts before an entry is parked until `retry_sync_outbox`
+pub const MAX_ATTEMPTS: u32 = 10;
+const BASE_RETRY_DELAY_SECS: i64 = 30;
+const MAX_RETRY_DELAY_SECS: i64 = 6 * 60 * 60;
+/// Entries replayed per pass
+const DRAIN_BATCH_SIZE: u32 = 50;
+
+/// Outbox timestamps are UTC with a fixed format so they sort as text.
+pub fn outbox_timestamp(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Delay before the next try after `attempts` consecutive failures: 30s, 1m, 2m, ... capped at 6h.
+pub fn retry_delay(attempts: u32) -> chrono::Duration {
+    let exponent = attempts.saturating_sub(1).min(20);
+    let secs = (BASE_RETRY_DELAY_SECS << exponent).min(MAX_RETRY_DELAY_SECS);
+    chrono::Duration::seconds(secs)
+}
+
+/// When to try again after the `attempts`-th failure, or `None` to park the entry
+pub fn next_attempt_at(attempts: u32, error: &AppError, now: DateTime<Utc>) -> Option<String> {
+    // Rejected requests (e.g. HTTP 400) won't pass on a retry; park them right away
+    (attempts < MAX_ATTEMPTS && error.is_retryable())
+        .then(|| outbox_timestamp(now + retry_delay(attempts)))
+}
+
+enum Step {
+    Done,
+    Deferred,
+}
+
+/// Replays due outbox entries in order.
+///
+/// Replays are safe to repeat: every request carries an `Idempotency-Key` that stays the same
+/// across retries, so a request the server applied but whose response was lost (e.g. a timeout
+/// during a slow ingestion) is not applied again. Besides that, articles that already have a
+/// `server_article_id` are never created again, an entry is removed as soon as its request
+/// succeeded, and each article has at most one pending entry per operation. A network error means the server is offline, so the pass stops there
+/// instead of timing out on every remaining entry; a rejected token stops it the same way.
+pub async fn drain_outbox(
+    outbox: &dyn SyncRepository,
+    articles: &dyn RecommendationRepository,
+    service: &SyncService,
+    now: DateTime<Utc>,
+) -> Result<DrainReport, AppError> {
+    let mut report = DrainReport::default();
+
+    for entry in outbox.get_due(&outbox_timestamp(now), DRAIN_BATCH_SIZE)? {
+        match replay(&entry, outbox, articles, service).await {
+            Ok(Step::Done) => {
+                outbox.complete(&entry)?;
+                report.succeeded += 1;
+            }
+            Ok(Step::Deferred) => report.deferred += 1,
+            // Local failures aren't the server's fault; don't burn an attempt on them
+            Err(e @ AppError::Database(_)) => return Err(e),
+            Err(e) => {
+                let next_attempt_at = next_attempt_at(entry.attempts + 1, &e, now);
+                outbox.record_failure(entry.id, &e.to_string(), next_attempt_at.as_deref())?;
+                report.failed += 1;
+
+                // Every other entry would fail the same way
+                if matches!(e, AppError::Network(_) | AppError::Unauthorized(_)) {
+                    report.interrupted = true;
+                    break;
+                }
+            }
+        }
+    }
+
+    report.remaining = outbox.get_all()?.len() as u32;
+    Ok(report)
+}
+
+async fn replay(
+    entry: &OutboxEntry,
+    outbox: &dyn SyncRepository,
+    articles: &dyn RecommendationRepository,
+    service: &SyncService,
+) -> Result<Step, AppError> {
+    match &entry.operation {
+        OutboxOperation::CreateArticle { article_id } => {
+            if articles.is_article_synced(article_id)? {
+                return Ok(Step::Done);
+            }
+            // Deleted locally: nothing left to send
+            let Some(article) = articles.get_article(article_id)? else {
+                return Ok(Step::Done);
+            };
+            let server_article_id = service.sync_article(&article).await?;
+            articles.mark_article_synced(article_id, &server_article_id)?;
+            Ok(Step::Done)
+        }
+        OutboxOperation::SubmitFeedback {
+            article_id,
+            helpful,
+            reason,
+        } => match articles.get_server_article_id(article_id)? {
+            Some(server_article_id) => {
+                service
+                    .submit_feedback(
+                        &server_article_id,
+                        *helpful,
+                        reason.as_deref(),
+                        &entry.idempotency_key(),
+                    )
+                    .await?;
+                Ok(Step::Done)
+            }
+            None => {
+                let create = OutboxOperation::CreateArticle {
+                    article_id: article_id.clone(),
+                };
+                if outbox.has_pending(&create)? {
+                    Ok(Step::Deferred)
+                } else {
+                    // The article will never reach the server, so neither can its rating
+                    Ok(Step::Done)
+                }
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_backs_off_exponentially() {
+        let secs: Vec<i64> = (1..=6).map(|n| retry_delay(n).num_seconds()).collect();
+        assert_eq!(secs, vec![30, 60, 120, 240, 480, 960]);
+        assert_eq!(retry_delay(11).num_seconds(), 6 * 60 * 60);
+        assert_eq!(retry_delay(u32::MAX).num_seconds(), 6 * 60 * 60);
+    }
+}
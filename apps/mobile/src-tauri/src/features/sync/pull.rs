@@ -0,0 +1,74 @@
+use crate::error::AppError;
+use crate::features::sync::model::PullReport;
+use crate::features::sync::outbox::outbox_timestamp;
+use crate::features::sync::repository::SyncRepository;
+use crate::features::sync::service::SyncService;
+use chrono::{DateTime, Utc};
+
+/// Largest page the Brain Server returns
+const PULL_PAGE_SIZE: u32 = 100;
+/// Cursor for the first pull, so the server still sorts by `updatedAt`
+const EPOCH: &str = "1970-01-01T00:00:00.000Z";
+
+/// Pulls articles changed on the Brain Server since the last pull and merges them locally
+/// (see `SyncRepository::merge_server_article` for the conflict rules).
+///
+/// Pages are requested with `updatedSince` set to the newest change seen so far, so updates
+/// made during the pull can't shift unseen articles onto an already-fetched page. Servers that
+/// ignore `updatedSince` are paged through in full and filtered here. The cursor is only saved
+/// once every page was merged; merging is idempotent, so an interrupted pull is simply repeated.
+pub async fn pull_changes(
+    repo: &dyn SyncRepository,
+    service: &SyncService,
+    now: DateTime<Utc>,
+) -> Result<PullReport, AppError> {
+    let start = repo.get_pull_cursor()?;
+    let start = start.as_deref().unwrap_or(EPOCH);
+    let synced_at = outbox_timestamp(now);
+
+    let mut report = PullReport::default();
+    let mut newest = start.to_string();
+    let mut since = start.to_string();
+    let mut page = 1;
+    let mut filtered_by_server = true;
+
+    loop {
+        let response = service
+            .list_changed_articles(&since, page, PULL_PAGE_SIZE)
+            .await?;
+
+        if response.articles.iter().any(|a| a.updated_at <= since) {
+            filtered_by_server = false;
+        }
+
+        for article in response
+            .articles
+            .iter()
+            .filter(|a| a.updated_at.as_str() > start)
+        {
+            report.fetched += 1;
+            report.record(repo.merge_server_article(article, &synced_at)?);
+            if article.updated_at > newest {
+                newest = article.updated_at.clone();
+            }
+        }
+
+        if filtered_by_server {
+            if response.articles.len() < PULL_PAGE_SIZE as usize || newest == since {
+                break;
+            }
+            since = newest.clone();
+        } else {
+            if page >= response.pagination.total_pages {
+                break;
+            }
+            page += 1;
+        }
+    }
+
+    if newest != start {
+        repo.set_pull_cursor(&newest)?;
+    }
+    report.cursor = (newest != EPOCH).then_some(newest);
+    Ok(report)
+}
@@ -0,0 +1,300 @@
+use crate::db::DbPool;
+use crate::error::AppError;
+use crate::features::recommendation::model::ArticleCategory;
+use crate::features::sync::client::ServerArticle;
+use crate::features::sync::model::{MergeOutcome, OutboxEntry, OutboxOperation};
+use rusqlite::OptionalExtension;
+
+const PULL_CURSOR_KEY: &str = "pull_cursor";
+
+/// Persistent queue of Brain Server operations (`sync_outbox`).
+/// Timestamps are UTC RFC 3339 so they compare correctly as text.
+pub trait SyncRepository: Send + Sync {
+    /// Adds the operation, or replaces the pending one with the same dedupe key and makes it due at `now`.
+    /// Replacing it with a different payload bumps its `revision`.
+    fn enqueue(&self, operation: &OutboxOperation, now: &str) -> Result<i64, AppError>;
+    /// Entries whose retry time has come, oldest first
+    fn get_due(&self, now: &str, limit: u32) -> Result<Vec<OutboxEntry>, AppError>;
+    fn get_all(&self) -> Result<Vec<OutboxEntry>, AppError>;
+    fn has_pending(&self, operation: &OutboxOperation) -> Result<bool, AppError>;
+    /// The entry holding `operation`'s dedupe key, if there is one
+    fn get_pending(&self, operation: &OutboxOperation) -> Result<Option<OutboxEntry>, AppError>;
+    /// Removes a replayed entry, unless it was re-enqueued with a new payload meanwhile
+    fn complete(&self, entry: &OutboxEntry) -> Result<(), AppError>;
+    /// Counts a failed attempt; `next_attempt_at: None` parks the entry until it is retried manually
+    fn record_failure(
+        &self,
+        id: i64,
+        error: &str,
+        next_attempt_at: Option<&str>,
+    ) -> Result<(), AppError>;
+    /// Makes every entry (including parked ones) due at `now` with a fresh attempt budget
+    fn reschedule_all(&self, now: &str) -> Result<(), AppError>;
+
+    /// Server `updatedAt` of the newest change already pulled
+    fn get_pull_cursor(&self) -> Result<Option<String>, AppError>;
+    fn set_pull_cursor(&self, cursor: &str) -> Result<(), AppError>;
+    /// Merges a pulled server article into `articles`.
+    ///
+    /// Conflict rules:
+    /// - A local article is matched by `server_article_id`, then by URL. A URL match that is
+    ///   already linked to a different server record is left alone (first link wins).
+    /// - The server owns the aggregate rating and counts; they are replaced unless the stored
+    ///   copy is newer.
+    /// - Tags are unioned; tags that aren't a known category are ignored.
+    /// - Title, summary, the local rating and reading state always stay as they are on this device.
+    /// - Articles only on the server are imported as unread, without a summary; a later feed
+    ///   fetch of the same URL fills it in.
+    fn merge_server_article(
+        &self,
+        article: &ServerArticle,
+        now: &str,
+    ) -> Result<MergeOutcome, AppError>;
+}
+
+pub struct SqliteSyncRepository {
+    pool: DbPool,
+}
+
+impl SqliteSyncRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    fn query_entries<P: rusqlite::Params>(
+        &self,
+        clause: &str,
+        params: P,
+    ) -> Result<Vec<OutboxEntry>, AppError> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, payload, attempts, next_attempt_at, last_error, created_at, revision
+             FROM sync_outbox {}",
+            clause
+        ))?;
+        let entry_iter = stmt.query_map(params, |row| {
+            let payload: String = row.get(1)?;
+            let operation = serde_json::from_str(&payload).map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(
+                    1,
+                    rusqlite::types::Type::Text,
+                    Box::new(e),
+                )
+            })?;
+            Ok(OutboxEntry {
+                id: row.get(0)?,
+                operation,
+                attempts: row.get(2)?,
+                next_attempt_at: row.get(3)?,
+                last_error: row.get(4)?,
+                created_at: row.get(5)?,
+                revision: row.get(6)?,
+            })
+        })?;
+
+        let mut entries = Vec::new();
+        for e in entry_iter {
+            entries.push(e?);
+        }
+        Ok(entries)
+    }
+}
+
+impl SyncRepository for SqliteSyncRepository {
+    fn enqueue(&self, operation: &OutboxOperation, now: &str) -> Result<i64, AppError> {
+        let conn = self.pool.get()?;
+        let id = conn.query_row(
+            "INSERT INTO sync_outbox (op_type, dedupe_key, payload, attempts, next_attempt_at, created_at)
+             VALUES (?1, ?2, ?3, 0, ?4, ?4)
+             ON CONFLICT(dedupe_key) DO UPDATE SET
+                revision = CASE WHEN payload = excluded.payload THEN revision ELSE revision + 1 END,
+                payload = excluded.payload,
+                attempts = 0,
+                next_attempt_at = excluded.next_attempt_at,
+                last_error = NULL
+             RETURNING id",
+            rusqlite::params![
+                operation.op_type(),
+                operation.dedupe_key(),
+                serde_json::to_string(operation)?,
+                now
+            ],
+            |row| row.get(0),
+        )?;
+        Ok(id)
+    }
+
+    fn get_due(&self, now: &str, limit: u32) -> Result<Vec<OutboxEntry>, AppError> {
+        self.query_entries(
+            "WHERE next_attempt_at IS NOT NULL AND next_attempt_at <= ?1 ORDER BY id LIMIT ?2",
+            rusqlite::params![now, limit],
+        )
+    }
+
+    fn get_all(&self) -> Result<Vec<OutboxEntry>, AppError> {
+        self.query_entries("ORDER BY id", [])
+    }
+
+    fn has_pending(&self, operation: &OutboxOperation) -> Result<bool, AppError> {
+        let conn = self.pool.get()?;
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM sync_outbox WHERE dedupe_key = ?1",
+            rusqlite::params![operation.dedupe_key()],
+            |row| row.get(0),
+        )?;
+        Ok(count > 0)
+    }
+
+    fn get_pending(&self, operation: &OutboxOperation) -> Result<Option<OutboxEntry>, AppError> {
+        Ok(self
+            .query_entries(
+                "WHERE dedupe_key = ?1",
+                rusqlite::params![operation.dedupe_key()],
+            )?
+            .pop())
+    }
+
+    fn complete(&self, entry: &OutboxEntry) -> Result<(), AppError> {
+        let conn = self.pool.get()?;
+        conn.execute(
+            "DELETE FROM sync_outbox WHERE id = ?1 AND payload = ?2",
+            rusqlite::params![entry.id, serde_json::to_string(&entry.operation)?],
+        )?;
+        Ok(())
+    }
+
+    fn record_failure(
+        &self,
+        id: i64,
+        error: &str,
+        next_attempt_at: Option<&str>,
+    ) -> Result<(), AppError> {
+        let conn = self.pool.get()?;
+        conn.execute(
+            "UPDATE sync_outbox SET attempts = attempts + 1, last_error = ?1, next_attempt_at = ?2
+             WHERE id = ?3",
+            rusqlite::params![error, next_attempt_at, id],
+        )?;
+        Ok(())
+    }
+
+    fn reschedule_all(&self, now: &str) -> Result<(), AppError> {
+        let conn = self.pool.get()?;
+        conn.execute(
+            "UPDATE sync_outbox SET attempts = 0, next_attempt_at = ?1",
+            rusqlite::params![now],
+        )?;
+        Ok(())
+    }
+
+    fn get_pull_cursor(&self) -> Result<Option<String>, AppError> {
+        let conn = self.pool.get()?;
+        let cursor = conn
+            .query_row(
+                "SELECT value FROM sync_meta WHERE key = ?1",
+                rusqlite::params![PULL_CURSOR_KEY],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(cursor)
+    }
+
+    fn set_pull_cursor(&self, cursor: &str) -> Result<(), AppError> {
+        let conn = self.pool.get()?;
+        conn.execute(
+            "INSERT INTO sync_meta (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            rusqlite::params![PULL_CURSOR_KEY, cursor],
+        )?;
+        Ok(())
+    }
+
+    fn merge_server_article(
+        &self,
+        article: &ServerArticle,
+        now: &str,
+    ) -> Result<MergeOutcome, AppError> {
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+
+        let server_tags: Vec<ArticleCategory> =
+            article.tags.iter().filter_map(|t| t.parse().ok()).collect();
+
+        // Prefer the row already linked to this record over a URL match
+        let local: Option<(String, Option<String>, String, Option<String>)> = tx
+            .query_row(
+                "SELECT id, server_article_id, tags, server_updated_at FROM articles
+                 WHERE server_article_id = ?1 OR url = ?2
+                 ORDER BY (server_article_id IS ?1) DESC
+                 LIMIT 1",
+                rusqlite::params![article.id, article.url],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .optional()?;
+
+        let outcome = match local {
+            None => {
+                // Feed articles fall back to their link as id too
+                let inserted = tx.execute(
+                    "INSERT OR IGNORE INTO articles (id, title, summary, url, tags, published_at,
+                        server_article_id, synced_at, server_rating, server_positive_count,
+                        server_negative_count, server_updated_at)
+                     VALUES (?1, ?2, '', ?1, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                    rusqlite::params![
+                        article.url,
+                        article.title,
+                        serde_json::to_string(&server_tags)?,
+                        article.created_at,
+                        article.id,
+                        now,
+                        article.rating,
+                        article.positive_count,
+                        article.negative_count,
+                        article.updated_at
+                    ],
+                )?;
+                if inserted > 0 {
+                    MergeOutcome::Imported
+                } else {
+                    MergeOutcome::Skipped
+                }
+            }
+            Some((_, Some(linked), _, _)) if linked != article.id => MergeOutcome::Skipped,
+            Some((_, _, _, Some(stored))) if stored >= article.updated_at => MergeOutcome::Skipped,
+            Some((id, linked, tags_json, _)) => {
+                let mut tags: Vec<ArticleCategory> =
+                    serde_json::from_str(&tags_json).unwrap_or_default();
+                for tag in server_tags {
+                    if !tags.contains(&tag) {
+                        tags.push(tag);
+                    }
+                }
+
+                tx.execute(
+                    "UPDATE articles SET server_article_id = ?1, synced_at = COALESCE(synced_at, ?2),
+                        tags = ?3, server_rating = ?4, server_positive_count = ?5,
+                        server_negative_count = ?6, server_updated_at = ?7
+                     WHERE id = ?8",
+                    rusqlite::params![
+                        article.id,
+                        now,
+                        serde_json::to_string(&tags)?,
+                        article.rating,
+                        article.positive_count,
+                        article.negative_count,
+                        article.updated_at,
+                        id
+                    ],
+                )?;
+                if linked.is_some() {
+                    MergeOutcome::Updated
+                } else {
+                    MergeOutcome::Linked
+                }
+            }
+        };
+
+        tx.commit()?;
+        Ok(outcome)
+    }
+}
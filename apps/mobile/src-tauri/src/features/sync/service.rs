@@ -1,6 +1,15 @@
+use crate::error::AppError;
 use crate::features::recommendation::model::Article;
-use crate::features::sync::client::{BrainServerClient, CreateArticleRequest};
+use crate::features::recommendation::repository::RecommendationRepository;
+use crate::features::sync::bulk::upload_articles;
+use crate::features::sync::client::{ArticleListResponse, BrainServerClient, CreateArticleRequest};
+use crate::features::sync::credentials::{CredentialStore, ServerCredentials};
+use crate::features::sync::model::{BulkSyncReport, DrainReport, OutboxOperation, SyncProgress};
+use crate::features::sync::outbox::{drain_outbox, outbox_timestamp};
+use crate::features::sync::repository::SyncRepository;
 use reqwest::Client;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
 /// Service for syncing articles with the Brain Server
 pub struct SyncService {
@@ -8,15 +17,24 @@ pub struct SyncService {
 }
 
 impl SyncService {
-    pub fn new(http_client: Client) -> Self {
+    pub fn new(http_client: Client, credentials: &ServerCredentials) -> Self {
         Self {
-            client: BrainServerClient::new(http_client),
+            client: BrainServerClient::with_base_url(http_client, &credentials.base_url())
+                .with_token(credentials.token()),
+        }
+    }
+
+    pub fn with_base_url(http_client: Client, base_url: &str) -> Self {
+        Self {
+            client: BrainServerClient::with_base_url(http_client, base_url),
         }
     }
 
     /// Sync an article to the Brain Server
-    /// Returns the server-side article ID if successful
-    pub async fn sync_article(&self, article: &Article) -> Result<String, String> {
+    /// Returns the server-side article ID if successful.
+    /// Every upload of the same local article carries the same idempotency key, whether it comes
+    /// from `sync_all` or the outbox, so a retry after a lost response doesn't create it twice.
+    pub async fn sync_article(&self, article: &Article) -> Result<String, AppError> {
         // Convert tags to strings
         let tags: Vec<String> = article.tags.iter().map(|t| t.to_string()).collect();
 
@@ -27,24 +45,151 @@ impl SyncService {
             tags,
         };
 
-        let response = self.client.create_article(request).await?;
+        let response = self
+            .client
+            .create_article(request, &format!("create-article-{}", article.id))
+            .await?;
         Ok(response.id)
     }
 
-    /// Submit feedback for an article to the Brain Server
+    /// Submit feedback for an article to the Brain Server; see `OutboxEntry::idempotency_key`
     pub async fn submit_feedback(
         &self,
         server_article_id: &str,
         helpful: bool,
         reason: Option<&str>,
-    ) -> Result<(), String> {
+        idempotency_key: &str,
+    ) -> Result<(), AppError> {
         self.client
-            .submit_feedback(server_article_id, helpful, reason)
+            .submit_feedback(server_article_id, helpful, reason, idempotency_key)
             .await
     }
 
+    /// One page of server articles changed after `since`
+    pub async fn list_changed_articles(
+        &self,
+        since: &str,
+        page: u32,
+        limit: u32,
+    ) -> Result<ArticleListResponse, AppError> {
+        self.client.list_articles(Some(since), page, limit).await
+    }
+
     /// Check if the Brain Server is healthy
     pub async fn health_check(&self) -> bool {
         self.client.health_check().await
     }
+
+    /// Makes one authenticated request; fails with `AppError::Unauthorized` if the token is rejected
+    pub async fn verify_credentials(&self) -> Result<(), AppError> {
+        self.client.list_articles(None, 1, 1).await?;
+        Ok(())
+    }
+}
+
+/// Outbox of pending Brain Server operations. Cheap to clone; clones share the same sync lock.
+#[derive(Clone)]
+pub struct SyncState {
+    pub outbox: Arc<dyn SyncRepository>,
+    pub credentials: Arc<CredentialStore>,
+    /// Held while the outbox drains or `sync_all` runs, so no article is uploaded twice at once
+    draining: Arc<AtomicBool>,
+    cancel_requested: Arc<AtomicBool>,
+}
+
+/// Releases the sync lock even if the pass fails.
+struct DrainGuard(Arc<AtomicBool>);
+
+impl Drop for DrainGuard {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
+
+impl SyncState {
+    pub fn new(outbox: Arc<dyn SyncRepository>, credentials: Arc<CredentialStore>) -> Self {
+        Self {
+            outbox,
+            credentials,
+            draining: Arc::new(AtomicBool::new(false)),
+            cancel_requested: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    fn try_lock(&self) -> Option<DrainGuard> {
+        self.draining
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
+            .then(|| DrainGuard(self.draining.clone()))
+    }
+
+    /// A client for the currently configured server and token
+    pub fn service(&self, http_client: Client) -> SyncService {
+        SyncService::new(http_client, &self.credentials.get())
+    }
+
+    pub fn enqueue(&self, operation: &OutboxOperation) -> Result<(), AppError> {
+        self.outbox
+            .enqueue(operation, &outbox_timestamp(chrono::Utc::now()))?;
+        Ok(())
+    }
+
+    /// Replays due entries. Returns `None` without doing anything if another pass is running,
+    /// so the same entry is never sent twice concurrently.
+    pub async fn drain(
+        &self,
+        articles: &dyn RecommendationRepository,
+        service: &SyncService,
+    ) -> Result<Option<DrainReport>, AppError> {
+        let Some(_guard) = self.try_lock() else {
+            return Ok(None);
+        };
+
+        drain_outbox(self.outbox.as_ref(), articles, service, chrono::Utc::now())
+            .await
+            .map(Some)
+    }
+
+    /// Uploads `articles` (see `upload_articles`). Fails if a drain or another bulk sync is running.
+    pub async fn sync_all(
+        &self,
+        articles: Vec<Article>,
+        repo: &dyn RecommendationRepository,
+        service: &SyncService,
+        on_progress: impl Fn(SyncProgress),
+    ) -> Result<BulkSyncReport, AppError> {
+        let Some(_guard) = self.try_lock() else {
+            return Err(AppError::Sync("A sync is already in progress".to_string()));
+        };
+        self.cancel_requested.store(false, Ordering::SeqCst);
+
+        upload_articles(
+            articles,
+            repo,
+            self.outbox.as_ref(),
+            service,
+            &self.cancel_requested,
+            on_progress,
+        )
+        .await
+    }
+
+    /// Stops a running `sync_all` after the uploads already in flight
+    pub fn cancel_sync_all(&self) {
+        self.cancel_requested.store(true, Ordering::SeqCst);
+    }
+
+    /// Drains in the background; failures are only logged since every entry stays queued.
+    pub fn spawn_drain(&self, articles: Arc<dyn RecommendationRepository>, service: SyncService) {
+        let state = self.clone();
+        tauri::async_runtime::spawn(async move {
+            match state.drain(articles.as_ref(), &service).await {
+                Ok(Some(report)) if report.failed > 0 || report.interrupted => {
+                    eprintln!("Sync outbox pass incomplete: {:?}", report)
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("Failed to drain sync outbox: {}", e),
+            }
+        });
+    }
 }
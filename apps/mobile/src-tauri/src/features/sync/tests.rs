@@ -0,0 +1,781 @@
+use crate::db::create_test_pool;
+use crate::error::AppError;
+use crate::features::recommendation::model::{
+    Article, ArticleCategory, ArticleState, ServerRating,
+};
+use crate::features::recommendation::repository::{
+    RecommendationRepository, SqliteRecommendationRepository,
+};
+use crate::features::sync::bulk::{upload_articles, SYNC_ALL_CONCURRENCY};
+use crate::features::sync::client::ServerArticle;
+use crate::features::sync::credentials::ServerCredentials;
+use crate::features::sync::model::{
+    DrainReport, MergeOutcome, OutboxOperation, PullReport, SyncPolicy, SyncProgress,
+};
+use crate::features::sync::outbox::{drain_outbox, outbox_timestamp, MAX_ATTEMPTS};
+use crate::features::sync::pull::pull_changes;
+use crate::features::sync::repository::{SqliteSyncRepository, SyncRepository};
+use crate::features::sync::service::SyncService;
+use crate::test_utils::{MockResponse, MockServer};
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+const CREATED: &str = r#"{"id":"srv-1","title":"T","url":"https://example.com/a","tags":["Rust"],
+    "createdAt":"2025-01-01T00:00:00Z","chunkCount":1,"rating":1.0,"positiveCount":0,"negativeCount":0}"#;
+
+fn now() -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339("2025-03-01T12:00:00Z")
+        .unwrap()
+        .with_timezone(&Utc)
+}
+
+fn setup(ids: &[&str]) -> (SqliteRecommendationRepository, SqliteSyncRepository) {
+    let pool = create_test_pool();
+    let articles = SqliteRecommendationRepository::new(pool.clone());
+    articles
+        .upsert_articles(
+            ids.iter()
+                .map(|id| Article {
+                    id: id.to_string(),
+                    title: format!("Article {}", id),
+                    summary: "A long enough summary for the server".into(),
+                    url: format!("https://example.com/{}", id),
+                    tags: vec![ArticleCategory::Rust],
+                    published_at: "2025-01-01".into(),
+                    feedback: None,
+                    image_url: None,
+                    author: None,
+                    state: ArticleState::Unread,
+                    server_rating: None,
+                })
+                .collect(),
+        )
+        .unwrap();
+    (articles, SqliteSyncRepository::new(pool))
+}
+
+fn create(id: &str) -> OutboxOperation {
+    OutboxOperation::CreateArticle {
+        article_id: id.into(),
+    }
+}
+
+fn feedback(id: &str, helpful: bool) -> OutboxOperation {
+    OutboxOperation::SubmitFeedback {
+        article_id: id.into(),
+        helpful,
+        reason: None,
+    }
+}
+
+/// Base URL of a port nothing listens on
+fn offline_url() -> String {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    format!("http://{}", listener.local_addr().unwrap())
+}
+
+#[test]
+fn test_drain_replays_create_then_feedback() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/articles" => MockResponse::json(201, CREATED),
+        _ => MockResponse::json(200, r#"{"message":"ok"}"#),
+    });
+    let service = SyncService::with_base_url(reqwest::Client::new(), &server.url);
+    let (articles, outbox) = setup(&["a"]);
+    let ts = outbox_timestamp(now());
+
+    outbox.enqueue(&create("a"), &ts).unwrap();
+    outbox.enqueue(&feedback("a", true), &ts).unwrap();
+
+    let report =
+        tauri::async_runtime::block_on(drain_outbox(&outbox, &articles, &service, now())).unwrap();
+    assert_eq!(
+        report,
+        DrainReport {
+            succeeded: 2,
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        articles.get_server_article_id("a").unwrap().as_deref(),
+        Some("srv-1")
+    );
+    assert!(outbox.get_all().unwrap().is_empty());
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].path, "/articles");
+    assert_eq!(requests[0].json()["url"], "https://example.com/a");
+    assert_eq!(requests[1].path, "/articles/srv-1/feedback");
+    assert_eq!(requests[1].json()["type"], "positive");
+
+    // Replaying a create for an article that's already on the server never posts it again
+    outbox.enqueue(&create("a"), &ts).unwrap();
+    let report =
+        tauri::async_runtime::block_on(drain_outbox(&outbox, &articles, &service, now())).unwrap();
+    assert_eq!(report.succeeded, 1);
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[test]
+fn test_replays_reuse_idempotency_keys() {
+    // Every first attempt fails as if the response was lost
+    let calls = Arc::new(Mutex::new(0));
+    let counter = calls.clone();
+    let server = MockServer::start(move |req| {
+        let mut calls = counter.lock().unwrap();
+        *calls += 1;
+        match (req.path.as_str(), *calls) {
+            (_, 1) | (_, 3) => MockResponse::json(503, r#"{"error":"busy"}"#),
+            ("/articles", _) => MockResponse::json(201, CREATED),
+            _ => MockResponse::json(200, r#"{"message":"ok"}"#),
+        }
+    });
+    let service = SyncService::with_base_url(reqwest::Client::new(), &server.url);
+    let (articles, outbox) = setup(&["a"]);
+    let ts = outbox_timestamp(now());
+    outbox.enqueue(&create("a"), &ts).unwrap();
+    outbox.enqueue(&feedback("a", true), &ts).unwrap();
+
+    let later = |hours| now() + chrono::Duration::hours(hours);
+    for at in [now(), later(1), later(2)] {
+        tauri::async_runtime::block_on(drain_outbox(&outbox, &articles, &service, at)).unwrap();
+    }
+    // Re-rating replaces the payload, which is a new request
+    outbox.enqueue(&feedback("a", false), &ts).unwrap();
+    tauri::async_runtime::block_on(drain_outbox(&outbox, &articles, &service, later(3))).unwrap();
+    assert!(outbox.get_all().unwrap().is_empty());
+
+    let requests = server.requests();
+    let keys: Vec<&str> = requests
+        .iter()
+        .map(|r| r.header("Idempotency-Key").unwrap())
+        .collect();
+    assert_eq!(requests.len(), 5);
+    assert_eq!(keys[0], "create-article-a");
+    assert_eq!(keys[1], keys[0]);
+    assert_eq!(requests[2].path, "/articles/srv-1/feedback");
+    assert_eq!(keys[3], keys[2]);
+    assert_ne!(keys[4], keys[3]);
+    assert_eq!(requests[4].json()["type"], "negative");
+}
+
+#[test]
+fn test_drain_backs_off_while_offline() {
+    let service = SyncService::with_base_url(reqwest::Client::new(), &offline_url());
+    let (articles, outbox) = setup(&["a", "b"]);
+    let ts = outbox_timestamp(now());
+    outbox.enqueue(&create("a"), &ts).unwrap();
+    outbox.enqueue(&create("b"), &ts).unwrap();
+    outbox.enqueue(&feedback("a", false), &ts).unwrap();
+
+    let report =
+        tauri::async_runtime::block_on(drain_outbox(&outbox, &articles, &service, now())).unwrap();
+
+    // Stops at the first network error instead of trying every entry
+    assert_eq!(
+        report,
+        DrainReport {
+            failed: 1,
+            remaining: 3,
+            interrupted: true,
+            ..Default::default()
+        }
+    );
+    let entries = outbox.get_all().unwrap();
+    assert_eq!(entries[0].attempts, 1);
+    assert_eq!(
+        entries[0].next_attempt_at.as_deref(),
+        Some("2025-03-01T12:00:30Z")
+    );
+    assert!(entries[0].last_error.is_some());
+    assert_eq!(entries[1].attempts, 0);
+
+    // Not due yet: the failed entry is skipped, the next one is tried
+    let report =
+        tauri::async_runtime::block_on(drain_outbox(&outbox, &articles, &service, now())).unwrap();
+    assert_eq!(report.failed, 1);
+    let entries = outbox.get_all().unwrap();
+    assert_eq!(entries[0].attempts, 1);
+    assert_eq!(entries[1].attempts, 1);
+    assert!(articles.get_server_article_id("a").unwrap().is_none());
+}
+
+#[test]
+fn test_drain_parks_unreadable_responses() {
+    let server = MockServer::start(|_| MockResponse::json(201, r#"{"unexpected":true}"#));
+    let service = SyncService::with_base_url(reqwest::Client::new(), &server.url);
+    let (articles, outbox) = setup(&["a"]);
+    outbox
+        .enqueue(&create("a"), &outbox_timestamp(now()))
+        .unwrap();
+
+    let report =
+        tauri::async_runtime::block_on(drain_outbox(&outbox, &articles, &service, now())).unwrap();
+    assert_eq!(report.failed, 1);
+    // A response that can't be parsed won't parse on a retry either
+    let entry = &outbox.get_all().unwrap()[0];
+    assert_eq!(entry.attempts, 1);
+    assert_eq!(entry.next_attempt_at, None);
+    assert!(entry.last_error.as_deref().unwrap().contains("parse"));
+}
+
+#[test]
+fn test_drain_defers_feedback_until_article_exists() {
+    let server = MockServer::start(|_| MockResponse::new(500, "boom"));
+    let service = SyncService::with_base_url(reqwest::Client::new(), &server.url);
+    let (articles, outbox) = setup(&["a", "b"]);
+    let ts = outbox_timestamp(now());
+    outbox.enqueue(&create("a"), &ts).unwrap();
+    outbox.enqueue(&feedback("a", true), &ts).unwrap();
+    // No create pending and never synced: the rating can't go anywhere
+    outbox.enqueue(&feedback("b", true), &ts).unwrap();
+
+    let report =
+        tauri::async_runtime::block_on(drain_outbox(&outbox, &articles, &service, now())).unwrap();
+
+    assert_eq!(
+        report,
+        DrainReport {
+            succeeded: 1,
+            failed: 1,
+            deferred: 1,
+            remaining: 2,
+            interrupted: false,
+        }
+    );
+    // Only the create reached the server
+    assert_eq!(server.requests().len(), 1);
+    let entries = outbox.get_all().unwrap();
+    assert!(entries[0].last_error.as_deref().unwrap().contains("500"));
+    assert_eq!(entries[1].operation, feedback("a", true));
+    assert_eq!(entries[1].attempts, 0);
+}
+
+#[test]
+fn test_outbox_dedupes_and_parks() {
+    let (_, outbox) = setup(&[]);
+    let ts = outbox_timestamp(now());
+
+    let first = outbox.enqueue(&feedback("a", true), &ts).unwrap();
+    let in_flight = outbox.get_all().unwrap().remove(0);
+    // Re-rating before the first rating was sent replaces it
+    let second = outbox.enqueue(&feedback("a", false), &ts).unwrap();
+    assert_eq!(first, second);
+    let entries = outbox.get_all().unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].operation, feedback("a", false));
+
+    // Completing the stale payload must not drop the new rating
+    outbox.complete(&in_flight).unwrap();
+    assert_eq!(outbox.get_all().unwrap().len(), 1);
+    outbox.complete(&entries[0]).unwrap();
+    assert!(outbox.get_all().unwrap().is_empty());
+
+    // Parked entries are never due until rescheduled
+    let id = outbox.enqueue(&create("b"), &ts).unwrap();
+    for _ in 0..MAX_ATTEMPTS {
+        outbox.record_failure(id, "offline", None).unwrap();
+    }
+    assert!(outbox
+        .get_due("9999-01-01T00:00:00Z", 10)
+        .unwrap()
+        .is_empty());
+    outbox.reschedule_all(&ts).unwrap();
+    let due = outbox.get_due(&ts, 10).unwrap();
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0].attempts, 0);
+}
+
+fn server_article(
+    id: &str,
+    url: &str,
+    updated_at: &str,
+    positive: i64,
+    negative: i64,
+) -> ServerArticle {
+    ServerArticle {
+        id: id.into(),
+        title: format!("Server {}", id),
+        url: url.into(),
+        tags: vec!["AI".into(), "Cooking".into()],
+        created_at: "2025-01-15T00:00:00.000Z".into(),
+        updated_at: updated_at.into(),
+        rating: if positive + negative == 0 { 1.0 } else { 1.5 },
+        positive_count: positive,
+        negative_count: negative,
+    }
+}
+
+fn list_body(articles: &[serde_json::Value]) -> String {
+    json!({
+        "articles": articles,
+        "pagination": {"page": 1, "limit": 100, "total": articles.len(), "totalPages": 1}
+    })
+    .to_string()
+}
+
+fn article_json(article: &ServerArticle) -> serde_json::Value {
+    json!({
+        "id": article.id, "title": article.title, "url": article.url, "tags": article.tags,
+        "createdAt": article.created_at, "updatedAt": article.updated_at, "chunkCount": 1,
+        "rating": article.rating, "positiveCount": article.positive_count,
+        "negativeCount": article.negative_count
+    })
+}
+
+#[test]
+fn test_pull_applies_conflict_rules() {
+    let changes: Vec<serde_json::Value> = [
+        server_article(
+            "srv-a",
+            "https://example.com/a",
+            "2025-02-01T00:00:00.000Z",
+            1,
+            0,
+        ),
+        server_article(
+            "srv-b",
+            "https://example.com/b",
+            "2025-02-02T00:00:00.000Z",
+            0,
+            1,
+        ),
+        server_article(
+            "srv-c",
+            "https://example.com/c",
+            "2025-02-03T00:00:00.000Z",
+            2,
+            0,
+        ),
+        server_article(
+            "srv-d",
+            "https://example.com/d",
+            "2025-02-04T00:00:00.000Z",
+            1,
+            0,
+        ),
+    ]
+    .iter()
+    .map(article_json)
+    .collect();
+    let server = MockServer::start(move |req| {
+        // A server that honors updatedSince: nothing changed after the first pull
+        if req.path.contains("updatedSince=1970") {
+            MockResponse::json(200, &list_body(&changes))
+        } else {
+            MockResponse::json(200, &list_body(&[]))
+        }
+    });
+    let service = SyncService::with_base_url(reqwest::Client::new(), &server.url);
+    let (articles, repo) = setup(&["a", "b", "d"]);
+    articles.mark_article_synced("b", "srv-b").unwrap();
+    articles
+        .update_feedback("b", true, "useful", "2025-02-10T00:00:00Z")
+        .unwrap();
+    articles.mark_article_synced("d", "srv-other").unwrap();
+
+    let report = tauri::async_runtime::block_on(pull_changes(&repo, &service, now())).unwrap();
+    assert_eq!(
+        report,
+        PullReport {
+            fetched: 4,
+            linked: 1,
+            updated: 1,
+            imported: 1,
+            skipped: 1,
+            cursor: Some("2025-02-04T00:00:00.000Z".into()),
+        }
+    );
+
+    // Linked by URL; tags unioned, unknown tags dropped, local title kept
+    let a = articles.get_article("a").unwrap().unwrap();
+    assert_eq!(
+        articles.get_server_article_id("a").unwrap().as_deref(),
+        Some("srv-a")
+    );
+    assert_eq!(a.tags, vec![ArticleCategory::Rust, ArticleCategory::AI]);
+    assert_eq!(a.title, "Article a");
+    assert_eq!(
+        a.server_rating,
+        Some(ServerRating {
+            rating: 1.5,
+            positive_count: 1,
+            negative_count: 0,
+            updated_at: "2025-02-01T00:00:00.000Z".into(),
+        })
+    );
+
+    // The server's aggregate never overwrites this device's own rating
+    let b = articles.get_article("b").unwrap().unwrap();
+    assert!(b.feedback.unwrap().is_helpful);
+    assert_eq!(b.server_rating.unwrap().negative_count, 1);
+
+    // Only on the server: imported as unread, summary filled in by a later feed fetch
+    let c = articles
+        .get_article("https://example.com/c")
+        .unwrap()
+        .unwrap();
+    assert_eq!(c.state, ArticleState::Unread);
+    assert_eq!(c.summary, "");
+    assert_eq!(c.server_rating.as_ref().unwrap().positive_count, 2);
+    articles
+        .upsert_articles(vec![Article {
+            summary: "Fetched from the feed".into(),
+            ..c
+        }])
+        .unwrap();
+    let c = articles
+        .get_article("https://example.com/c")
+        .unwrap()
+        .unwrap();
+    assert_eq!(c.summary, "Fetched from the feed");
+
+    // Already linked to another record for the same URL: first link wins
+    let d = articles.get_article("d").unwrap().unwrap();
+    assert_eq!(
+        articles.get_server_article_id("d").unwrap().as_deref(),
+        Some("srv-other")
+    );
+    assert!(d.server_rating.is_none());
+
+    // Stale copies never replace newer aggregates
+    let stale = server_article(
+        "srv-a",
+        "https://example.com/a",
+        "2025-01-20T00:00:00.000Z",
+        0,
+        5,
+    );
+    assert_eq!(
+        repo.merge_server_article(&stale, &outbox_timestamp(now()))
+            .unwrap(),
+        MergeOutcome::Skipped
+    );
+    assert_eq!(
+        articles
+            .get_article("a")
+            .unwrap()
+            .unwrap()
+            .server_rating
+            .unwrap()
+            .positive_count,
+        1
+    );
+
+    // The next pull resumes from the cursor
+    let report = tauri::async_runtime::block_on(pull_changes(&repo, &service, now())).unwrap();
+    assert_eq!(report.fetched, 0);
+    assert_eq!(report.cursor.as_deref(), Some("2025-02-04T00:00:00.000Z"));
+    let requests = server.requests();
+    assert!(requests[1]
+        .path
+        .contains("updatedSince=2025-02-04T00%3A00%3A00.000Z"));
+}
+
+#[test]
+fn test_second_device_sees_first_devices_rating() {
+    // In-memory Brain Server that ignores updatedSince, like servers before it was added
+    let store: Arc<Mutex<Vec<ServerArticle>>> = Arc::new(Mutex::new(Vec::new()));
+    let server_store = store.clone();
+    let server = MockServer::start(move |req| {
+        let mut articles = server_store.lock().unwrap();
+        let updated_at = format!("2025-03-01T00:00:{:02}.000Z", articles.len() + 1);
+        if req.method == "GET" {
+            let list: Vec<_> = articles.iter().map(article_json).collect();
+            return MockResponse::json(200, &list_body(&list));
+        }
+        if req.path == "/articles" {
+            let body = req.json();
+            let created = server_article(
+                &format!("srv-{}", articles.len() + 1),
+                body["url"].as_str().unwrap(),
+                &updated_at,
+                0,
+                0,
+            );
+            articles.push(created.clone());
+            return MockResponse::json(201, &article_json(&created).to_string());
+        }
+        let article = articles
+            .iter_mut()
+            .find(|a| req.path == format!("/articles/{}/feedback", a.id))
+            .unwrap();
+        article.positive_count += 1;
+        article.rating = 2.0;
+        article.updated_at = "2025-03-02T00:00:00.000Z".into();
+        MockResponse::json(200, r#"{"message":"ok"}"#)
+    });
+    let service = SyncService::with_base_url(reqwest::Client::new(), &server.url);
+    let ts = outbox_timestamp(now());
+
+    // Device 1 rates the article
+    let (articles_1, outbox_1) = setup(&["a"]);
+    outbox_1.enqueue(&create("a"), &ts).unwrap();
+    outbox_1.enqueue(&feedback("a", true), &ts).unwrap();
+    tauri::async_runtime::block_on(drain_outbox(&outbox_1, &articles_1, &service, now())).unwrap();
+    assert_eq!(store.lock().unwrap()[0].positive_count, 1);
+
+    // Device 2 fetched the same article from its feeds but never rated it
+    let (articles_2, repo_2) = setup(&["a"]);
+    let report = tauri::async_runtime::block_on(pull_changes(&repo_2, &service, now())).unwrap();
+    assert_eq!(report.linked, 1);
+
+    let a = articles_2.get_article("a").unwrap().unwrap();
+    assert!(a.feedback.is_none());
+    let rating = a.server_rating.unwrap();
+    assert_eq!(rating.positive_count, 1);
+    assert_eq!(rating.rating, 2.0);
+
+    // Now linked, so syncing it from device 2 doesn't create a duplicate
+    let requests_before = server.requests().len();
+    repo_2.enqueue(&create("a"), &ts).unwrap();
+    let report =
+        tauri::async_runtime::block_on(drain_outbox(&repo_2, &articles_2, &service, now()))
+            .unwrap();
+    assert_eq!(report.succeeded, 1);
+    assert_eq!(server.requests().len(), requests_before);
+}
+
+fn authenticated_server() -> MockServer {
+    MockServer::start(|req| {
+        if req.header("authorization") != Some("Bearer s3cret-token") {
+            return MockResponse::json(
+                401,
+                r#"{"error":"Unauthorized","message":"Missing or invalid API token"}"#,
+            );
+        }
+        match req.path.as_str() {
+            "/articles" => MockResponse::json(201, CREATED),
+            _ => MockResponse::json(200, &list_body(&[])),
+        }
+    })
+}
+
+fn service_with_token(server: &MockServer, token: Option<&str>) -> SyncService {
+    SyncService::new(
+        reqwest::Client::new(),
+        &ServerCredentials {
+            server_url: Some(server.url.clone()),
+            api_token: token.map(String::from),
+        },
+    )
+}
+
+#[test]
+fn test_client_sends_bearer_token() {
+    let server = authenticated_server();
+
+    let service = service_with_token(&server, Some("s3cret-token"));
+    tauri::async_runtime::block_on(service.verify_credentials()).unwrap();
+    assert_eq!(
+        server.requests()[0].header("authorization"),
+        Some("Bearer s3cret-token")
+    );
+
+    for token in [None, Some("wrong")] {
+        let service = service_with_token(&server, token);
+        let err = tauri::async_runtime::block_on(service.verify_credentials()).unwrap_err();
+        assert!(matches!(err, AppError::Unauthorized(_)), "{:?}", err);
+    }
+}
+
+#[test]
+fn test_drain_stops_when_token_is_rejected() {
+    let server = authenticated_server();
+    let (articles, outbox) = setup(&["a", "b"]);
+    let ts = outbox_timestamp(now());
+    outbox.enqueue(&create("a"), &ts).unwrap();
+    outbox.enqueue(&create("b"), &ts).unwrap();
+
+    let service = service_with_token(&server, Some("expired"));
+    let report =
+        tauri::async_runtime::block_on(drain_outbox(&outbox, &articles, &service, now())).unwrap();
+    assert_eq!(
+        report,
+        DrainReport {
+            failed: 1,
+            remaining: 2,
+            interrupted: true,
+            ..Default::default()
+        }
+    );
+    assert!(outbox.get_all().unwrap()[0]
+        .last_error
+        .as_deref()
+        .unwrap()
+        .starts_with("Unauthorized"));
+
+    // Fixing the token and retrying sends everything
+    outbox.reschedule_all(&ts).unwrap();
+    let service = service_with_token(&server, Some("s3cret-token"));
+    let report =
+        tauri::async_runtime::block_on(drain_outbox(&outbox, &articles, &service, now())).unwrap();
+    assert_eq!(report.succeeded, 2);
+}
+
+fn ids(articles: &[Article]) -> Vec<&str> {
+    articles.iter().map(|a| a.id.as_str()).collect()
+}
+
+#[test]
+fn test_unsynced_articles_follow_policy() {
+    let (articles, _) = setup(&["a", "b", "c", "d", "e"]);
+    let ts = "2025-02-10T00:00:00Z";
+    articles.update_feedback("a", true, "good", ts).unwrap();
+    articles.update_feedback("b", false, "bad", ts).unwrap();
+    articles
+        .set_article_state("c", ArticleState::Saved, ts)
+        .unwrap();
+    articles.update_feedback("d", true, "good", ts).unwrap();
+    articles.mark_article_synced("d", "srv-d").unwrap();
+
+    let default = articles
+        .get_unsynced_articles(&SyncPolicy::default())
+        .unwrap();
+    assert_eq!(ids(&default), vec!["a", "c"]);
+
+    let rated = SyncPolicy {
+        helpful: false,
+        rated: true,
+        saved: false,
+    };
+    assert_eq!(
+        ids(&articles.get_unsynced_articles(&rated).unwrap()),
+        vec!["a", "b"]
+    );
+}
+
+#[test]
+fn test_upload_articles_reports_progress_and_queues_failures() {
+    let server = MockServer::start(|req| {
+        if req.json()["url"] == "https://example.com/b" {
+            MockResponse::json(400, r#"{"error":"Invalid content"}"#)
+        } else {
+            MockResponse::json(201, CREATED)
+        }
+    });
+    let service = SyncService::with_base_url(reqwest::Client::new(), &server.url);
+    let (articles, outbox) = setup(&["a", "b", "c"]);
+    let to_upload = ["a", "b", "c"]
+        .iter()
+        .map(|id| articles.get_article(id).unwrap().unwrap())
+        .collect();
+
+    let progress = Mutex::new(Vec::new());
+    let report = tauri::async_runtime::block_on(upload_articles(
+        to_upload,
+        &articles,
+        &outbox,
+        &service,
+        &AtomicBool::new(false),
+        |p| progress.lock().unwrap().push(p),
+    ))
+    .unwrap();
+
+    assert_eq!(report.total, 3);
+    assert_eq!(report.succeeded, 2);
+    assert_eq!(report.failed, 1);
+    assert_eq!(report.failures[0].article_id, "b");
+    assert!(!report.cancelled && !report.interrupted);
+
+    let progress = progress.into_inner().unwrap();
+    assert_eq!(
+        progress.iter().map(|p| p.done).collect::<Vec<_>>(),
+        vec![1, 2, 3]
+    );
+    assert_eq!(
+        progress.last(),
+        Some(&SyncProgress {
+            done: 3,
+            total: 3,
+            failed: 1
+        })
+    );
+
+    assert!(articles.is_article_synced("a").unwrap());
+    assert!(articles.is_article_synced("c").unwrap());
+    // The failed upload is left to the outbox as a failed attempt; a 400 won't pass on a retry
+    let entry = &outbox.get_all().unwrap()[0];
+    assert_eq!(entry.operation, create("b"));
+    assert_eq!(entry.attempts, 1);
+    assert_eq!(entry.next_attempt_at, None);
+    assert!(entry.last_error.is_some());
+}
+
+#[test]
+fn test_upload_articles_keeps_outbox_attempts() {
+    let server = MockServer::start(|_| MockResponse::json(503, r#"{"error":"Busy"}"#));
+    let service = SyncService::with_base_url(reqwest::Client::new(), &server.url);
+    let (articles, outbox) = setup(&["a"]);
+    let upload = || {
+        tauri::async_runtime::block_on(upload_articles(
+            vec![articles.get_article("a").unwrap().unwrap()],
+            &articles,
+            &outbox,
+            &service,
+            &AtomicBool::new(false),
+            |_| {},
+        ))
+        .unwrap()
+    };
+
+    // Repeated runs count towards the same backoff instead of starting it over
+    for attempts in 1..MAX_ATTEMPTS {
+        assert_eq!(upload().failed, 1);
+        let entries = outbox.get_all().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].attempts, attempts);
+        assert!(entries[0].next_attempt_at.is_some());
+    }
+    upload();
+    let entry = &outbox.get_all().unwrap()[0];
+    assert_eq!(entry.attempts, MAX_ATTEMPTS);
+    assert_eq!(entry.next_attempt_at, None);
+}
+
+#[test]
+fn test_upload_articles_stops_on_cancel_or_offline() {
+    let ids = ["a", "b", "c", "d", "e", "f"];
+    let (articles, outbox) = setup(&ids);
+    let to_upload = || -> Vec<Article> {
+        ids.iter()
+            .map(|id| articles.get_article(id).unwrap().unwrap())
+            .collect()
+    };
+
+    // Offline: nothing new starts after the first failure
+    let offline = SyncService::with_base_url(reqwest::Client::new(), &offline_url());
+    let report = tauri::async_runtime::block_on(upload_articles(
+        to_upload(),
+        &articles,
+        &outbox,
+        &offline,
+        &AtomicBool::new(false),
+        |_| {},
+    ))
+    .unwrap();
+    assert!(report.interrupted);
+    assert!(report.failed >= 1 && report.failed as usize <= SYNC_ALL_CONCURRENCY);
+    assert_eq!((report.failed + report.skipped) as usize, ids.len());
+
+    // Cancelled after the first upload: only uploads already in flight finish
+    let server = MockServer::start(|_| MockResponse::json(201, CREATED));
+    let service = SyncService::with_base_url(reqwest::Client::new(), &server.url);
+    let cancel = AtomicBool::new(false);
+    let report = tauri::async_runtime::block_on(upload_articles(
+        to_upload(),
+        &articles,
+        &outbox,
+        &service,
+        &cancel,
+        |_| cancel.store(true, Ordering::SeqCst),
+    ))
+    .unwrap();
+    assert!(report.cancelled);
+    assert!(report.succeeded >= 1 && report.succeeded as usize <= SYNC_ALL_CONCURRENCY);
+    assert_eq!((report.succeeded + report.skipped) as usize, ids.len());
+    assert_eq!(server.requests().len(), report.succeeded as usize);
+}
@@ -1,23 +1,110 @@
-use super::model::Todo;
+use super::model::{NewTodo, Priority, Todo, TodoPatch, TodoSort};
+use super::quick_add::parse_quick_add;
 use super::service::TodoState;
+use crate::error::AppError;
 use tauri::State;
 
+/// Manual order unless another `sort` is chosen
 #[tauri::command]
-pub fn get_todos(state: State<TodoState>) -> Result<Vec<Todo>, String> {
-    state.get_all()
+pub fn get_todos(sort: Option<TodoSort>, state: State<TodoState>) -> Result<Vec<Todo>, AppError> {
+    state.get_sorted(sort.unwrap_or_default())
 }
 
 #[tauri::command]
-pub fn add_todo(text: String, state: State<TodoState>) -> Result<Vec<Todo>, String> {
-    state.add(text)
+pub fn add_todo(todo: NewTodo, state: State<TodoState>) -> Result<Vec<Todo>, AppError> {
+    state.add(todo)
 }
 
+/// Parses dates, times, priority, `#category` and recurrence out of a one-line entry
 #[tauri::command]
-pub fn toggle_todo(id: u32, state: State<TodoState>) -> Result<Vec<Todo>, String> {
+pub fn quick_add_todo(input: String, state: State<TodoState>) -> Result<Vec<Todo>, AppError> {
+    state.quick_add(&input, chrono::Local::now().date_naive())
+}
+
+/// What `quick_add_todo` would create, for showing the recognised fields while typing
+#[tauri::command]
+pub fn preview_quick_add(input: String) -> NewTodo {
+    parse_quick_add(&input, chrono::Local::now().date_naive())
+}
+
+#[tauri::command]
+pub fn update_todo(
+    id: u32,
+    patch: TodoPatch,
+    state: State<TodoState>,
+) -> Result<Vec<Todo>, AppError> {
+    state.update(id, patch)
+}
+
+#[tauri::command]
+pub fn add_subtask(
+    parent_id: u32,
+    todo: NewTodo,
+    state: State<TodoState>,
+) -> Result<Vec<Todo>, AppError> {
+    state.add_subtask(parent_id, todo)
+}
+
+#[tauri::command]
+pub fn reorder_subtasks(
+    parent_id: u32,
+    ids: Vec<u32>,
+    state: State<TodoState>,
+) -> Result<Vec<Todo>, AppError> {
+    state.reorder_subtasks(parent_id, &ids)
+}
+
+#[tauri::command]
+pub fn move_subtask(
+    id: u32,
+    parent_id: Option<u32>,
+    state: State<TodoState>,
+) -> Result<Vec<Todo>, AppError> {
+    state.move_subtask(id, parent_id)
+}
+
+#[tauri::command]
+pub fn move_todo(
+    id: u32,
+    before_id: Option<u32>,
+    after_id: Option<u32>,
+    state: State<TodoState>,
+) -> Result<Vec<Todo>, AppError> {
+    state.move_todo(id, before_id, after_id)
+}
+
+#[tauri::command]
+pub fn toggle_todo(id: u32, state: State<TodoState>) -> Result<Vec<Todo>, AppError> {
     state.toggle(id)
 }
 
 #[tauri::command]
-pub fn delete_todo(id: u32, state: State<TodoState>) -> Result<Vec<Todo>, String> {
+pub fn delete_todo(id: u32, state: State<TodoState>) -> Result<Vec<Todo>, AppError> {
     state.delete(id)
 }
+
+#[tauri::command]
+pub fn get_overdue_todos(state: State<TodoState>) -> Result<Vec<Todo>, AppError> {
+    state.overdue(chrono::Local::now().date_naive())
+}
+
+#[tauri::command]
+pub fn get_todos_due_today(state: State<TodoState>) -> Result<Vec<Todo>, AppError> {
+    state.due_today(chrono::Local::now().date_naive())
+}
+
+#[tauri::command]
+pub fn get_todos_by_category(
+    category: String,
+    state: State<TodoState>,
+) -> Result<Vec<Todo>, AppError> {
+    state.by_category(&category)
+}
+
+#[tauri::command]
+pub fn get_todos_by_priority(
+    priority: Priority,
+    state: State<TodoState>,
+) -> Result<Vec<Todo>, AppError> {
+    state.by_priority(priority)
+}
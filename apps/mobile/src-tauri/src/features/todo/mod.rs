@@ -1,4 +1,6 @@
 pub mod commands;
 pub mod model;
+pub mod quick_add;
+pub mod recurrence;
 pub mod repository;
 pub mod service;
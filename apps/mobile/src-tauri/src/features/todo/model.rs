@@ -1,8 +1,150 @@
+use super::recurrence::Recurrence;
+use crate::serde_util::nullable;
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 
-#[derive(Clone, Serialize, Deserialize, Debug)]
+/// Declared from least to most urgent, so `Ord` ranks `High` highest
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    Low,
+    #[default]
+    Medium,
+    High,
+}
+
+impl Priority {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Priority::Low => "low",
+            Priority::Medium => "medium",
+            Priority::High => "high",
+        }
+    }
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Priority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "low" => Ok(Priority::Low),
+            "medium" => Ok(Priority::Medium),
+            "high" => Ok(Priority::High),
+            _ => Err(format!("Unknown priority: {}", s)),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
 pub struct Todo {
     pub id: u32,
     pub text: String,
     pub completed: bool,
+    /// `YYYY-MM-DD`
+    pub due_date: Option<String>,
+    /// `HH:MM`, on the due date
+    pub due_time: Option<String>,
+    pub priority: Priority,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub notes: Option<String>,
+    /// Set on the open occurrence of a recurring todo; completing it moves the rule to the next one
+    pub recurrence: Option<Recurrence>,
+    /// 1-based position in the series
+    pub occurrence: u32,
+    pub created_at: String,
+    pub updated_at: String,
+    pub completed_at: Option<String>,
+    /// Set on subtasks
+    pub parent_id: Option<u32>,
+    /// Manual order among siblings; gaps are normal, only the relative order matters
+    pub position: i64,
+    /// Ordered children; only filled in when todos are returned as a tree
+    #[serde(default)]
+    pub subtasks: Vec<Todo>,
+    /// Completed vs. total direct subtasks, `None` when there are none
+    #[serde(default)]
+    pub progress: Option<SubtaskProgress>,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct SubtaskProgress {
+    pub completed: u32,
+    pub total: u32,
+}
+
+/// Fields for `add_todo`; everything but `text` is optional.
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct NewTodo {
+    pub text: String,
+    pub due_date: Option<String>,
+    pub due_time: Option<String>,
+    pub priority: Priority,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub notes: Option<String>,
+    pub recurrence: Option<Recurrence>,
+}
+
+/// Changes for `update_todo`. Omitted fields stay as they are;
+/// for the optional ones, an explicit `null` clears the value.
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct TodoPatch {
+    pub text: Option<String>,
+    pub completed: Option<bool>,
+    #[serde(deserialize_with = "nullable")]
+    pub due_date: Option<Option<String>>,
+    #[serde(deserialize_with = "nullable")]
+    pub due_time: Option<Option<String>>,
+    pub priority: Option<Priority>,
+    #[serde(deserialize_with = "nullable")]
+    pub category: Option<Option<String>>,
+    pub tags: Option<Vec<String>>,
+    #[serde(deserialize_with = "nullable")]
+    pub notes: Option<Option<String>>,
+    #[serde(deserialize_with = "nullable")]
+    pub recurrence: Option<Option<Recurrence>>,
+}
+
+/// Conditions for `TodoRepository::query`; unset fields don't restrict the result.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TodoFilter {
+    pub completed: Option<bool>,
+    /// Due strictly before this date
+    pub due_before: Option<String>,
+    pub due_on: Option<String>,
+    pub category: Option<String>,
+    pub priority: Option<Priority>,
+}
+
+/// Where `move_todo` puts a todo relative to its target
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Placement {
+    Before,
+    After,
+}
+
+/// How `get_todos` orders each level of the tree
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TodoSort {
+    /// The user's own arrangement
+    #[default]
+    Manual,
+    /// Soonest first, undated last
+    DueDate,
+    /// Most urgent first
+    Priority,
+    /// Oldest first
+    Created,
 }
@@ -0,0 +1,990 @@
+use super::model::{NewTodo, Priority};
+use super::recurrence::{Recurrence, RecurrenceRule, MAX_INTERVAL_DAYS};
+use chrono::{Datelike, Days, Duration, Months, NaiveDate, NaiveTime, Weekday};
+
+const WORKDAYS: [Weekday; 5] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+];
+
+/// Korean particles that attach to a date or time word, e.g. `내일까지`, `3시에`
+const PARTICLES: [&str; 5] = ["까지", "부터", "에는", "에", "엔"];
+
+/// How often the todo repeats, as written. A plain "monthly" takes its day from the due date.
+enum Repeat {
+    Rule(RecurrenceRule),
+    MonthlyOnDueDay,
+}
+
+#[derive(Default)]
+struct Parsed {
+    date: Option<NaiveDate>,
+    time: Option<NaiveTime>,
+    priority: Option<Priority>,
+    category: Option<String>,
+    tags: Vec<String>,
+    repeat: Option<Repeat>,
+}
+
+/// Turns a one-line entry like `pay invoice tomorrow 3pm !high #work every friday` into a todo.
+///
+/// Recognised phrases are removed from the title and stored in their own fields; everything else
+/// is kept as written. Each field is taken from its first phrase only, so a second date stays in
+/// the title. `#word` sets the category, later ones become tags. Relative dates count from
+/// `today`; a recurring todo without a date is due on its first occurrence, and a time without a
+/// date means today.
+pub fn parse_quick_add(input: &str, today: NaiveDate) -> NewTodo {
+    let tokens: Vec<&str> = input.split_whitespace().collect();
+    let words: Vec<String> = tokens.iter().map(|t| normalize(t)).collect();
+    let words: Vec<&str> = words.iter().map(String::as_str).collect();
+
+    let mut parsed = Parsed::default();
+    let mut title = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        match parsed.take(tokens[i], &words[i..], today) {
+            Some(consumed) => i += consumed,
+            None => {
+                title.push(tokens[i]);
+                i += 1;
+            }
+        }
+    }
+    parsed.into_new_todo(title.join(" "), input, today)
+}
+
+/// Lowercases and drops trailing punctuation and Korean particles, for matching only
+fn normalize(token: &str) -> String {
+    let word = token.to_lowercase();
+    let mut word = word.trim_end_matches([',', '.']);
+    for particle in PARTICLES {
+        if let Some(stripped) = word.strip_suffix(particle).filter(|w| !w.is_empty()) {
+            word = stripped;
+            break;
+        }
+    }
+    word.to_string()
+}
+
+impl Parsed {
+    /// Tries to read a phrase starting at `words[0]`; returns how many words it used
+    fn take(&mut self, token: &str, words: &[&str], today: NaiveDate) -> Option<usize> {
+        let tag = token.trim_end_matches([',', '.']).strip_prefix('#');
+        if let Some(tag) = tag.filter(|t| !t.is_empty()) {
+            match self.category {
+                None => self.category = Some(tag.to_string()),
+                Some(_) => self.tags.push(tag.to_string()),
+            }
+            return Some(1);
+        }
+        if self.priority.is_none() {
+            if let Some(priority) = priority(words[0]) {
+                self.priority = Some(priority);
+                return Some(1);
+            }
+        }
+        if self.repeat.is_none() {
+            if let Some((repeat, consumed)) = repeat(words) {
+                self.repeat = Some(repeat);
+                return Some(consumed);
+            }
+        }
+        if self.date.is_none() {
+            if let Some((date, consumed)) = date(words, today) {
+                self.date = Some(date);
+                return Some(consumed);
+            }
+        }
+        if self.time.is_none() {
+            if let Some((time, consumed)) = time(words) {
+                self.time = Some(time);
+                return Some(consumed);
+            }
+        }
+        None
+    }
+
+    fn into_new_todo(self, title: String, input: &str, today: NaiveDate) -> NewTodo {
+        let recurrence = self.repeat.map(|repeat| {
+            let rule = match repeat {
+                Repeat::Rule(rule) => rule,
+                Repeat::MonthlyOnDueDay => RecurrenceRule::Monthly {
+                    day: self.date.unwrap_or(today).day(),
+                },
+            };
+            Recurrence {
+                rule,
+                until: None,
+                count: None,
+            }
+        });
+        let date = self
+            .date
+            .or_else(|| recurrence.as_ref().map(|r| first_occurrence(r, today)))
+            .or_else(|| self.time.map(|_| today));
+
+        NewTodo {
+            // A line that is nothing but a date still needs a title
+            text: if title.is_empty() {
+                input.trim().to_string()
+            } else {
+                title
+            },
+            due_date: date.map(|d| d.format("%Y-%m-%d").to_string()),
+            due_time: self.time.map(|t| t.format("%H:%M").to_string()),
+            priority: self.priority.unwrap_or_default(),
+            category: self.category,
+            tags: self.tags,
+            notes: None,
+            recurrence,
+        }
+    }
+}
+
+/// The first date on or after `today` that the rule allows
+fn first_occurrence(recurrence: &Recurrence, today: NaiveDate) -> NaiveDate {
+    match &recurrence.rule {
+        RecurrenceRule::Weekly { weekdays } if !weekdays.is_empty() => {
+            recurrence.next_after(today - Duration::days(1))
+        }
+        RecurrenceRule::Monthly { .. } => recurrence.next_after(today - Duration::days(1)),
+        _ => None,
+    }
+    .unwrap_or(today)
+}
+
+fn priority(word: &str) -> Option<Priority> {
+    match word.strip_prefix('!')? {
+        "low" | "l" | "낮음" => Some(Priority::Low),
+        "medium" | "med" | "m" | "보통" => Some(Priority::Medium),
+        "high" | "h" | "urgent" | "높음" | "긴급" => Some(Priority::High),
+        _ => None,
+    }
+}
+
+/// Full English or Korean weekday names; `short` also allows `mon`, `tue`, …
+fn weekday(word: &str, short: bool) -> Option<Weekday> {
+    let day = match word {
+        "monday" | "월요일" => Weekday::Mon,
+        "tuesday" | "화요일" => Weekday::Tue,
+        "wednesday" | "수요일" => Weekday::Wed,
+        "thursday" | "목요일" => Weekday::Thu,
+        "friday" | "금요일" => Weekday::Fri,
+        "saturday" | "토요일" => Weekday::Sat,
+        "sunday" | "일요일" => Weekday::Sun,
+        _ if !short => return None,
+        "mon" => Weekday::Mon,
+        "tue" | "tues" => Weekday::Tue,
+        "wed" => Weekday::Wed,
+        "thu" | "thur" | "thurs" => Weekday::Thu,
+        "fri" => Weekday::Fri,
+        "sat" => Weekday::Sat,
+        "sun" => Weekday::Sun,
+        _ => return None,
+    };
+    Some(day)
+}
+
+/// Korean single-character weekdays, as in `매주 월,수` or `매주 월수금`
+fn korean_weekday_chars(word: &str) -> Option<Vec<Weekday>> {
+    let days: Option<Vec<Weekday>> = word
+        .chars()
+        .filter(|c| *c != ',')
+        .map(|c| match c {
+            '월' => Some(Weekday::Mon),
+            '화' => Some(Weekday::Tue),
+            '수' => Some(Weekday::Wed),
+            '목' => Some(Weekday::Thu),
+            '금' => Some(Weekday::Fri),
+            '토' => Some(Weekday::Sat),
+            '일' => Some(Weekday::Sun),
+            _ => None,
+        })
+        .collect();
+    days.filter(|d| !d.is_empty())
+}
+
+fn month(word: &str) -> Option<u32> {
+    let month = match word {
+        "january" | "jan" => 1,
+        "february" | "feb" => 2,
+        "march" | "mar" => 3,
+        "april" | "apr" => 4,
+        "may" => 5,
+        "june" | "jun" => 6,
+        "july" | "jul" => 7,
+        "august" | "aug" => 8,
+        "september" | "sep" | "sept" => 9,
+        "october" | "oct" => 10,
+        "november" | "nov" => 11,
+        "december" | "dec" => 12,
+        _ => return None,
+    };
+    Some(month)
+}
+
+fn number(word: &str) -> Option<u32> {
+    if word.is_empty() || !word.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    word.parse().ok()
+}
+
+/// `15`, `15th`, `1st`, `15일`
+fn day_of_month(word: &str) -> Option<u32> {
+    let digits = ["st", "nd", "rd", "th", "일"]
+        .iter()
+        .find_map(|suffix| word.strip_suffix(suffix))
+        .unwrap_or(word);
+    number(digits).filter(|d| (1..=31).contains(d))
+}
+
+/// Like `day_of_month` but the suffix is required, so `every 3` isn't read as a day
+fn ordinal(word: &str) -> Option<u32> {
+    day_of_month(word).filter(|_| number(word).is_none())
+}
+
+/// `Nunit` with a Korean unit attached, e.g. `3일`, `2주`, `3일마다`
+fn korean_count<'a>(word: &'a str, unit: &str) -> Option<(u32, &'a str)> {
+    let split = word.find(unit)?;
+    let rest = &word[split + unit.len()..];
+    number(&word[..split]).map(|n| (n, rest))
+}
+
+fn monday_of(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(i64::from(date.weekday().num_days_from_monday()))
+}
+
+fn on_or_after(today: NaiveDate, day: Weekday) -> NaiveDate {
+    let ahead = (7 + day.num_days_from_monday() - today.weekday().num_days_from_monday()) % 7;
+    today + Duration::days(i64::from(ahead))
+}
+
+/// A month and day without a year: this year's, or next year's once it has passed
+fn upcoming(today: NaiveDate, month: u32, day: u32) -> Option<NaiveDate> {
+    let date = NaiveDate::from_ymd_opt(today.year(), month, day)?;
+    if date >= today {
+        Some(date)
+    } else {
+        NaiveDate::from_ymd_opt(today.year() + 1, month, day)
+    }
+}
+
+/// `None` for an unknown unit, or a date too far out to exist
+fn add_units(today: NaiveDate, count: u32, unit: &str) -> Option<NaiveDate> {
+    let days = u64::from(count);
+    match unit {
+        "day" | "days" | "일" => today.checked_add_days(Days::new(days)),
+        "week" | "weeks" | "주" => today.checked_add_days(Days::new(days * 7)),
+        "month" | "months" | "달" | "개월" => today.checked_add_months(Months::new(count)),
+        _ => None,
+    }
+}
+
+fn first_of_next_month(today: NaiveDate) -> NaiveDate {
+    today
+        .with_day(1)
+        .and_then(|d| d.checked_add_months(Months::new(1)))
+        .expect("the first of next month exists")
+}
+
+fn date(words: &[&str], today: NaiveDate) -> Option<(NaiveDate, usize)> {
+    let first = *words.first()?;
+    let second = words.get(1).copied();
+    let third = words.get(2).copied();
+
+    if matches!(first, "on" | "by" | "due" | "this") {
+        if let Some(day) = second.and_then(|w| weekday(w, true)) {
+            return Some((on_or_after(today, day), 2));
+        }
+        return date(&words[1..], today).map(|(d, n)| (d, n + 1));
+    }
+
+    let days = match first {
+        "today" | "tonight" | "오늘" | "오늘밤" => Some(0),
+        "tomorrow" | "tmr" | "tmrw" | "내일" => Some(1),
+        "모레" => Some(2),
+        "글피" => Some(3),
+        _ => None,
+    };
+    if let Some(days) = days {
+        return Some((today + Duration::days(days), 1));
+    }
+    if words.starts_with(&["day", "after", "tomorrow"]) {
+        return Some((today + Duration::days(2), 3));
+    }
+
+    // in 3 days, in a week
+    if first == "in" {
+        let count = second.and_then(|w| match w {
+            "a" | "an" | "one" => Some(1),
+            _ => number(w),
+        });
+        if let Some(date) = count
+            .zip(third)
+            .and_then(|(n, unit)| add_units(today, n, unit))
+        {
+            return Some((date, 3));
+        }
+    }
+    // 3일 후, 2주 뒤, 3일후
+    for unit in ["일", "주", "개월", "달"] {
+        if let Some((count, rest)) = korean_count(first, unit) {
+            let after = |w: &str| w == "후" || w == "뒤";
+            if after(rest) {
+                return add_units(today, count, unit).map(|d| (d, 1));
+            }
+            if rest.is_empty() && second.is_some_and(after) {
+                return add_units(today, count, unit).map(|d| (d, 2));
+            }
+        }
+    }
+
+    // next week, next friday, 다음주 금요일, 다음 주, 이번주 금요일
+    let (week, consumed) = match (first, second) {
+        ("next", Some("week")) | ("다음", Some("주")) => (Some(7), 2),
+        ("다음주", _) => (Some(7), 1),
+        ("이번", Some("주")) => (Some(0), 2),
+        ("이번주", _) => (Some(0), 1),
+        _ => (None, 0),
+    };
+    if let Some(offset) = week {
+        let monday = monday_of(today) + Duration::days(offset);
+        if let Some(day) = words.get(consumed).and_then(|w| weekday(w, false)) {
+            let date = monday + Duration::days(i64::from(day.num_days_from_monday()));
+            return Some((date, consumed + 1));
+        }
+        // A bare "this week" doesn't say which day
+        return (offset > 0).then_some((monday, consumed));
+    }
+    if first == "next" {
+        if let Some(day) = second.and_then(|w| weekday(w, true)) {
+            let date = monday_of(today) + Duration::days(7 + i64::from(day.num_days_from_monday()));
+            return Some((date, 2));
+        }
+    }
+    match (first, second) {
+        ("next", Some("month")) | ("다음", Some("달")) => {
+            return Some((first_of_next_month(today), 2))
+        }
+        ("다음달", _) => return Some((first_of_next_month(today), 1)),
+        _ => {}
+    }
+    if let Some(day) = weekday(first, false) {
+        return Some((on_or_after(today, day), 1));
+    }
+
+    absolute_date(words, today)
+}
+
+fn absolute_date(words: &[&str], today: NaiveDate) -> Option<(NaiveDate, usize)> {
+    let first = words[0];
+    let second = words.get(1).copied();
+
+    if let Ok(date) = NaiveDate::parse_from_str(first, "%Y-%m-%d") {
+        return Some((date, 1));
+    }
+    // 3/20 and 3/20/2025, month first
+    let parts: Vec<&str> = first.split('/').collect();
+    if let [month, day, rest @ ..] = parts.as_slice() {
+        let (month, day) = (number(month)?, number(day)?);
+        return match rest {
+            [] => upcoming(today, month, day).map(|d| (d, 1)),
+            [year] => NaiveDate::from_ymd_opt(number(year)? as i32, month, day).map(|d| (d, 1)),
+            _ => None,
+        };
+    }
+    // march 20th, 20 march
+    if let Some(month) = month(first) {
+        if let Some(day) = second.and_then(day_of_month) {
+            let year = words.get(2).and_then(|w| number(w)).filter(|y| *y >= 1000);
+            return match year {
+                Some(year) => NaiveDate::from_ymd_opt(year as i32, month, day).map(|d| (d, 3)),
+                None => upcoming(today, month, day).map(|d| (d, 2)),
+            };
+        }
+    }
+    if let (Some(day), Some(month)) = (day_of_month(first), second.and_then(month)) {
+        return upcoming(today, month, day).map(|d| (d, 2));
+    }
+    korean_date(words, today)
+}
+
+/// `3월 20일`, `3월20일`, `2025년 3월 20일`
+fn korean_date(words: &[&str], today: NaiveDate) -> Option<(NaiveDate, usize)> {
+    let (year, rest, used) = match korean_count(words[0], "년") {
+        Some((year, "")) => (Some(year as i32), &words[1..], 1),
+        _ => (None, words, 0),
+    };
+    let (month, day_word) = korean_count(rest.first()?, "월")?;
+    let (day, used) = if day_word.is_empty() {
+        (
+            rest.get(1)
+                .and_then(|w| w.strip_suffix('일'))
+                .and_then(number)?,
+            used + 2,
+        )
+    } else {
+        (number(day_word.strip_suffix('일')?)?, used + 1)
+    };
+    let date = match year {
+        Some(year) => NaiveDate::from_ymd_opt(year, month, day)?,
+        None => upcoming(today, month, day)?,
+    };
+    Some((date, used))
+}
+
+fn time(words: &[&str]) -> Option<(NaiveTime, usize)> {
+    let first = *words.first()?;
+    match first {
+        "noon" | "정오" => return Some((NaiveTime::from_hms_opt(12, 0, 0)?, 1)),
+        "midnight" | "자정" => return Some((NaiveTime::from_hms_opt(0, 0, 0)?, 1)),
+        "at" => {
+            let rest = &words[1..];
+            return english_time(rest, true)
+                .or_else(|| time(rest))
+                .map(|(t, n)| (t, n + 1));
+        }
+        _ => {}
+    }
+    english_time(words, false).or_else(|| korean_time(words))
+}
+
+/// `3pm`, `3:30 pm`, `15:00`, and after "at" also a bare hour
+fn english_time(words: &[&str], bare_hour: bool) -> Option<(NaiveTime, usize)> {
+    let first = *words.first()?;
+    let (clock, meridiem, used) = if let Some(clock) = first.strip_suffix("am") {
+        (clock, Some(false), 1)
+    } else if let Some(clock) = first.strip_suffix("pm") {
+        (clock, Some(true), 1)
+    } else {
+        match words.get(1).copied() {
+            Some("am") => (first, Some(false), 2),
+            Some("pm") => (first, Some(true), 2),
+            _ => (first, None, 1),
+        }
+    };
+
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) if minute.len() == 2 => (number(hour)?, number(minute)?),
+        Some(_) => return None,
+        None if meridiem.is_some() || bare_hour => (number(clock)?, 0),
+        None => return None,
+    };
+    let hour = match meridiem {
+        Some(pm) if (1..=12).contains(&hour) => hour % 12 + if pm { 12 } else { 0 },
+        Some(_) => return None,
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0).map(|t| (t, used))
+}
+
+/// `오후 3시`, `3시 30분`, `저녁 7시반`, `15시`
+fn korean_time(words: &[&str]) -> Option<(NaiveTime, usize)> {
+    let period = |w: &str| -> Option<bool> {
+        match w {
+            "오전" | "아침" | "새벽" => Some(false),
+            "오후" | "낮" | "저녁" | "밤" => Some(true),
+            _ => None,
+        }
+    };
+    let (pm, rest, mut used) = match period(words[0]) {
+        Some(pm) => (Some(pm), &words[1..], 1),
+        None => (None, words, 0),
+    };
+    let (hour, after_hour) = korean_count(rest.first()?, "시")?;
+    used += 1;
+    let minute = match after_hour {
+        "반" => 30,
+        "" => match rest
+            .get(1)
+            .and_then(|w| w.strip_suffix('분'))
+            .and_then(number)
+        {
+            Some(minute) => {
+                used += 1;
+                minute
+            }
+            None => 0,
+        },
+        minutes => number(minutes.strip_suffix('분')?)?,
+    };
+    let hour = match pm {
+        Some(true) if hour < 12 => hour + 12,
+        Some(false) if hour == 12 => 0,
+        _ => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0).map(|t| (t, used))
+}
+
+fn weekly(weekdays: Vec<Weekday>) -> Repeat {
+    Repeat::Rule(RecurrenceRule::Weekly { weekdays })
+}
+
+fn repeat(words: &[&str]) -> Option<(Repeat, usize)> {
+    let first = *words.first()?;
+    let second = words.get(1).copied();
+
+    match first {
+        "daily" | "everyday" | "매일" => return Some((Repeat::Rule(RecurrenceRule::Daily), 1)),
+        "weekly" => return Some((weekly(vec![]), 1)),
+        "monthly" => return Some((Repeat::MonthlyOnDueDay, 1)),
+        "평일마다" => return Some((weekly(WORKDAYS.to_vec()), 1)),
+        "격일" => return Some((Repeat::Rule(RecurrenceRule::EveryNDays { days: 2 }), 1)),
+        "매주" => {
+            if second == Some("평일") {
+                return Some((weekly(WORKDAYS.to_vec()), 2));
+            }
+            let (days, used) = weekday_list(&words[1..], true);
+            return Some((weekly(days), 1 + used));
+        }
+        "매월" | "매달" => {
+            return Some(match second.and_then(day_of_month) {
+                Some(day) => (Repeat::Rule(RecurrenceRule::Monthly { day }), 2),
+                None => (Repeat::MonthlyOnDueDay, 1),
+            })
+        }
+        "every" => return every(&words[1..]).map(|(r, n)| (r, n + 1)),
+        _ => {}
+    }
+
+    // 3일마다, 2주마다, 금요일마다
+    let each = first.strip_suffix("마다")?;
+    if let Some(day) = weekday(each, false) {
+        return Some((weekly(vec![day]), 1));
+    }
+    let days = match korean_count(each, "일") {
+        Some((days, "")) => days,
+        _ => match korean_count(each, "주") {
+            Some((weeks, "")) => weeks.checked_mul(7)?,
+            _ => return None,
+        },
+    };
+    (1..=MAX_INTERVAL_DAYS)
+        .contains(&days)
+        .then_some((Repeat::Rule(RecurrenceRule::EveryNDays { days }), 1))
+}
+
+/// What follows "every"
+fn every(words: &[&str]) -> Option<(Repeat, usize)> {
+    let first = *words.first()?;
+    let second = words.get(1).copied();
+
+    match (first, second) {
+        ("day", _) => return Some((Repeat::Rule(RecurrenceRule::Daily), 1)),
+        ("other", Some("day")) => {
+            return Some((Repeat::Rule(RecurrenceRule::EveryNDays { days: 2 }), 2))
+        }
+        ("weekday" | "weekdays", _) => return Some((weekly(WORKDAYS.to_vec()), 1)),
+        ("week", _) => return Some((weekly(vec![]), 1)),
+        ("month", _) => {
+            // every month on the 15th
+            let rest = &words[1..];
+            let rest = rest.strip_prefix(&["on"]).unwrap_or(rest);
+            let rest = rest.strip_prefix(&["the"]).unwrap_or(rest);
+            let used = words.len() - rest.len();
+            return Some(match rest.first().and_then(|w| ordinal(w)) {
+                Some(day) => (Repeat::Rule(RecurrenceRule::Monthly { day }), used + 1),
+                None => (Repeat::MonthlyOnDueDay, 1),
+            });
+        }
+        _ => {}
+    }
+
+    if let Some(count) = number(first).filter(|n| *n > 0) {
+        let days = match second? {
+            "days" | "day" => count,
+            "weeks" | "week" => count.checked_mul(7)?,
+            _ => return None,
+        };
+        if days > MAX_INTERVAL_DAYS {
+            return None;
+        }
+        return Some((Repeat::Rule(RecurrenceRule::EveryNDays { days }), 2));
+    }
+    if let Some(day) = ordinal(first) {
+        return Some((Repeat::Rule(RecurrenceRule::Monthly { day }), 1));
+    }
+    let (days, used) = weekday_list(words, false);
+    (!days.is_empty()).then(|| (weekly(days), used))
+}
+
+/// Weekdays in a row, joined by commas, "and" or "&": `mon and thu`, `월요일 수요일`, `월,수`.
+/// Korean single characters are only read when `korean_chars` is set.
+fn weekday_list(words: &[&str], korean_chars: bool) -> (Vec<Weekday>, usize) {
+    let mut days = Vec::new();
+    let mut used = 0;
+    let mut i = 0;
+    while let Some(word) = words.get(i) {
+        let found = match weekday(word, true) {
+            Some(day) => vec![day],
+            None if korean_chars => match korean_weekday_chars(word) {
+                Some(found) => found,
+                None => break,
+            },
+            None if matches!(*word, "and" | "&") && !days.is_empty() => {
+                i += 1;
+                continue;
+            }
+            None => break,
+        };
+        for day in found {
+            if !days.contains(&day) {
+                days.push(day);
+            }
+        }
+        i += 1;
+        used = i;
+    }
+    (days, used)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Case {
+        input: &'static str,
+        text: &'static str,
+        due_date: Option<&'static str>,
+        due_time: Option<&'static str>,
+        priority: Priority,
+        category: Option<&'static str>,
+        rule: Option<RecurrenceRule>,
+    }
+
+    fn weekly_on(days: &[Weekday]) -> Option<RecurrenceRule> {
+        Some(RecurrenceRule::Weekly {
+            weekdays: days.to_vec(),
+        })
+    }
+
+    /// A Wednesday
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, 12).unwrap()
+    }
+
+    fn check(cases: Vec<Case>) {
+        for case in cases {
+            let todo = parse_quick_add(case.input, today());
+            let rule = todo.recurrence.map(|r| r.rule);
+            assert_eq!(
+                (
+                    todo.text.as_str(),
+                    todo.due_date.as_deref(),
+                    todo.due_time.as_deref(),
+                    todo.priority,
+                    todo.category.as_deref(),
+                    rule
+                ),
+                (
+                    case.text,
+                    case.due_date,
+                    case.due_time,
+                    case.priority,
+                    case.category,
+                    case.rule
+                ),
+                "input: {:?}",
+                case.input
+            );
+        }
+    }
+
+    #[test]
+    fn test_english_phrases() {
+        check(vec![
+            Case {
+                input: "pay invoice tomorrow 3pm !high #work every friday",
+                text: "pay invoice",
+                due_date: Some("2025-03-13"),
+                due_time: Some("15:00"),
+                priority: Priority::High,
+                category: Some("work"),
+                rule: weekly_on(&[Weekday::Fri]),
+            },
+            Case {
+                input: "Buy milk",
+                text: "Buy milk",
+                ..Default::default()
+            },
+            Case {
+                input: "call mom on friday at 6:30pm",
+                text: "call mom",
+                due_date: Some("2025-03-14"),
+                due_time: Some("18:30"),
+                ..Default::default()
+            },
+            Case {
+                input: "dentist next mon 9 am",
+                text: "dentist",
+                due_date: Some("2025-03-17"),
+                due_time: Some("09:00"),
+                ..Default::default()
+            },
+            Case {
+                input: "report in 3 days !low",
+                text: "report",
+                due_date: Some("2025-03-15"),
+                priority: Priority::Low,
+                ..Default::default()
+            },
+            Case {
+                input: "taxes due 2025-04-15 #finance",
+                text: "taxes",
+                due_date: Some("2025-04-15"),
+                category: Some("finance"),
+                ..Default::default()
+            },
+            Case {
+                input: "trip March 20th",
+                text: "trip",
+                due_date: Some("2025-03-20"),
+                ..Default::default()
+            },
+            // Already past this year
+            Case {
+                input: "party 3/1",
+                text: "party",
+                due_date: Some("2026-03-01"),
+                ..Default::default()
+            },
+            Case {
+                input: "lunch at noon",
+                text: "lunch",
+                due_date: Some("2025-03-12"),
+                due_time: Some("12:00"),
+                ..Default::default()
+            },
+            Case {
+                input: "plan sprint next week",
+                text: "plan sprint",
+                due_date: Some("2025-03-17"),
+                ..Default::default()
+            },
+            Case {
+                input: "buy a gift wednesday",
+                text: "buy a gift",
+                due_date: Some("2025-03-12"),
+                ..Default::default()
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_english_recurrence() {
+        check(vec![
+            Case {
+                input: "standup every weekday at 9:15",
+                text: "standup",
+                due_date: Some("2025-03-12"),
+                due_time: Some("09:15"),
+                rule: weekly_on(&WORKDAYS),
+                ..Default::default()
+            },
+            Case {
+                input: "rent every month on the 1st",
+                text: "rent",
+                due_date: Some("2025-04-01"),
+                rule: Some(RecurrenceRule::Monthly { day: 1 }),
+                ..Default::default()
+            },
+            Case {
+                input: "water plants every 3 days",
+                text: "water plants",
+                due_date: Some("2025-03-12"),
+                rule: Some(RecurrenceRule::EveryNDays { days: 3 }),
+                ..Default::default()
+            },
+            Case {
+                input: "backup every 2 weeks",
+                text: "backup",
+                due_date: Some("2025-03-12"),
+                rule: Some(RecurrenceRule::EveryNDays { days: 14 }),
+                ..Default::default()
+            },
+            Case {
+                input: "review finances monthly",
+                text: "review finances",
+                due_date: Some("2025-03-12"),
+                rule: Some(RecurrenceRule::Monthly { day: 12 }),
+                ..Default::default()
+            },
+            Case {
+                input: "gym every mon and thu",
+                text: "gym",
+                due_date: Some("2025-03-13"),
+                rule: weekly_on(&[Weekday::Mon, Weekday::Thu]),
+                ..Default::default()
+            },
+            Case {
+                input: "pay card every 31st",
+                text: "pay card",
+                due_date: Some("2025-03-31"),
+                rule: Some(RecurrenceRule::Monthly { day: 31 }),
+                ..Default::default()
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_korean_phrases() {
+        check(vec![
+            Case {
+                input: "내일 오후 3시에 회의 !높음 #업무",
+                text: "회의",
+                due_date: Some("2025-03-13"),
+                due_time: Some("15:00"),
+                priority: Priority::High,
+                category: Some("업무"),
+                ..Default::default()
+            },
+            Case {
+                input: "모레까지 보고서 제출",
+                text: "보고서 제출",
+                due_date: Some("2025-03-14"),
+                ..Default::default()
+            },
+            Case {
+                input: "다음주 금요일 저녁 7시 30분 회식",
+                text: "회식",
+                due_date: Some("2025-03-21"),
+                due_time: Some("19:30"),
+                ..Default::default()
+            },
+            Case {
+                input: "3월 20일 치과",
+                text: "치과",
+                due_date: Some("2025-03-20"),
+                ..Default::default()
+            },
+            Case {
+                input: "5일 후 약속",
+                text: "약속",
+                due_date: Some("2025-03-17"),
+                ..Default::default()
+            },
+            Case {
+                input: "금요일까지 과제",
+                text: "과제",
+                due_date: Some("2025-03-14"),
+                ..Default::default()
+            },
+            Case {
+                input: "오전 10시반 병원",
+                text: "병원",
+                due_date: Some("2025-03-12"),
+                due_time: Some("10:30"),
+                ..Default::default()
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_korean_recurrence() {
+        check(vec![
+            Case {
+                input: "매주 월,수 운동",
+                text: "운동",
+                due_date: Some("2025-03-12"),
+                rule: weekly_on(&[Weekday::Mon, Weekday::Wed]),
+                ..Default::default()
+            },
+            Case {
+                input: "매월 25일 월세 내기",
+                text: "월세 내기",
+                due_date: Some("2025-03-25"),
+                rule: Some(RecurrenceRule::Monthly { day: 25 }),
+                ..Default::default()
+            },
+            Case {
+                input: "3일마다 물 주기",
+                text: "물 주기",
+                due_date: Some("2025-03-12"),
+                rule: Some(RecurrenceRule::EveryNDays { days: 3 }),
+                ..Default::default()
+            },
+            Case {
+                input: "매일 아침 8시 약 먹기",
+                text: "약 먹기",
+                due_date: Some("2025-03-12"),
+                due_time: Some("08:00"),
+                rule: Some(RecurrenceRule::Daily),
+                ..Default::default()
+            },
+            Case {
+                input: "토요일마다 청소",
+                text: "청소",
+                due_date: Some("2025-03-15"),
+                rule: weekly_on(&[Weekday::Sat]),
+                ..Default::default()
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_leaves_ambiguous_words_alone() {
+        check(vec![
+            // Abbreviated weekdays and bare numbers need context
+            Case {
+                input: "meet sat",
+                text: "meet sat",
+                ..Default::default()
+            },
+            Case {
+                input: "read 1984 at home",
+                text: "read 1984 at home",
+                ..Default::default()
+            },
+            // Only the first date is used; the second stays in the title
+            Case {
+                input: "move today or tomorrow",
+                text: "move or tomorrow",
+                due_date: Some("2025-03-12"),
+                ..Default::default()
+            },
+            // Counts too large for a date or interval are just words
+            Case {
+                input: "report in 4000000000 days",
+                text: "report in 4000000000 days",
+                ..Default::default()
+            },
+            Case {
+                input: "3000000000일 후 보고",
+                text: "3000000000일 후 보고",
+                ..Default::default()
+            },
+            Case {
+                input: "standup every 999999999 weeks",
+                text: "standup every 999999999 weeks",
+                ..Default::default()
+            },
+            Case {
+                input: "정리 999999999주마다",
+                text: "정리 999999999주마다",
+                ..Default::default()
+            },
+            // Longer than a recurrence may be
+            Case {
+                input: "renew every 5000 days",
+                text: "renew every 5000 days",
+                ..Default::default()
+            },
+            // Nothing left but the date: keep the input as the title
+            Case {
+                input: "tomorrow",
+                text: "tomorrow",
+                due_date: Some("2025-03-13"),
+                ..Default::default()
+            },
+        ]);
+
+        let todo = parse_quick_add("ship it #work #urgent #q1", today());
+        assert_eq!(todo.category.as_deref(), Some("work"));
+        assert_eq!(todo.tags, vec!["urgent", "q1"]);
+    }
+}
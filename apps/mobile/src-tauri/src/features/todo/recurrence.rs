@@ -0,0 +1,334 @@
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+/// Longest `EveryNDays` interval, about ten years
+pub const MAX_INTERVAL_DAYS: u32 = 3660;
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RecurrenceRule {
+    Daily,
+    /// On each listed weekday; empty means the weekday of the first due date
+    Weekly {
+        #[serde(default)]
+        weekdays: Vec<Weekday>,
+    },
+    /// On this day of the month, or the month's last day when it is shorter
+    Monthly {
+        day: u32,
+    },
+    EveryNDays {
+        days: u32,
+    },
+}
+
+/// When a todo comes back after being completed.
+/// `until` (inclusive) and `count` (occurrences, including the first) end the series.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct Recurrence {
+    #[serde(flatten)]
+    pub rule: RecurrenceRule,
+    #[serde(default)]
+    pub until: Option<String>,
+    #[serde(default)]
+    pub count: Option<u32>,
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .and_then(|d| d.pred_opt())
+        .map(|d| d.day())
+        .unwrap_or(28)
+}
+
+/// `day` of the given month, clamped to its last day
+fn clamped_day(year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(year, month, day.min(days_in_month(year, month)))
+}
+
+impl Recurrence {
+    pub fn validate(&self) -> Result<(), String> {
+        match &self.rule {
+            RecurrenceRule::Monthly { day } if !(1..=31).contains(day) => {
+                return Err(format!("Day of month must be 1-31, got {}", day))
+            }
+            RecurrenceRule::EveryNDays { days } if !(1..=MAX_INTERVAL_DAYS).contains(days) => {
+                return Err(format!(
+                    "Interval must be 1-{} days, got {}",
+                    MAX_INTERVAL_DAYS, days
+                ))
+            }
+            _ => {}
+        }
+        if self.count == Some(0) {
+            return Err("Count must be at least 1".to_string());
+        }
+        self.until_date()?;
+        Ok(())
+    }
+
+    fn until_date(&self) -> Result<Option<NaiveDate>, String> {
+        self.until
+            .as_deref()
+            .map(|d| {
+                NaiveDate::parse_from_str(d, DATE_FORMAT)
+                    .map_err(|_| format!("Invalid end date: {}", d))
+            })
+            .transpose()
+    }
+
+    /// The first date after `date` that matches the rule, ignoring `until` and `count`.
+    /// `None` when that date is past the last one `NaiveDate` can hold.
+    pub fn next_after(&self, date: NaiveDate) -> Option<NaiveDate> {
+        match &self.rule {
+            RecurrenceRule::Daily => date.checked_add_days(Days::new(1)),
+            RecurrenceRule::EveryNDays { days } => {
+                date.checked_add_days(Days::new(u64::from(*days)))
+            }
+            RecurrenceRule::Weekly { weekdays } => {
+                if weekdays.is_empty() {
+                    return date.checked_add_days(Days::new(7));
+                }
+                (1..=7)
+                    .filter_map(|offset| date.checked_add_days(Days::new(offset)))
+                    .find(|d| weekdays.contains(&d.weekday()))
+            }
+            RecurrenceRule::Monthly { day } => {
+                let this_month = clamped_day(date.year(), date.month(), *day)?;
+                if this_month > date {
+                    return Some(this_month);
+                }
+                let (year, month) = if date.month() == 12 {
+                    (date.year() + 1, 1)
+                } else {
+                    (date.year(), date.month() + 1)
+                };
+                clamped_day(year, month, *day)
+            }
+        }
+    }
+
+    /// The occurrence that follows number `occurrence`, which was due on `due`.
+    ///
+    /// Occurrences that already lie before `today` (the item was completed late) are skipped but
+    /// still count towards `count`. Returns the date and its occurrence number, or `None` once the
+    /// series has ended (or would run past the last representable date).
+    pub fn next_occurrence(
+        &self,
+        due: NaiveDate,
+        occurrence: u32,
+        today: NaiveDate,
+    ) -> Option<(NaiveDate, u32)> {
+        let until = self.until_date().ok().flatten();
+        let mut date = due;
+        let mut number = occurrence;
+        loop {
+            date = self.next_after(date)?;
+            number += 1;
+            if self.count.is_some_and(|count| number > count) || until.is_some_and(|u| date > u) {
+                return None;
+            }
+            if date >= today {
+                return Some((date, number));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, DATE_FORMAT).unwrap()
+    }
+
+    fn rule(rule: RecurrenceRule) -> Recurrence {
+        Recurrence {
+            rule,
+            until: None,
+            count: None,
+        }
+    }
+
+    fn monthly(day: u32) -> Recurrence {
+        rule(RecurrenceRule::Monthly { day })
+    }
+
+    #[test]
+    fn test_monthly_clamps_to_month_end() {
+        let r = monthly(31);
+        let mut d = date("2024-01-31");
+        let mut seen = Vec::new();
+        for _ in 0..4 {
+            d = r.next_after(d).unwrap();
+            seen.push(d.to_string());
+        }
+        // Clamping in a short month doesn't drift the series to the 29th/30th
+        assert_eq!(
+            seen,
+            vec!["2024-02-29", "2024-03-31", "2024-04-30", "2024-05-31"]
+        );
+
+        assert_eq!(
+            monthly(31).next_after(date("2023-01-31")),
+            Some(date("2023-02-28"))
+        );
+        assert_eq!(
+            monthly(30).next_after(date("2024-02-29")),
+            Some(date("2024-03-30"))
+        );
+        // Later in the same month when the day hasn't come yet
+        assert_eq!(
+            monthly(31).next_after(date("2024-04-15")),
+            Some(date("2024-04-30"))
+        );
+        assert_eq!(
+            monthly(15).next_after(date("2024-12-20")),
+            Some(date("2025-01-15"))
+        );
+    }
+
+    #[test]
+    fn test_leap_years() {
+        let r = monthly(29);
+        assert_eq!(r.next_after(date("2024-01-29")), Some(date("2024-02-29")));
+        assert_eq!(r.next_after(date("2100-01-29")), Some(date("2100-02-28")));
+        assert_eq!(r.next_after(date("2000-01-29")), Some(date("2000-02-29")));
+
+        let daily = rule(RecurrenceRule::Daily);
+        assert_eq!(
+            daily.next_after(date("2024-02-28")),
+            Some(date("2024-02-29"))
+        );
+        assert_eq!(
+            daily.next_after(date("2023-02-28")),
+            Some(date("2023-03-01"))
+        );
+
+        let every = rule(RecurrenceRule::EveryNDays { days: 366 });
+        assert_eq!(
+            every.next_after(date("2024-01-01")),
+            Some(date("2025-01-01"))
+        );
+    }
+
+    #[test]
+    fn test_weekly_on_weekdays() {
+        let r = rule(RecurrenceRule::Weekly {
+            weekdays: vec![Weekday::Mon, Weekday::Thu],
+        });
+        // 2024-12-30 is a Monday; the series crosses the year boundary
+        assert_eq!(r.next_after(date("2024-12-30")), Some(date("2025-01-02")));
+        assert_eq!(r.next_after(date("2025-01-02")), Some(date("2025-01-06")));
+        // From a day that isn't in the set
+        assert_eq!(r.next_after(date("2025-01-04")), Some(date("2025-01-06")));
+
+        let same_day = rule(RecurrenceRule::Weekly { weekdays: vec![] });
+        assert_eq!(
+            same_day.next_after(date("2024-02-26")),
+            Some(date("2024-03-04"))
+        );
+    }
+
+    #[test]
+    fn test_series_end_and_late_completion() {
+        let r = Recurrence {
+            rule: RecurrenceRule::Daily,
+            until: Some("2025-01-05".into()),
+            count: None,
+        };
+        let today = date("2025-01-01");
+        assert_eq!(
+            r.next_occurrence(date("2025-01-04"), 1, today),
+            Some((date("2025-01-05"), 2))
+        );
+        assert_eq!(r.next_occurrence(date("2025-01-05"), 2, today), None);
+
+        let r = Recurrence {
+            rule: RecurrenceRule::EveryNDays { days: 2 },
+            until: None,
+            count: Some(3),
+        };
+        assert_eq!(
+            r.next_occurrence(date("2025-01-01"), 2, today),
+            Some((date("2025-01-03"), 3))
+        );
+        assert_eq!(r.next_occurrence(date("2025-01-03"), 3, today), None);
+
+        // Completed four days late: missed occurrences are skipped but counted
+        let r = Recurrence {
+            rule: RecurrenceRule::Daily,
+            until: None,
+            count: Some(10),
+        };
+        assert_eq!(
+            r.next_occurrence(date("2025-01-01"), 1, date("2025-01-05")),
+            Some((date("2025-01-05"), 5))
+        );
+    }
+
+    #[test]
+    fn test_series_ends_at_last_representable_date() {
+        let huge = rule(RecurrenceRule::EveryNDays { days: u32::MAX });
+        let today = date("2025-01-01");
+        assert_eq!(huge.next_after(today), None);
+        assert_eq!(huge.next_occurrence(today, 1, today), None);
+        for r in [
+            rule(RecurrenceRule::Daily),
+            monthly(31),
+            rule(RecurrenceRule::Weekly {
+                weekdays: vec![Weekday::Mon],
+            }),
+        ] {
+            assert_eq!(r.next_after(NaiveDate::MAX), None);
+            assert_eq!(r.next_occurrence(NaiveDate::MAX, 1, today), None);
+        }
+    }
+
+    #[test]
+    fn test_validate_and_serde() {
+        assert!(monthly(0).validate().is_err());
+        assert!(monthly(32).validate().is_err());
+        assert!(rule(RecurrenceRule::EveryNDays { days: 0 })
+            .validate()
+            .is_err());
+        assert!(rule(RecurrenceRule::EveryNDays { days: u32::MAX })
+            .validate()
+            .is_err());
+        assert!(rule(RecurrenceRule::EveryNDays {
+            days: MAX_INTERVAL_DAYS
+        })
+        .validate()
+        .is_ok());
+        let bad_until = Recurrence {
+            until: Some("next week".into()),
+            ..rule(RecurrenceRule::Daily)
+        };
+        assert!(bad_until.validate().is_err());
+
+        let parsed: Recurrence = serde_json::from_value(serde_json::json!({
+            "type": "weekly",
+            "weekdays": ["Mon", "Friday"],
+            "count": 4
+        }))
+        .unwrap();
+        assert_eq!(
+            parsed,
+            Recurrence {
+                rule: RecurrenceRule::Weekly {
+                    weekdays: vec![Weekday::Mon, Weekday::Fri]
+                },
+                until: None,
+                count: Some(4),
+            }
+        );
+        assert!(parsed.validate().is_ok());
+    }
+}
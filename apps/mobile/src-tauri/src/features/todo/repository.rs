@@ -1,11 +1,189 @@
-use super::model::Todo;
+use super::model::{NewTodo, Placement, Priority, Todo, TodoFilter, TodoPatch};
 use crate::db::DbPool;
+use crate::error::AppError;
+use rusqlite::OptionalExtension;
 
 pub trait TodoRepository {
-    fn get_all(&self) -> Result<Vec<Todo>, String>;
-    fn create(&self, text: String) -> Result<(), String>;
-    fn toggle(&self, id: u32) -> Result<(), String>;
-    fn delete(&self, id: u32) -> Result<(), String>;
+    /// Every todo, subtasks included, in sibling order
+    fn get_all(&self) -> Result<Vec<Todo>, AppError>;
+    fn get(&self, id: u32) -> Result<Option<Todo>, AppError>;
+    /// Open todos first, then by due date (undated last)
+    fn query(&self, filter: &TodoFilter) -> Result<Vec<Todo>, AppError>;
+    /// Appends a top-level todo; returns its id
+    fn create(&self, todo: &NewTodo, now: &str) -> Result<u32, AppError>;
+    /// Appends a subtask under `parent_id`; returns its id
+    fn add_subtask(&self, parent_id: u32, todo: &NewTodo, now: &str) -> Result<u32, AppError>;
+    /// Sets the order of `parent_id`'s subtasks; `ids` must list each of them exactly once
+    fn reorder_subtasks(&self, parent_id: u32, ids: &[u32], now: &str) -> Result<(), AppError>;
+    /// Moves a todo under another parent (or to the top level with `None`), appending it there.
+    /// Its own subtasks come along.
+    fn move_subtask(&self, id: u32, parent_id: Option<u32>, now: &str) -> Result<(), AppError>;
+    /// Places a todo directly before or after `target`, joining `target`'s parent if it has a
+    /// different one. Only the moved row is written unless its neighbours have run out of room.
+    fn move_todo(
+        &self,
+        id: u32,
+        target: u32,
+        placement: Placement,
+        now: &str,
+    ) -> Result<(), AppError>;
+    fn update(&self, id: u32, patch: &TodoPatch, now: &str) -> Result<(), AppError>;
+    fn toggle(&self, id: u32, now: &str) -> Result<(), AppError>;
+    /// Copies a recurring todo as a new open occurrence due on `due_date` and moves the
+    /// rule over to it, so the completed one can't spawn again. Its subtasks, at every level,
+    /// are copied unchecked.
+    /// Returns the new todo's id.
+    fn advance_recurrence(
+        &self,
+        id: u32,
+        due_date: &str,
+        occurrence: u32,
+        now: &str,
+    ) -> Result<u32, AppError>;
+    fn delete(&self, id: u32) -> Result<(), AppError>;
+}
+
+const TODO_COLUMNS: &str = "id, text, completed, due_date, priority, category, tags, notes, created_at, updated_at, completed_at, recurrence, occurrence, parent_id, position, due_time";
+
+/// Maps a row selected as `TODO_COLUMNS`.
+fn todo_from_row(row: &rusqlite::Row) -> rusqlite::Result<Todo> {
+    let priority: String = row.get(4)?;
+    let priority = priority.parse::<Priority>().map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(4, rusqlite::types::Type::Text, e.into())
+    })?;
+    let tags: String = row.get(6)?;
+    let tags: Vec<String> = serde_json::from_str(&tags).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(6, rusqlite::types::Type::Text, Box::new(e))
+    })?;
+    let recurrence: Option<String> = row.get(11)?;
+    let recurrence = recurrence
+        .map(|r| serde_json::from_str(&r))
+        .transpose()
+        .map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(11, rusqlite::types::Type::Text, Box::new(e))
+        })?;
+
+    Ok(Todo {
+        id: row.get(0)?,
+        text: row.get(1)?,
+        completed: row.get(2)?,
+        due_date: row.get(3)?,
+        due_time: row.get(15)?,
+        priority,
+        category: row.get(5)?,
+        tags,
+        notes: row.get(7)?,
+        recurrence,
+        occurrence: row.get(12)?,
+        created_at: row.get::<_, Option<String>>(8)?.unwrap_or_default(),
+        updated_at: row.get::<_, Option<String>>(9)?.unwrap_or_default(),
+        completed_at: row.get(10)?,
+        parent_id: row.get(13)?,
+        position: row.get(14)?,
+        subtasks: Vec::new(),
+        progress: None,
+    })
+}
+
+/// Space between neighbouring positions, so an item can usually be moved between two others
+/// by giving it the midpoint instead of renumbering its siblings
+pub const POSITION_GAP: i64 = 1024;
+
+/// Next free position among the children of parameter `?N` (top level when it is NULL)
+fn next_position(param: &str) -> String {
+    format!(
+        "(SELECT COALESCE(MAX(position) + {}, 0) FROM todos WHERE parent_id IS {})",
+        POSITION_GAP, param
+    )
+}
+
+/// Fails if `parent_id` is `id` itself or one of its descendants
+fn ensure_can_nest(conn: &rusqlite::Connection, id: u32, parent_id: u32) -> Result<(), AppError> {
+    // Walk up from the new parent: reaching `id` means it would become its own ancestor
+    let mut ancestor = Some(parent_id);
+    while let Some(current) = ancestor {
+        if current == id {
+            return Err(AppError::Validation(format!(
+                "Todo {} can't be moved under itself or one of its subtasks",
+                id
+            )));
+        }
+        ancestor = conn.query_row(
+            "SELECT parent_id FROM todos WHERE id = ?1",
+            [current],
+            |row| row.get(0),
+        )?;
+    }
+    Ok(())
+}
+
+/// Copies the subtree under `from` to `to`, unchecked and in the same order
+fn copy_subtasks(
+    conn: &rusqlite::Connection,
+    from: u32,
+    to: u32,
+    now: &str,
+) -> Result<(), AppError> {
+    for (child, _) in sibling_positions(conn, Some(from))? {
+        conn.execute(
+            "INSERT INTO todos (text, completed, priority, category, tags, notes, created_at, updated_at, parent_id, position)
+             SELECT text, 0, priority, category, tags, notes, ?2, ?2, ?3, position
+             FROM todos WHERE id = ?1",
+            rusqlite::params![child, now, to],
+        )?;
+        copy_subtasks(conn, child, conn.last_insert_rowid() as u32, now)?;
+    }
+    Ok(())
+}
+
+/// `(id, position)` of the children of `parent_id`, in order
+fn sibling_positions(
+    conn: &rusqlite::Connection,
+    parent_id: Option<u32>,
+) -> Result<Vec<(u32, i64)>, AppError> {
+    let siblings = conn
+        .prepare("SELECT id, position FROM todos WHERE parent_id IS ?1 ORDER BY position, id")?
+        .query_map([parent_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<_, _>>()?;
+    Ok(siblings)
+}
+
+fn todo_exists(conn: &rusqlite::Connection, id: u32) -> Result<bool, AppError> {
+    Ok(conn
+        .query_row("SELECT 1 FROM todos WHERE id = ?1", [id], |_| Ok(()))
+        .optional()?
+        .is_some())
+}
+
+fn insert_todo(
+    conn: &rusqlite::Connection,
+    todo: &NewTodo,
+    parent_id: Option<u32>,
+    now: &str,
+) -> Result<u32, AppError> {
+    conn.execute(
+        &format!(
+            "INSERT INTO todos (text, completed, due_date, due_time, priority, category, tags, notes, recurrence, created_at, updated_at, parent_id, position)
+             VALUES (?1, 0, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?9, ?10, {})",
+            next_position("?10")
+        ),
+        rusqlite::params![
+            todo.text,
+            todo.due_date,
+            todo.due_time,
+            todo.priority.as_str(),
+            todo.category,
+            serde_json::to_string(&todo.tags)?,
+            todo.notes,
+            todo.recurrence
+                .as_ref()
+                .map(serde_json::to_string)
+                .transpose()?,
+            now,
+            parent_id
+        ],
+    )?;
+    Ok(conn.last_insert_rowid() as u32)
 }
 
 pub struct SqliteTodoRepository {
@@ -16,63 +194,341 @@ impl SqliteTodoRepository {
     pub fn new(pool: DbPool) -> Self {
         Self { pool }
     }
-}
 
-impl TodoRepository for SqliteTodoRepository {
-    fn get_all(&self) -> Result<Vec<Todo>, String> {
-        let conn = self.pool.get().map_err(|e| e.to_string())?;
-        let mut stmt = conn
-            .prepare("SELECT id, text, completed FROM todos ORDER BY id ASC")
-            .map_err(|e| e.to_string())?;
-
-        let todo_iter = stmt
-            .query_map([], |row| {
-                Ok(Todo {
-                    id: row.get(0)?,
-                    text: row.get(1)?,
-                    completed: row.get(2)?,
-                })
-            })
-            .map_err(|e| e.to_string())?;
+    fn query_todos(
+        &self,
+        clause: &str,
+        params: &[&dyn rusqlite::ToSql],
+    ) -> Result<Vec<Todo>, AppError> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM todos {}", TODO_COLUMNS, clause))?;
+        let todo_iter = stmt.query_map(params, todo_from_row)?;
 
         let mut todos = Vec::new();
         for todo in todo_iter {
-            todos.push(todo.map_err(|e| e.to_string())?);
+            todos.push(todo?);
         }
         Ok(todos)
     }
+}
 
-    fn create(&self, text: String) -> Result<(), String> {
-        let conn = self.pool.get().map_err(|e| e.to_string())?;
-        conn.execute(
-            "INSERT INTO todos (text, completed) VALUES (?1, 0)",
-            [&text],
+impl TodoRepository for SqliteTodoRepository {
+    fn get_all(&self) -> Result<Vec<Todo>, AppError> {
+        self.query_todos("ORDER BY position ASC, id ASC", &[])
+    }
+
+    fn get(&self, id: u32) -> Result<Option<Todo>, AppError> {
+        let conn = self.pool.get()?;
+        let todo = conn
+            .query_row(
+                &format!("SELECT {} FROM todos WHERE id = ?1", TODO_COLUMNS),
+                [id],
+                todo_from_row,
+            )
+            .optional()?;
+        Ok(todo)
+    }
+
+    fn query(&self, filter: &TodoFilter) -> Result<Vec<Todo>, AppError> {
+        let mut conditions = Vec::new();
+        let mut params: Vec<&dyn rusqlite::ToSql> = Vec::new();
+
+        if let Some(completed) = &filter.completed {
+            conditions.push("completed = ?");
+            params.push(completed);
+        }
+        if let Some(date) = &filter.due_before {
+            conditions.push("due_date < ?");
+            params.push(date);
+        }
+        if let Some(date) = &filter.due_on {
+            conditions.push("due_date = ?");
+            params.push(date);
+        }
+        if let Some(category) = &filter.category {
+            conditions.push("category = ? COLLATE NOCASE");
+            params.push(category);
+        }
+        let priority = filter.priority.map(|p| p.as_str());
+        if let Some(priority) = &priority {
+            conditions.push("priority = ?");
+            params.push(priority);
+        }
+
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+        self.query_todos(
+            &format!(
+                "{} ORDER BY completed ASC, due_date IS NULL, due_date ASC, due_time IS NULL, due_time ASC, id ASC",
+                where_clause
+            ),
+            &params,
         )
-        .map_err(|e| e.to_string())?;
+    }
+
+    fn create(&self, todo: &NewTodo, now: &str) -> Result<u32, AppError> {
+        let conn = self.pool.get()?;
+        insert_todo(&conn, todo, None, now)
+    }
+
+    fn add_subtask(&self, parent_id: u32, todo: &NewTodo, now: &str) -> Result<u32, AppError> {
+        let conn = self.pool.get()?;
+        if !todo_exists(&conn, parent_id)? {
+            return Err(AppError::NotFound(format!("Todo {}", parent_id)));
+        }
+        insert_todo(&conn, todo, Some(parent_id), now)
+    }
+
+    fn reorder_subtasks(&self, parent_id: u32, ids: &[u32], now: &str) -> Result<(), AppError> {
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+        if !todo_exists(&tx, parent_id)? {
+            return Err(AppError::NotFound(format!("Todo {}", parent_id)));
+        }
+        let mut current: Vec<u32> = tx
+            .prepare("SELECT id FROM todos WHERE parent_id = ?1")?
+            .query_map([parent_id], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        let mut requested = ids.to_vec();
+        current.sort_unstable();
+        requested.sort_unstable();
+        if current != requested {
+            return Err(AppError::Validation(format!(
+                "The new order must list each subtask of todo {} exactly once",
+                parent_id
+            )));
+        }
+
+        for (position, id) in ids.iter().enumerate() {
+            tx.execute(
+                "UPDATE todos SET position = ?1, updated_at = ?2 WHERE id = ?3",
+                rusqlite::params![position as i64 * POSITION_GAP, now, id],
+            )?;
+        }
+        tx.commit()?;
         Ok(())
     }
 
-    fn toggle(&self, id: u32) -> Result<(), String> {
-        let conn = self.pool.get().map_err(|e| e.to_string())?;
-        // First get current status
-        let completed: bool = conn
-            .query_row("SELECT completed FROM todos WHERE id = ?1", [id], |row| {
-                row.get(0)
-            })
-            .map_err(|e| e.to_string())?;
+    fn move_subtask(&self, id: u32, parent_id: Option<u32>, now: &str) -> Result<(), AppError> {
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+        if !todo_exists(&tx, id)? {
+            return Err(AppError::NotFound(format!("Todo {}", id)));
+        }
+        if let Some(parent_id) = parent_id {
+            if !todo_exists(&tx, parent_id)? {
+                return Err(AppError::NotFound(format!("Todo {}", parent_id)));
+            }
+            ensure_can_nest(&tx, id, parent_id)?;
+        }
 
-        conn.execute(
-            "UPDATE todos SET completed = ?1 WHERE id = ?2",
-            (!completed, id),
-        )
-        .map_err(|e| e.to_string())?;
+        tx.execute(
+            &format!(
+                "UPDATE todos SET parent_id = ?1, position = {}, updated_at = ?2 WHERE id = ?3",
+                next_position("?1")
+            ),
+            rusqlite::params![parent_id, now, id],
+        )?;
+        tx.commit()?;
         Ok(())
     }
 
-    fn delete(&self, id: u32) -> Result<(), String> {
-        let conn = self.pool.get().map_err(|e| e.to_string())?;
-        conn.execute("DELETE FROM todos WHERE id = ?1", [id])
-            .map_err(|e| e.to_string())?;
+    fn move_todo(
+        &self,
+        id: u32,
+        target: u32,
+        placement: Placement,
+        now: &str,
+    ) -> Result<(), AppError> {
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+        if !todo_exists(&tx, id)? {
+            return Err(AppError::NotFound(format!("Todo {}", id)));
+        }
+        if id == target {
+            return Err(AppError::Validation(format!(
+                "Todo {} can't be moved relative to itself",
+                id
+            )));
+        }
+        let parent_id: Option<u32> = tx
+            .query_row(
+                "SELECT parent_id FROM todos WHERE id = ?1",
+                [target],
+                |row| row.get(0),
+            )
+            .optional()?
+            .ok_or_else(|| AppError::NotFound(format!("Todo {}", target)))?;
+        if let Some(parent_id) = parent_id {
+            ensure_can_nest(&tx, id, parent_id)?;
+        }
+
+        let mut siblings = sibling_positions(&tx, parent_id)?;
+        siblings.retain(|(sibling, _)| *sibling != id);
+        let index = siblings
+            .iter()
+            .position(|(sibling, _)| *sibling == target)
+            .expect("target is among its own siblings");
+        // The neighbours the moved todo ends up between
+        let (before, after) = match placement {
+            Placement::Before => (index.checked_sub(1), Some(index)),
+            Placement::After => (Some(index), Some(index + 1).filter(|i| *i < siblings.len())),
+        };
+        let between = |siblings: &[(u32, i64)]| match (before, after) {
+            (Some(b), Some(a)) => {
+                let (low, high) = (siblings[b].1, siblings[a].1);
+                (high - low >= 2).then(|| low + (high - low) / 2)
+            }
+            (None, Some(a)) => Some(siblings[a].1 - POSITION_GAP),
+            (Some(b), None) => Some(siblings[b].1 + POSITION_GAP),
+            (None, None) => Some(0),
+        };
+
+        let position = match between(&siblings) {
+            Some(position) => position,
+            None => {
+                // No room left between the neighbours: spread the siblings out again
+                for (i, (sibling, position)) in siblings.iter_mut().enumerate() {
+                    *position = i as i64 * POSITION_GAP;
+                    tx.execute(
+                        "UPDATE todos SET position = ?1 WHERE id = ?2",
+                        rusqlite::params![*position, *sibling],
+                    )?;
+                }
+                between(&siblings).expect("siblings are a full gap apart")
+            }
+        };
+
+        tx.execute(
+            "UPDATE todos SET parent_id = ?1, position = ?2, updated_at = ?3 WHERE id = ?4",
+            rusqlite::params![parent_id, position, now, id],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn update(&self, id: u32, patch: &TodoPatch, now: &str) -> Result<(), AppError> {
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+        let mut todo = tx
+            .query_row(
+                &format!("SELECT {} FROM todos WHERE id = ?1", TODO_COLUMNS),
+                [id],
+                todo_from_row,
+            )
+            .optional()?
+            .ok_or_else(|| AppError::NotFound(format!("Todo {}", id)))?;
+
+        if let Some(text) = &patch.text {
+            todo.text = text.clone();
+        }
+        if let Some(completed) = patch.completed {
+            if completed != todo.completed {
+                todo.completed_at = completed.then(|| now.to_string());
+            }
+            todo.completed = completed;
+        }
+        if let Some(due_date) = &patch.due_date {
+            todo.due_date = due_date.clone();
+        }
+        if let Some(due_time) = &patch.due_time {
+            todo.due_time = due_time.clone();
+        }
+        if let Some(priority) = patch.priority {
+            todo.priority = priority;
+        }
+        if let Some(category) = &patch.category {
+            todo.category = category.clone();
+        }
+        if let Some(tags) = &patch.tags {
+            todo.tags = tags.clone();
+        }
+        if let Some(notes) = &patch.notes {
+            todo.notes = notes.clone();
+        }
+        if let Some(recurrence) = &patch.recurrence {
+            todo.recurrence = recurrence.clone();
+        }
+
+        tx.execute(
+            "UPDATE todos SET text = ?1, completed = ?2, due_date = ?3, priority = ?4, category = ?5,
+                tags = ?6, notes = ?7, completed_at = ?8, updated_at = ?9, recurrence = ?10, due_time = ?11
+             WHERE id = ?12",
+            rusqlite::params![
+                todo.text,
+                todo.completed,
+                todo.due_date,
+                todo.priority.as_str(),
+                todo.category,
+                serde_json::to_string(&todo.tags)?,
+                todo.notes,
+                todo.completed_at,
+                now,
+                todo.recurrence
+                    .as_ref()
+                    .map(serde_json::to_string)
+                    .transpose()?,
+                todo.due_time,
+                id
+            ],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn toggle(&self, id: u32, now: &str) -> Result<(), AppError> {
+        let conn = self.pool.get()?;
+        let updated = conn.execute(
+            "UPDATE todos SET completed = NOT completed,
+                completed_at = CASE WHEN completed THEN NULL ELSE ?1 END,
+                updated_at = ?1
+             WHERE id = ?2",
+            rusqlite::params![now, id],
+        )?;
+        if updated == 0 {
+            return Err(AppError::NotFound(format!("Todo {}", id)));
+        }
+        Ok(())
+    }
+
+    fn advance_recurrence(
+        &self,
+        id: u32,
+        due_date: &str,
+        occurrence: u32,
+        now: &str,
+    ) -> Result<u32, AppError> {
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+        let inserted = tx.execute(
+            "INSERT INTO todos (text, completed, due_date, due_time, priority, category, tags, notes, recurrence, occurrence, created_at, updated_at, parent_id, position)
+             SELECT text, 0, ?2, due_time, priority, category, tags, notes, recurrence, ?3, ?4, ?4, parent_id,
+                 (SELECT COALESCE(MAX(s.position) + ?5, 0) FROM todos s WHERE s.parent_id IS t.parent_id)
+             FROM todos t WHERE id = ?1 AND recurrence IS NOT NULL",
+            rusqlite::params![id, due_date, occurrence, now, POSITION_GAP],
+        )?;
+        if inserted == 0 {
+            return Err(AppError::NotFound(format!("Recurring todo {}", id)));
+        }
+        let new_id = tx.last_insert_rowid() as u32;
+        copy_subtasks(&tx, id, new_id, now)?;
+        tx.execute(
+            "UPDATE todos SET recurrence = NULL, updated_at = ?2 WHERE id = ?1",
+            rusqlite::params![id, now],
+        )?;
+        tx.commit()?;
+        Ok(new_id)
+    }
+
+    fn delete(&self, id: u32) -> Result<(), AppError> {
+        let conn = self.pool.get()?;
+        let deleted = conn.execute("DELETE FROM todos WHERE id = ?1", [id])?;
+        if deleted == 0 {
+            return Err(AppError::NotFound(format!("Todo {}", id)));
+        }
         Ok(())
     }
 }
@@ -1,32 +1,308 @@
-use super::model::Todo;
+use super::model::{
+    NewTodo, Placement, Priority, SubtaskProgress, Todo, TodoFilter, TodoPatch, TodoSort,
+};
+use super::quick_add::parse_quick_add;
+use super::recurrence::Recurrence;
 use super::repository::TodoRepository;
+use crate::error::AppError;
+use chrono::{NaiveDate, NaiveTime};
+use std::cmp::Reverse;
+use std::collections::HashMap;
 use std::sync::Arc;
 
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const TIME_FORMAT: &str = "%H:%M";
+
 pub struct TodoState {
     pub repo: Arc<dyn TodoRepository + Send + Sync>,
 }
 
+fn now() -> String {
+    chrono::Local::now().to_rfc3339()
+}
+
+fn validate_text(text: &str) -> Result<String, AppError> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(AppError::Validation(
+            "Todo text cannot be empty".to_string(),
+        ));
+    }
+    Ok(text.to_string())
+}
+
+/// Accepts `YYYY-MM-DD` only, so due dates compare correctly as text
+fn validate_due_date(date: Option<&str>) -> Result<Option<String>, AppError> {
+    match date.map(str::trim).filter(|d| !d.is_empty()) {
+        Some(date) => NaiveDate::parse_from_str(date, DATE_FORMAT)
+            .map(|d| Some(d.format(DATE_FORMAT).to_string()))
+            .map_err(|_| AppError::Validation(format!("Invalid due date: {}", date))),
+        None => Ok(None),
+    }
+}
+
+fn validate_recurrence(recurrence: Option<Recurrence>) -> Result<Option<Recurrence>, AppError> {
+    if let Some(recurrence) = &recurrence {
+        recurrence.validate().map_err(AppError::Validation)?;
+    }
+    Ok(recurrence)
+}
+
+/// Accepts `HH:MM` (24-hour)
+fn validate_due_time(time: Option<&str>) -> Result<Option<String>, AppError> {
+    match time.map(str::trim).filter(|t| !t.is_empty()) {
+        Some(time) => NaiveTime::parse_from_str(time, TIME_FORMAT)
+            .map(|t| Some(t.format(TIME_FORMAT).to_string()))
+            .map_err(|_| AppError::Validation(format!("Invalid due time: {}", time))),
+        None => Ok(None),
+    }
+}
+
+/// Trims free text, treating blank as unset
+fn normalize_optional(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(String::from)
+}
+
+/// Trims tags and drops blanks and duplicates (case-insensitively), keeping the first spelling
+fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    for tag in tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+        if !result
+            .iter()
+            .any(|existing| existing.eq_ignore_ascii_case(tag))
+        {
+            result.push(tag.to_string());
+        }
+    }
+    result
+}
+
+/// Nests todos under their parents, keeping sibling order, and fills in the subtask progress
+fn build_tree(todos: Vec<Todo>) -> Vec<Todo> {
+    let mut children: HashMap<Option<u32>, Vec<Todo>> = HashMap::new();
+    for todo in todos {
+        children.entry(todo.parent_id).or_default().push(todo);
+    }
+
+    fn attach(mut todo: Todo, children: &mut HashMap<Option<u32>, Vec<Todo>>) -> Todo {
+        let subtasks: Vec<Todo> = children
+            .remove(&Some(todo.id))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|child| attach(child, children))
+            .collect();
+        if !subtasks.is_empty() {
+            todo.progress = Some(SubtaskProgress {
+                completed: subtasks.iter().filter(|t| t.completed).count() as u32,
+                total: subtasks.len() as u32,
+            });
+        }
+        todo.subtasks = subtasks;
+        todo
+    }
+
+    children
+        .remove(&None)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|todo| attach(todo, &mut children))
+        .collect()
+}
+
+/// Sorts every level of the tree; ties keep the manual order
+fn sort_tree(todos: &mut [Todo], sort: TodoSort) {
+    match sort {
+        TodoSort::Manual => {}
+        TodoSort::DueDate => todos.sort_by_key(|t| {
+            (
+                t.due_date.is_none(),
+                t.due_date.clone(),
+                t.due_time.is_none(),
+                t.due_time.clone(),
+            )
+        }),
+        TodoSort::Priority => todos.sort_by_key(|t| Reverse(t.priority)),
+        TodoSort::Created => {
+            todos.sort_by(|a, b| (&a.created_at, a.id).cmp(&(&b.created_at, b.id)))
+        }
+    }
+    for todo in todos {
+        sort_tree(&mut todo.subtasks, sort);
+    }
+}
+
+fn validate_new_todo(todo: NewTodo) -> Result<NewTodo, AppError> {
+    Ok(NewTodo {
+        text: validate_text(&todo.text)?,
+        due_date: validate_due_date(todo.due_date.as_deref())?,
+        due_time: validate_due_time(todo.due_time.as_deref())?,
+        priority: todo.priority,
+        category: normalize_optional(todo.category.as_deref()),
+        tags: normalize_tags(&todo.tags),
+        notes: normalize_optional(todo.notes.as_deref()),
+        recurrence: validate_recurrence(todo.recurrence)?,
+    })
+}
+
 impl TodoState {
     pub fn new(repo: Arc<dyn TodoRepository + Send + Sync>) -> Self {
         Self { repo }
     }
 
-    pub fn add(&self, text: String) -> Result<Vec<Todo>, String> {
-        self.repo.create(text)?;
+    pub fn add(&self, todo: NewTodo) -> Result<Vec<Todo>, AppError> {
+        self.repo.create(&validate_new_todo(todo)?, &now())?;
+        self.get_all()
+    }
+
+    /// Adds a todo typed as one line, e.g. `pay invoice tomorrow 3pm !high #work`
+    pub fn quick_add(&self, input: &str, today: NaiveDate) -> Result<Vec<Todo>, AppError> {
+        self.add(parse_quick_add(input, today))
+    }
+
+    pub fn add_subtask(&self, parent_id: u32, todo: NewTodo) -> Result<Vec<Todo>, AppError> {
+        self.repo
+            .add_subtask(parent_id, &validate_new_todo(todo)?, &now())?;
+        self.get_all()
+    }
+
+    pub fn reorder_subtasks(&self, parent_id: u32, ids: &[u32]) -> Result<Vec<Todo>, AppError> {
+        self.repo.reorder_subtasks(parent_id, ids, &now())?;
+        self.get_all()
+    }
+
+    pub fn move_subtask(&self, id: u32, parent_id: Option<u32>) -> Result<Vec<Todo>, AppError> {
+        self.repo.move_subtask(id, parent_id, &now())?;
+        self.get_all()
+    }
+
+    pub fn update(&self, id: u32, patch: TodoPatch) -> Result<Vec<Todo>, AppError> {
+        let patch = TodoPatch {
+            text: patch.text.as_deref().map(validate_text).transpose()?,
+            completed: patch.completed,
+            due_date: patch
+                .due_date
+                .map(|d| validate_due_date(d.as_deref()))
+                .transpose()?,
+            due_time: patch
+                .due_time
+                .map(|t| validate_due_time(t.as_deref()))
+                .transpose()?,
+            priority: patch.priority,
+            category: patch.category.map(|c| normalize_optional(c.as_deref())),
+            tags: patch.tags.map(|t| normalize_tags(&t)),
+            notes: patch.notes.map(|n| normalize_optional(n.as_deref())),
+            recurrence: patch.recurrence.map(validate_recurrence).transpose()?,
+        };
+        self.repo.update(id, &patch, &now())?;
+        if patch.completed == Some(true) {
+            self.schedule_next_occurrence(id)?;
+        }
         self.get_all()
     }
 
-    pub fn toggle(&self, id: u32) -> Result<Vec<Todo>, String> {
-        self.repo.toggle(id)?;
+    /// Puts `id` right before `before_id` or right after `after_id`; exactly one must be given
+    pub fn move_todo(
+        &self,
+        id: u32,
+        before_id: Option<u32>,
+        after_id: Option<u32>,
+    ) -> Result<Vec<Todo>, AppError> {
+        let (target, placement) = match (before_id, after_id) {
+            (Some(target), None) => (target, Placement::Before),
+            (None, Some(target)) => (target, Placement::After),
+            _ => {
+                return Err(AppError::Validation(
+                    "Give exactly one of before_id and after_id".to_string(),
+                ))
+            }
+        };
+        self.repo.move_todo(id, target, placement, &now())?;
         self.get_all()
     }
 
-    pub fn delete(&self, id: u32) -> Result<Vec<Todo>, String> {
+    /// Completing a recurring todo also adds its next occurrence
+    pub fn toggle(&self, id: u32) -> Result<Vec<Todo>, AppError> {
+        self.repo.toggle(id, &now())?;
+        self.schedule_next_occurrence(id)?;
+        self.get_all()
+    }
+
+    /// Adds the occurrence after a completed recurring todo, unless the series has ended.
+    /// An undated one recurs from the day it was completed.
+    fn schedule_next_occurrence(&self, id: u32) -> Result<(), AppError> {
+        let Some(todo) = self.repo.get(id)? else {
+            return Ok(());
+        };
+        let Some(recurrence) = todo.recurrence.as_ref().filter(|_| todo.completed) else {
+            return Ok(());
+        };
+        let today = chrono::Local::now().date_naive();
+        let due = todo
+            .due_date
+            .as_deref()
+            .and_then(|d| NaiveDate::parse_from_str(d, DATE_FORMAT).ok())
+            .unwrap_or(today);
+        if let Some((date, occurrence)) = recurrence.next_occurrence(due, todo.occurrence, today) {
+            self.repo.advance_recurrence(
+                id,
+                &date.format(DATE_FORMAT).to_string(),
+                occurrence,
+                &now(),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Subtasks are deleted along with their parent
+    pub fn delete(&self, id: u32) -> Result<Vec<Todo>, AppError> {
         self.repo.delete(id)?;
         self.get_all()
     }
 
-    pub fn get_all(&self) -> Result<Vec<Todo>, String> {
-        self.repo.get_all()
+    /// Top-level todos with their subtasks nested inside, in manual order
+    pub fn get_all(&self) -> Result<Vec<Todo>, AppError> {
+        Ok(build_tree(self.repo.get_all()?))
+    }
+
+    pub fn get_sorted(&self, sort: TodoSort) -> Result<Vec<Todo>, AppError> {
+        let mut todos = self.get_all()?;
+        sort_tree(&mut todos, sort);
+        Ok(todos)
+    }
+
+    /// Open todos due before `today`
+    pub fn overdue(&self, today: NaiveDate) -> Result<Vec<Todo>, AppError> {
+        self.repo.query(&TodoFilter {
+            completed: Some(false),
+            due_before: Some(today.format(DATE_FORMAT).to_string()),
+            ..Default::default()
+        })
+    }
+
+    /// Open todos due on `today`
+    pub fn due_today(&self, today: NaiveDate) -> Result<Vec<Todo>, AppError> {
+        self.repo.query(&TodoFilter {
+            completed: Some(false),
+            due_on: Some(today.format(DATE_FORMAT).to_string()),
+            ..Default::default()
+        })
+    }
+
+    /// Matched case-insensitively
+    pub fn by_category(&self, category: &str) -> Result<Vec<Todo>, AppError> {
+        self.repo.query(&TodoFilter {
+            category: Some(category.trim().to_string()),
+            ..Default::default()
+        })
+    }
+
+    pub fn by_priority(&self, priority: Priority) -> Result<Vec<Todo>, AppError> {
+        self.repo.query(&TodoFilter {
+            priority: Some(priority),
+            ..Default::default()
+        })
     }
 }
@@ -1,17 +1,144 @@
-use super::model::WorkLog;
+use super::model::{
+    DailyHours, ExportFormat, NewWorkLog, ProjectHours, WorkLog, WorkLogImport, WorkLogPatch,
+    WorkLogTotals, WorkLogTrend, WorkTimer,
+};
 use super::service::WorkLogState;
+use crate::error::AppError;
 use tauri::State;
 
 #[tauri::command]
-pub fn get_work_logs(state: State<WorkLogState>) -> Result<Vec<WorkLog>, String> {
+pub fn get_work_logs(state: State<WorkLogState>) -> Result<Vec<WorkLog>, AppError> {
     state.get_all()
 }
 
 #[tauri::command]
-pub fn add_work_log(
+pub fn add_work_log(log: NewWorkLog, state: State<WorkLogState>) -> Result<Vec<WorkLog>, AppError> {
+    state.add(log)
+}
+
+#[tauri::command]
+pub fn update_work_log(
+    id: u32,
+    patch: WorkLogPatch,
+    state: State<WorkLogState>,
+) -> Result<Vec<WorkLog>, AppError> {
+    state.update(id, patch)
+}
+
+#[tauri::command]
+pub fn delete_work_log(id: u32, state: State<WorkLogState>) -> Result<Vec<WorkLog>, AppError> {
+    state.delete(id)
+}
+
+#[tauri::command]
+pub fn get_timers(state: State<WorkLogState>) -> Result<Vec<WorkTimer>, AppError> {
+    state.timers()
+}
+
+#[tauri::command]
+pub fn start_timer(
     project: String,
-    hours: f32,
+    allow_concurrent: Option<bool>,
+    state: State<WorkLogState>,
+) -> Result<Vec<WorkTimer>, AppError> {
+    state.start_timer(&project, allow_concurrent.unwrap_or(false))
+}
+
+#[tauri::command]
+pub fn pause_timer(id: u32, state: State<WorkLogState>) -> Result<Vec<WorkTimer>, AppError> {
+    state.pause_timer(id)
+}
+
+#[tauri::command]
+pub fn resume_timer(
+    id: u32,
+    allow_concurrent: Option<bool>,
+    state: State<WorkLogState>,
+) -> Result<Vec<WorkTimer>, AppError> {
+    state.resume_timer(id, allow_concurrent.unwrap_or(false))
+}
+
+#[tauri::command]
+pub fn stop_timer(id: u32, state: State<WorkLogState>) -> Result<Vec<WorkLog>, AppError> {
+    state.stop_timer(id)
+}
+
+/// Drops a timer without logging it, e.g. one left running that can't be stopped
+#[tauri::command]
+pub fn discard_timer(id: u32, state: State<WorkLogState>) -> Result<Vec<WorkTimer>, AppError> {
+    state.discard_timer(id)
+}
+
+#[tauri::command]
+pub fn get_work_log_totals(state: State<WorkLogState>) -> Result<WorkLogTotals, AppError> {
+    state.totals(chrono::Local::now().date_naive())
+}
+
+/// Both bounds are inclusive `YYYY-MM-DD` dates; leaving them out covers all logs
+#[tauri::command]
+pub fn get_project_hours(
+    from: Option<String>,
+    to: Option<String>,
+    state: State<WorkLogState>,
+) -> Result<Vec<ProjectHours>, AppError> {
+    state.project_hours(from.as_deref(), to.as_deref())
+}
+
+/// Defaults to the last 7 days
+#[tauri::command]
+pub fn get_daily_hours(
+    from: Option<String>,
+    to: Option<String>,
+    state: State<WorkLogState>,
+) -> Result<Vec<DailyHours>, AppError> {
+    let today = chrono::Local::now().date_naive();
+    let to = to.unwrap_or_else(|| today.format("%Y-%m-%d").to_string());
+    let from = from.unwrap_or_else(|| {
+        (today - chrono::Duration::days(6))
+            .format("%Y-%m-%d")
+            .to_string()
+    });
+    state.daily_hours(&from, &to)
+}
+
+/// Defaults to the last 30 days with a 7-day average
+#[tauri::command]
+pub fn get_work_log_trend(
+    days: Option<u32>,
+    window: Option<u32>,
+    state: State<WorkLogState>,
+) -> Result<WorkLogTrend, AppError> {
+    state.trend(
+        chrono::Local::now().date_naive(),
+        days.unwrap_or(30),
+        window.unwrap_or(7),
+    )
+}
+
+/// Returns the file's content; both bounds are inclusive `YYYY-MM-DD` dates and may be left out
+#[tauri::command]
+pub fn export_work_logs(
+    format: ExportFormat,
+    from: Option<String>,
+    to: Option<String>,
+    include_summary: Option<bool>,
+    state: State<WorkLogState>,
+) -> Result<String, AppError> {
+    state.export(
+        format,
+        from.as_deref(),
+        to.as_deref(),
+        include_summary.unwrap_or(false),
+        chrono::Local::now().fixed_offset(),
+    )
+}
+
+/// Imports CSV content; times without a zone are read in the device's current time zone
+#[tauri::command]
+pub fn import_work_logs_csv(
+    content: String,
     state: State<WorkLogState>,
-) -> Result<Vec<WorkLog>, String> {
-    state.add(project, hours)
+) -> Result<WorkLogImport, AppError> {
+    let offset = *chrono::Local::now().fixed_offset().offset();
+    state.import_csv(&content, offset)
 }
@@ -2,3 +2,5 @@ pub mod commands;
 pub mod model;
 pub mod repository;
 pub mod service;
+pub mod stats;
+pub mod timesheet;
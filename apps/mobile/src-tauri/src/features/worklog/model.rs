@@ -1,3 +1,4 @@
+use crate::serde_util::nullable;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -5,5 +6,128 @@ pub struct WorkLog {
     pub id: u32,
     pub project: String,
     pub hours: f32,
+    /// `YYYY-MM-DD`
     pub date: String,
+    /// RFC 3339; set for timer logs and entries given with start and end times
+    pub started_at: Option<String>,
+    pub ended_at: Option<String>,
+    /// Time worked in seconds, set when both times are; `hours` is this rounded to `f32`.
+    /// Less than the span between `started_at` and `ended_at` when it leaves out breaks,
+    /// such as a timer's paused time.
+    pub duration_seconds: Option<i64>,
+    pub description: Option<String>,
+}
+
+/// Fields for `add_work_log`. `hours` may be left out when both start and end are given;
+/// `date` defaults to the start's day, or today.
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct NewWorkLog {
+    pub project: String,
+    pub hours: Option<f32>,
+    pub date: Option<String>,
+    pub started_at: Option<String>,
+    pub ended_at: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Changes for `update_work_log`. Omitted fields stay as they are;
+/// for the optional ones, an explicit `null` clears the value.
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct WorkLogPatch {
+    pub project: Option<String>,
+    pub hours: Option<f32>,
+    pub date: Option<String>,
+    #[serde(deserialize_with = "nullable")]
+    pub started_at: Option<Option<String>>,
+    #[serde(deserialize_with = "nullable")]
+    pub ended_at: Option<Option<String>>,
+    #[serde(deserialize_with = "nullable")]
+    pub description: Option<Option<String>>,
+}
+
+/// A timer that has been started but not stopped yet
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct WorkTimer {
+    pub id: u32,
+    pub project: String,
+    pub started_at: String,
+    /// When the current run began; `None` while paused
+    pub resumed_at: Option<String>,
+    pub running: bool,
+    /// Time counted so far, including the current run up to when this was read
+    pub elapsed_seconds: i64,
+}
+
+/// Hours logged today and in the current week (Monday to Sunday)
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct WorkLogTotals {
+    pub today: String,
+    pub today_hours: f64,
+    pub week_start: String,
+    pub week_hours: f64,
+}
+
+/// One slice of the project distribution chart
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct ProjectHours {
+    pub project: String,
+    pub hours: f64,
+    /// Fraction of all hours in the range, 0 to 1
+    pub share: f64,
+}
+
+/// One bar of the daily chart; days without logs have zero hours
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct DailyHours {
+    pub date: String,
+    pub hours: f64,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct TrendPoint {
+    pub date: String,
+    pub hours: f64,
+    /// Mean over the `window` days ending on `date`, counting days without logs as zero
+    pub average: f64,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct WorkLogTrend {
+    pub window: u32,
+    pub points: Vec<TrendPoint>,
+    /// Days in a row with hours logged, up to today (or yesterday, while today is still empty)
+    pub current_streak: u32,
+    pub longest_streak: u32,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// One row per entry, optionally followed by per-project totals
+    Csv,
+    /// One VEVENT per entry with start and end times; entries without them are left out
+    Ical,
+    Json,
+}
+
+/// The JSON export: the range it covers, the entries and, when asked for, per-project totals
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct Timesheet {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub logs: Vec<WorkLog>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub projects: Option<Vec<ProjectHours>>,
+}
+
+/// What a CSV import did
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct WorkLogImport {
+    pub imported: usize,
+    /// Rows matching a stored entry (or an earlier row) that were not imported again
+    pub duplicates: usize,
+    /// One message per rejected row, with its line number
+    pub errors: Vec<String>,
 }
@@ -1,9 +1,129 @@
-use super::model::WorkLog;
+use super::model::{WorkLog, WorkTimer};
 use crate::db::DbPool;
+use crate::error::AppError;
+use chrono::{DateTime, FixedOffset};
+use rusqlite::OptionalExtension;
 
 pub trait WorkLogRepository {
-    fn get_all(&self) -> Result<Vec<WorkLog>, String>;
-    fn create(&self, project: String, hours: f32) -> Result<(), String>;
+    fn get_all(&self) -> Result<Vec<WorkLog>, AppError>;
+    fn get(&self, id: u32) -> Result<Option<WorkLog>, AppError>;
+    /// Logs dated within `from..=to`, oldest first; a missing bound is open
+    fn get_between(&self, from: Option<&str>, to: Option<&str>) -> Result<Vec<WorkLog>, AppError>;
+    /// Stores every field but `id`; returns the new log's id
+    fn create(&self, log: &WorkLog) -> Result<u32, AppError>;
+    fn update(&self, log: &WorkLog) -> Result<(), AppError>;
+    fn delete(&self, id: u32) -> Result<(), AppError>;
+    /// Hours logged on `date`, leaving out the log `excluding` (the one being edited)
+    fn total_hours_on(&self, date: &str, excluding: Option<u32>) -> Result<f64, AppError>;
+    /// Hours per project for logs dated within `from..=to`; a missing bound is open
+    fn hours_by_project(
+        &self,
+        from: Option<&str>,
+        to: Option<&str>,
+    ) -> Result<Vec<(String, f64)>, AppError>;
+    /// Hours per date within `from..=to`, oldest first; days without logs are left out
+    fn hours_by_day(&self, from: &str, to: &str) -> Result<Vec<(String, f64)>, AppError>;
+    /// Every date with more than zero hours logged, oldest first
+    fn logged_dates(&self) -> Result<Vec<String>, AppError>;
+
+    /// Timers not yet stopped, oldest first, with elapsed time counted up to `now`
+    fn get_timers(&self, now: DateTime<FixedOffset>) -> Result<Vec<WorkTimer>, AppError>;
+    /// Starts a running timer; fails while another one runs unless `allow_concurrent`.
+    /// Returns the timer's id.
+    fn start_timer(
+        &self,
+        project: &str,
+        allow_concurrent: bool,
+        now: DateTime<FixedOffset>,
+    ) -> Result<u32, AppError>;
+    fn pause_timer(&self, id: u32, now: DateTime<FixedOffset>) -> Result<(), AppError>;
+    /// Same concurrency rule as `start_timer`
+    fn resume_timer(
+        &self,
+        id: u32,
+        allow_concurrent: bool,
+        now: DateTime<FixedOffset>,
+    ) -> Result<(), AppError>;
+    /// Turns the timer into a work log with its exact duration; returns the log's id
+    fn stop_timer(&self, id: u32, now: DateTime<FixedOffset>) -> Result<u32, AppError>;
+    /// Removes the timer without logging its time
+    fn discard_timer(&self, id: u32) -> Result<(), AppError>;
+}
+
+const LOG_COLUMNS: &str =
+    "id, project, hours, date, started_at, ended_at, duration_seconds, description";
+
+/// Maps a row selected as `LOG_COLUMNS`.
+fn log_from_row(row: &rusqlite::Row) -> rusqlite::Result<WorkLog> {
+    let hours_f64: f64 = row.get(2)?;
+    Ok(WorkLog {
+        id: row.get(0)?,
+        project: row.get(1)?,
+        hours: hours_f64 as f32,
+        date: row.get(3)?,
+        started_at: row.get(4)?,
+        ended_at: row.get(5)?,
+        duration_seconds: row.get(6)?,
+        description: row.get(7)?,
+    })
+}
+
+struct TimerRow {
+    project: String,
+    started_at: String,
+    resumed_at: Option<String>,
+    elapsed_seconds: i64,
+}
+
+impl TimerRow {
+    /// Counted seconds, including the current run. A clock that went backwards counts as zero.
+    fn elapsed_at(&self, now: DateTime<FixedOffset>) -> Result<i64, AppError> {
+        let running = match &self.resumed_at {
+            Some(resumed_at) => (now - parse_timestamp(resumed_at)?).num_seconds().max(0),
+            None => 0,
+        };
+        Ok(self.elapsed_seconds + running)
+    }
+}
+
+fn parse_timestamp(value: &str) -> Result<DateTime<FixedOffset>, AppError> {
+    DateTime::parse_from_rfc3339(value)
+        .map_err(|e| AppError::Unknown(format!("Invalid timestamp {}: {}", value, e)))
+}
+
+fn get_timer(conn: &rusqlite::Connection, id: u32) -> Result<TimerRow, AppError> {
+    conn.query_row(
+        "SELECT project, started_at, resumed_at, elapsed_seconds FROM work_timers WHERE id = ?1",
+        [id],
+        |row| {
+            Ok(TimerRow {
+                project: row.get(0)?,
+                started_at: row.get(1)?,
+                resumed_at: row.get(2)?,
+                elapsed_seconds: row.get(3)?,
+            })
+        },
+    )
+    .optional()?
+    .ok_or_else(|| AppError::NotFound(format!("Timer {}", id)))
+}
+
+/// Fails if some timer is running, so a new one can't run alongside it
+fn ensure_none_running(conn: &rusqlite::Connection) -> Result<(), AppError> {
+    let running: Option<String> = conn
+        .query_row(
+            "SELECT project FROM work_timers WHERE resumed_at IS NOT NULL LIMIT 1",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?;
+    match running {
+        Some(project) => Err(AppError::Validation(format!(
+            "A timer is already running for {}",
+            project
+        ))),
+        None => Ok(()),
+    }
 }
 
 pub struct SqliteWorkLogRepository {
@@ -17,43 +137,276 @@ impl SqliteWorkLogRepository {
 }
 
 impl WorkLogRepository for SqliteWorkLogRepository {
-    fn get_all(&self) -> Result<Vec<WorkLog>, String> {
-        let conn = self.pool.get().map_err(|e| e.to_string())?;
-        let mut stmt = conn
-            .prepare("SELECT id, project, hours, date FROM work_logs ORDER BY id DESC")
-            .map_err(|e| e.to_string())?;
-
-        let log_iter = stmt
-            .query_map([], |row| {
-                let hours_f64: f64 = row.get(2)?;
-                Ok(WorkLog {
-                    id: row.get(0)?,
-                    project: row.get(1)?,
-                    hours: hours_f64 as f32,
-                    date: row.get(3)?,
-                })
-            })
-            .map_err(|e| e.to_string())?;
+    fn get_all(&self) -> Result<Vec<WorkLog>, AppError> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM work_logs ORDER BY id DESC",
+            LOG_COLUMNS
+        ))?;
+        let log_iter = stmt.query_map([], log_from_row)?;
 
         let mut logs = Vec::new();
         for log in log_iter {
-            logs.push(log.map_err(|e| e.to_string())?);
+            logs.push(log?);
         }
         Ok(logs)
     }
 
-    fn create(&self, project: String, hours: f32) -> Result<(), String> {
-        let conn = self.pool.get().map_err(|e| e.to_string())?;
-        let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+    fn get(&self, id: u32) -> Result<Option<WorkLog>, AppError> {
+        let conn = self.pool.get()?;
+        let log = conn
+            .query_row(
+                &format!("SELECT {} FROM work_logs WHERE id = ?1", LOG_COLUMNS),
+                [id],
+                log_from_row,
+            )
+            .optional()?;
+        Ok(log)
+    }
 
-        // Cast f32 to f64 for SQLite REAL compatibility
-        let hours_f64 = hours as f64;
+    fn get_between(&self, from: Option<&str>, to: Option<&str>) -> Result<Vec<WorkLog>, AppError> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM work_logs
+             WHERE (?1 IS NULL OR date >= ?1) AND (?2 IS NULL OR date <= ?2)
+             ORDER BY date ASC, started_at ASC, id ASC",
+            LOG_COLUMNS
+        ))?;
+        let rows = stmt.query_map(rusqlite::params![from, to], log_from_row)?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
 
+    fn create(&self, log: &WorkLog) -> Result<u32, AppError> {
+        let conn = self.pool.get()?;
         conn.execute(
-            "INSERT INTO work_logs (project, hours, date) VALUES (?1, ?2, ?3)",
-            rusqlite::params![project, hours_f64, date],
-        )
-        .map_err(|e| e.to_string())?;
+            "INSERT INTO work_logs (project, hours, date, started_at, ended_at, duration_seconds, description)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            rusqlite::params![
+                log.project,
+                // Cast f32 to f64 for SQLite REAL compatibility
+                log.hours as f64,
+                log.date,
+                log.started_at,
+                log.ended_at,
+                log.duration_seconds,
+                log.description
+            ],
+        )?;
+        Ok(conn.last_insert_rowid() as u32)
+    }
+
+    fn update(&self, log: &WorkLog) -> Result<(), AppError> {
+        let conn = self.pool.get()?;
+        let updated = conn.execute(
+            "UPDATE work_logs SET project = ?1, hours = ?2, date = ?3, started_at = ?4, ended_at = ?5,
+                duration_seconds = ?6, description = ?7
+             WHERE id = ?8",
+            rusqlite::params![
+                log.project,
+                log.hours as f64,
+                log.date,
+                log.started_at,
+                log.ended_at,
+                log.duration_seconds,
+                log.description,
+                log.id
+            ],
+        )?;
+        if updated == 0 {
+            return Err(AppError::NotFound(format!("Work log {}", log.id)));
+        }
+        Ok(())
+    }
+
+    fn delete(&self, id: u32) -> Result<(), AppError> {
+        let conn = self.pool.get()?;
+        let deleted = conn.execute("DELETE FROM work_logs WHERE id = ?1", [id])?;
+        if deleted == 0 {
+            return Err(AppError::NotFound(format!("Work log {}", id)));
+        }
+        Ok(())
+    }
+
+    fn total_hours_on(&self, date: &str, excluding: Option<u32>) -> Result<f64, AppError> {
+        let conn = self.pool.get()?;
+        let total = conn.query_row(
+            "SELECT COALESCE(SUM(hours), 0) FROM work_logs WHERE date = ?1 AND id IS NOT ?2",
+            rusqlite::params![date, excluding],
+            |row| row.get(0),
+        )?;
+        Ok(total)
+    }
+
+    fn hours_by_project(
+        &self,
+        from: Option<&str>,
+        to: Option<&str>,
+    ) -> Result<Vec<(String, f64)>, AppError> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT project, SUM(hours) FROM work_logs
+             WHERE (?1 IS NULL OR date >= ?1) AND (?2 IS NULL OR date <= ?2)
+             GROUP BY project
+             ORDER BY SUM(hours) DESC, project ASC",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![from, to], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    fn hours_by_day(&self, from: &str, to: &str) -> Result<Vec<(String, f64)>, AppError> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT date, SUM(hours) FROM work_logs
+             WHERE date >= ?1 AND date <= ?2
+             GROUP BY date
+             ORDER BY date ASC",
+        )?;
+        let rows = stmt.query_map([from, to], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    fn logged_dates(&self) -> Result<Vec<String>, AppError> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT date FROM work_logs GROUP BY date HAVING SUM(hours) > 0 ORDER BY date ASC",
+        )?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    fn get_timers(&self, now: DateTime<FixedOffset>) -> Result<Vec<WorkTimer>, AppError> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, project, started_at, resumed_at, elapsed_seconds
+             FROM work_timers ORDER BY id ASC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, u32>(0)?,
+                TimerRow {
+                    project: row.get(1)?,
+                    started_at: row.get(2)?,
+                    resumed_at: row.get(3)?,
+                    elapsed_seconds: row.get(4)?,
+                },
+            ))
+        })?;
+
+        let mut timers = Vec::new();
+        for row in rows {
+            let (id, timer) = row?;
+            timers.push(WorkTimer {
+                id,
+                elapsed_seconds: timer.elapsed_at(now)?,
+                running: timer.resumed_at.is_some(),
+                project: timer.project,
+                started_at: timer.started_at,
+                resumed_at: timer.resumed_at,
+            });
+        }
+        Ok(timers)
+    }
+
+    fn start_timer(
+        &self,
+        project: &str,
+        allow_concurrent: bool,
+        now: DateTime<FixedOffset>,
+    ) -> Result<u32, AppError> {
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+        if !allow_concurrent {
+            ensure_none_running(&tx)?;
+        }
+        tx.execute(
+            "INSERT INTO work_timers (project, started_at, resumed_at) VALUES (?1, ?2, ?2)",
+            rusqlite::params![project, now.to_rfc3339()],
+        )?;
+        let id = tx.last_insert_rowid() as u32;
+        tx.commit()?;
+        Ok(id)
+    }
+
+    fn pause_timer(&self, id: u32, now: DateTime<FixedOffset>) -> Result<(), AppError> {
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+        let timer = get_timer(&tx, id)?;
+        if timer.resumed_at.is_none() {
+            return Err(AppError::Validation(format!(
+                "Timer {} is already paused",
+                id
+            )));
+        }
+        tx.execute(
+            "UPDATE work_timers SET elapsed_seconds = ?1, resumed_at = NULL WHERE id = ?2",
+            rusqlite::params![timer.elapsed_at(now)?, id],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn resume_timer(
+        &self,
+        id: u32,
+        allow_concurrent: bool,
+        now: DateTime<FixedOffset>,
+    ) -> Result<(), AppError> {
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+        let timer = get_timer(&tx, id)?;
+        if timer.resumed_at.is_some() {
+            return Err(AppError::Validation(format!(
+                "Timer {} is already running",
+                id
+            )));
+        }
+        if !allow_concurrent {
+            ensure_none_running(&tx)?;
+        }
+        tx.execute(
+            "UPDATE work_timers SET resumed_at = ?1 WHERE id = ?2",
+            rusqlite::params![now.to_rfc3339(), id],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn stop_timer(&self, id: u32, now: DateTime<FixedOffset>) -> Result<u32, AppError> {
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+        let timer = get_timer(&tx, id)?;
+        let seconds = timer.elapsed_at(now)?;
+        // Logged on the day the timer started, in the time zone it was started in
+        let date = parse_timestamp(&timer.started_at)?
+            .format("%Y-%m-%d")
+            .to_string();
+
+        tx.execute(
+            "INSERT INTO work_logs (project, hours, date, started_at, ended_at, duration_seconds)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![
+                timer.project,
+                seconds as f64 / 3600.0,
+                date,
+                timer.started_at,
+                now.to_rfc3339(),
+                seconds
+            ],
+        )?;
+        let log_id = tx.last_insert_rowid() as u32;
+        tx.execute("DELETE FROM work_timers WHERE id = ?1", [id])?;
+        tx.commit()?;
+        Ok(log_id)
+    }
+
+    fn discard_timer(&self, id: u32) -> Result<(), AppError> {
+        let conn = self.pool.get()?;
+        let deleted = conn.execute("DELETE FROM work_timers WHERE id = ?1", [id])?;
+        if deleted == 0 {
+            return Err(AppError::NotFound(format!("Timer {}", id)));
+        }
         Ok(())
     }
 }
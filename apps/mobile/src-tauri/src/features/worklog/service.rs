@@ -1,22 +1,507 @@
-use super::model::WorkLog;
+use super::model::{
+    DailyHours, ExportFormat, NewWorkLog, ProjectHours, Timesheet, TrendPoint, WorkLog,
+    WorkLogImport, WorkLogPatch, WorkLogTotals, WorkLogTrend, WorkTimer,
+};
 use super::repository::WorkLogRepository;
+use super::stats;
+use super::timesheet;
+use crate::error::AppError;
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate};
 use std::sync::Arc;
 
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const MAX_HOURS_PER_DAY: f64 = 24.0;
+/// Slack for `f32` rounding when adding up a day's hours
+const HOURS_TOLERANCE: f64 = 0.001;
+/// Longest range a daily series may span, to keep chart payloads bounded
+const MAX_SERIES_DAYS: i64 = 731;
+const MAX_AVERAGE_WINDOW: u32 = 90;
+
 pub struct WorkLogState {
     pub repo: Arc<dyn WorkLogRepository + Send + Sync>,
 }
 
+fn now() -> DateTime<FixedOffset> {
+    chrono::Local::now().fixed_offset()
+}
+
+fn validate_project(project: &str) -> Result<String, AppError> {
+    let project = project.trim();
+    if project.is_empty() {
+        return Err(AppError::Validation("Project cannot be empty".to_string()));
+    }
+    Ok(project.to_string())
+}
+
+fn validate_date(date: &str) -> Result<String, AppError> {
+    parse_date(date).map(format_date)
+}
+
+fn parse_time(value: &str) -> Result<DateTime<FixedOffset>, AppError> {
+    DateTime::parse_from_rfc3339(value.trim())
+        .map_err(|_| AppError::Validation(format!("Invalid time: {}", value)))
+}
+
+fn parse_date(date: &str) -> Result<NaiveDate, AppError> {
+    NaiveDate::parse_from_str(date.trim(), DATE_FORMAT)
+        .map_err(|_| AppError::Validation(format!("Invalid date: {}", date)))
+}
+
+fn format_date(date: NaiveDate) -> String {
+    date.format(DATE_FORMAT).to_string()
+}
+
+/// Blank descriptions are stored as none
+fn normalize_description(description: Option<&str>) -> Option<String> {
+    description
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(String::from)
+}
+
+/// Checks a log after all defaults and edits have been applied.
+///
+/// With both start and end given, an explicit `hours` may be less than the time between them
+/// (breaks, or a timer's paused time) but not more. Without one, `hours` is the whole span when
+/// the times are new (`times_changed`); otherwise the stored `hours` and `duration_seconds` stay,
+/// so editing e.g. the description of a paused timer's log doesn't add its pauses back.
+fn finalize(
+    mut log: WorkLog,
+    explicit_hours: Option<f32>,
+    times_changed: bool,
+) -> Result<WorkLog, AppError> {
+    log.date = validate_date(&log.date)?;
+    let start = log.started_at.as_deref().map(parse_time).transpose()?;
+    let end = log.ended_at.as_deref().map(parse_time).transpose()?;
+
+    match (start, end) {
+        (Some(start), Some(end)) => {
+            if end <= start {
+                return Err(AppError::Validation(
+                    "End time must be after the start time".to_string(),
+                ));
+            }
+            let span = (end - start).num_seconds();
+            if let Some(hours) = explicit_hours {
+                let seconds = (f64::from(hours) * 3600.0).round() as i64;
+                if seconds > span + 60 {
+                    return Err(AppError::Validation(
+                        "Hours can't be more than the time between start and end".to_string(),
+                    ));
+                }
+                log.hours = hours;
+                log.duration_seconds = Some(seconds.min(span));
+            } else if times_changed || log.duration_seconds.is_none() {
+                log.hours = span as f32 / 3600.0;
+                log.duration_seconds = Some(span);
+            }
+        }
+        _ => log.duration_seconds = None,
+    }
+    if !log.hours.is_finite() || log.hours < 0.0 {
+        return Err(AppError::Validation(format!(
+            "Hours must be zero or more, got {}",
+            log.hours
+        )));
+    }
+    Ok(log)
+}
+
 impl WorkLogState {
     pub fn new(repo: Arc<dyn WorkLogRepository + Send + Sync>) -> Self {
         Self { repo }
     }
 
-    pub fn add(&self, project: String, hours: f32) -> Result<Vec<WorkLog>, String> {
-        self.repo.create(project, hours)?;
+    pub fn add(&self, log: NewWorkLog) -> Result<Vec<WorkLog>, AppError> {
+        self.insert(log)?;
         self.get_all()
     }
 
-    pub fn get_all(&self) -> Result<Vec<WorkLog>, String> {
+    /// Validates and stores one entry, returning it as stored
+    fn insert(&self, log: NewWorkLog) -> Result<WorkLog, AppError> {
+        let started_at = log.started_at.as_deref().map(parse_time).transpose()?;
+        let date = match (&log.date, started_at) {
+            (Some(date), _) => date.clone(),
+            (None, Some(start)) => start.format(DATE_FORMAT).to_string(),
+            (None, None) => chrono::Local::now().format(DATE_FORMAT).to_string(),
+        };
+        if log.hours.is_none() && (log.started_at.is_none() || log.ended_at.is_none()) {
+            return Err(AppError::Validation(
+                "Give the hours, or a start and end time".to_string(),
+            ));
+        }
+
+        let entry = finalize(
+            WorkLog {
+                id: 0,
+                project: validate_project(&log.project)?,
+                hours: log.hours.unwrap_or_default(),
+                date,
+                started_at: log.started_at,
+                ended_at: log.ended_at,
+                duration_seconds: None,
+                description: normalize_description(log.description.as_deref()),
+            },
+            log.hours,
+            true,
+        )?;
+        self.check_day_total(&entry, None)?;
+        let id = self.repo.create(&entry)?;
+        Ok(WorkLog { id, ..entry })
+    }
+
+    pub fn update(&self, id: u32, patch: WorkLogPatch) -> Result<Vec<WorkLog>, AppError> {
+        let mut log = self
+            .repo
+            .get(id)?
+            .ok_or_else(|| AppError::NotFound(format!("Work log {}", id)))?;
+        if let Some(project) = &patch.project {
+            log.project = validate_project(project)?;
+        }
+        if let Some(hours) = patch.hours {
+            log.hours = hours;
+        }
+        if let Some(date) = patch.date {
+            log.date = date;
+        }
+        // An edit form may send the times back unchanged; only a different value counts
+        let times_changed = patch
+            .started_at
+            .as_ref()
+            .is_some_and(|started_at| *started_at != log.started_at)
+            || patch
+                .ended_at
+                .as_ref()
+                .is_some_and(|ended_at| *ended_at != log.ended_at);
+        if let Some(started_at) = patch.started_at {
+            log.started_at = started_at;
+        }
+        if let Some(ended_at) = patch.ended_at {
+            log.ended_at = ended_at;
+        }
+        if let Some(description) = &patch.description {
+            log.description = normalize_description(description.as_deref());
+        }
+
+        let log = finalize(log, patch.hours, times_changed)?;
+        self.check_day_total(&log, Some(id))?;
+        self.repo.update(&log)?;
+        self.get_all()
+    }
+
+    pub fn delete(&self, id: u32) -> Result<Vec<WorkLog>, AppError> {
+        self.repo.delete(id)?;
+        self.get_all()
+    }
+
+    /// No day may add up to more than 24 hours
+    fn check_day_total(&self, log: &WorkLog, excluding: Option<u32>) -> Result<(), AppError> {
+        let others = self.repo.total_hours_on(&log.date, excluding)?;
+        let total = others + f64::from(log.hours);
+        if total > MAX_HOURS_PER_DAY + HOURS_TOLERANCE {
+            return Err(AppError::Validation(format!(
+                "{} would have {:.2} hours logged; a day has at most {}",
+                log.date, total, MAX_HOURS_PER_DAY
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn totals(&self, today: NaiveDate) -> Result<WorkLogTotals, AppError> {
+        let week_start = stats::week_start(today);
+        let days = self.repo.hours_by_day(
+            &format_date(week_start),
+            &format_date(week_start + Duration::days(6)),
+        )?;
+        let today = format_date(today);
+        let today_hours = days
+            .iter()
+            .find(|(date, _)| *date == today)
+            .map_or(0.0, |(_, hours)| *hours);
+        Ok(WorkLogTotals {
+            today,
+            today_hours: stats::round_hours(today_hours),
+            week_start: format_date(week_start),
+            week_hours: stats::round_hours(days.iter().map(|(_, hours)| hours).sum()),
+        })
+    }
+
+    /// Hours per project between two dates (inclusive); either may be left open
+    pub fn project_hours(
+        &self,
+        from: Option<&str>,
+        to: Option<&str>,
+    ) -> Result<Vec<ProjectHours>, AppError> {
+        let from = from.map(validate_date).transpose()?;
+        let to = to.map(validate_date).transpose()?;
+        if let (Some(from), Some(to)) = (&from, &to) {
+            if from > to {
+                return Err(AppError::Validation(format!(
+                    "Range starts after it ends: {} to {}",
+                    from, to
+                )));
+            }
+        }
+        let totals = self.repo.hours_by_project(from.as_deref(), to.as_deref())?;
+        Ok(stats::project_shares(totals))
+    }
+
+    /// One entry per day between two dates (inclusive), including days without logs
+    pub fn daily_hours(&self, from: &str, to: &str) -> Result<Vec<DailyHours>, AppError> {
+        let (from, to) = (parse_date(from)?, parse_date(to)?);
+        let span = (to - from).num_days() + 1;
+        if span < 1 {
+            return Err(AppError::Validation(format!(
+                "Range starts after it ends: {} to {}",
+                from, to
+            )));
+        }
+        if span > MAX_SERIES_DAYS {
+            return Err(AppError::Validation(format!(
+                "Range covers {} days; at most {} are allowed",
+                span, MAX_SERIES_DAYS
+            )));
+        }
+        let totals = self
+            .repo
+            .hours_by_day(&format_date(from), &format_date(to))?;
+        Ok(stats::daily_series(from, to, &totals))
+    }
+
+    /// The last `days` days up to `today`, each with the rolling average over `window` days
+    pub fn trend(
+        &self,
+        today: NaiveDate,
+        days: u32,
+        window: u32,
+    ) -> Result<WorkLogTrend, AppError> {
+        if days == 0 || i64::from(days) > MAX_SERIES_DAYS {
+            return Err(AppError::Validation(format!(
+                "Days must be 1-{}, got {}",
+                MAX_SERIES_DAYS, days
+            )));
+        }
+        if window == 0 || window > MAX_AVERAGE_WINDOW {
+            return Err(AppError::Validation(format!(
+                "Window must be 1-{}, got {}",
+                MAX_AVERAGE_WINDOW, window
+            )));
+        }
+        let first = today - Duration::days(i64::from(days) - 1);
+        // Start early enough that the first point already has a full window behind it
+        let lead = first - Duration::days(i64::from(window) - 1);
+        let totals = self
+            .repo
+            .hours_by_day(&format_date(lead), &format_date(today))?;
+        let series = stats::daily_series(lead, today, &totals);
+        let hours: Vec<f64> = series.iter().map(|d| d.hours).collect();
+        let averages = stats::rolling_average(&hours, window as usize);
+        let points = series
+            .into_iter()
+            .zip(averages)
+            .skip(window as usize - 1)
+            .map(|(day, average)| TrendPoint {
+                date: day.date,
+                hours: day.hours,
+                average,
+            })
+            .collect();
+
+        let logged = self
+            .repo
+            .logged_dates()?
+            .iter()
+            .map(|d| parse_date(d))
+            .collect::<Result<Vec<_>, _>>()?;
+        let (current_streak, longest_streak) = stats::streaks(&logged, today);
+        Ok(WorkLogTrend {
+            window,
+            points,
+            current_streak,
+            longest_streak,
+        })
+    }
+
+    /// Logs between two dates (inclusive; either may be left open) as a timesheet file.
+    /// `include_summary` adds per-project totals to CSV and JSON.
+    pub fn export(
+        &self,
+        format: ExportFormat,
+        from: Option<&str>,
+        to: Option<&str>,
+        include_summary: bool,
+        now: DateTime<FixedOffset>,
+    ) -> Result<String, AppError> {
+        let from = from.map(validate_date).transpose()?;
+        let to = to.map(validate_date).transpose()?;
+        let logs = self.repo.get_between(from.as_deref(), to.as_deref())?;
+        let summary = if include_summary {
+            Some(self.project_hours(from.as_deref(), to.as_deref())?)
+        } else {
+            None
+        };
+
+        match format {
+            ExportFormat::Csv => Ok(timesheet::to_csv(&logs, summary.as_deref())),
+            ExportFormat::Ical => Ok(timesheet::to_ical(&logs, now)),
+            ExportFormat::Json => serde_json::to_string_pretty(&Timesheet {
+                from,
+                to,
+                logs,
+                projects: summary,
+            })
+            .map_err(|e| AppError::Unknown(e.to_string())),
+        }
+    }
+
+    /// Adds the entries of a CSV file, reading times without a zone in `offset`.
+    /// Rows that repeat a stored entry or an earlier row are counted and skipped, as are
+    /// rows that fail validation; the rest are imported.
+    pub fn import_csv(
+        &self,
+        content: &str,
+        offset: FixedOffset,
+    ) -> Result<WorkLogImport, AppError> {
+        let rows = timesheet::parse_csv(content, offset).map_err(AppError::Validation)?;
+        let mut known = self.repo.get_all()?;
+        let mut result = WorkLogImport::default();
+
+        for (line, row) in rows {
+            let entry = match row {
+                Ok(entry) => entry,
+                Err(msg) => {
+                    result.errors.push(format!("Line {}: {}", line, msg));
+                    continue;
+                }
+            };
+            if self.is_duplicate(&entry, &known) {
+                result.duplicates += 1;
+                continue;
+            }
+            match self.insert(entry) {
+                Ok(log) => {
+                    known.push(log);
+                    result.imported += 1;
+                }
+                Err(AppError::Validation(msg)) => {
+                    result.errors.push(format!("Line {}: {}", line, msg))
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(result)
+    }
+
+    /// Same project (ignoring case), date and hours within a minute; when both have a start
+    /// time, it must be the same instant too. Descriptions are not compared.
+    fn is_duplicate(&self, entry: &NewWorkLog, known: &[WorkLog]) -> bool {
+        // Normalize the row the way `insert` would; rows that fail here are left for it to reject
+        let Ok(start) = entry.started_at.as_deref().map(parse_time).transpose() else {
+            return false;
+        };
+        let end = entry.ended_at.as_deref().and_then(|t| parse_time(t).ok());
+        let hours = match (entry.hours, start, end) {
+            (Some(hours), _, _) => hours,
+            (None, Some(start), Some(end)) => (end - start).num_seconds() as f32 / 3600.0,
+            _ => return false,
+        };
+        let date = match (&entry.date, start) {
+            (Some(date), _) => match validate_date(date) {
+                Ok(date) => date,
+                Err(_) => return false,
+            },
+            (None, Some(start)) => format_date(start.date_naive()),
+            (None, None) => format_date(chrono::Local::now().date_naive()),
+        };
+        let project = entry.project.trim().to_lowercase();
+
+        known.iter().any(|log| {
+            let same_start = match (start, log.started_at.as_deref()) {
+                (Some(a), Some(b)) => parse_time(b).is_ok_and(|b| a == b),
+                _ => true,
+            };
+            log.project.to_lowercase() == project
+                && log.date == date
+                && (log.hours - hours).abs() < 1.0 / 60.0
+                && same_start
+        })
+    }
+
+    pub fn get_all(&self) -> Result<Vec<WorkLog>, AppError> {
         self.repo.get_all()
     }
+
+    pub fn timers(&self) -> Result<Vec<WorkTimer>, AppError> {
+        self.repo.get_timers(now())
+    }
+
+    /// Only one timer runs at a time unless `allow_concurrent` is set
+    pub fn start_timer(
+        &self,
+        project: &str,
+        allow_concurrent: bool,
+    ) -> Result<Vec<WorkTimer>, AppError> {
+        let project = project.trim();
+        if project.is_empty() {
+            return Err(AppError::Validation("Project cannot be empty".to_string()));
+        }
+        self.repo.start_timer(project, allow_concurrent, now())?;
+        self.timers()
+    }
+
+    pub fn pause_timer(&self, id: u32) -> Result<Vec<WorkTimer>, AppError> {
+        self.repo.pause_timer(id, now())?;
+        self.timers()
+    }
+
+    pub fn resume_timer(
+        &self,
+        id: u32,
+        allow_concurrent: bool,
+    ) -> Result<Vec<WorkTimer>, AppError> {
+        self.repo.resume_timer(id, allow_concurrent, now())?;
+        self.timers()
+    }
+
+    /// Returns the work logs, including the one the timer became.
+    /// Fails, leaving the timer as it is, if the log would put its day over 24 hours;
+    /// such a timer can be discarded and its time logged by hand.
+    pub fn stop_timer(&self, id: u32) -> Result<Vec<WorkLog>, AppError> {
+        self.stop_timer_at(id, now())
+    }
+
+    pub fn stop_timer_at(
+        &self,
+        id: u32,
+        now: DateTime<FixedOffset>,
+    ) -> Result<Vec<WorkLog>, AppError> {
+        let timer = self
+            .repo
+            .get_timers(now)?
+            .into_iter()
+            .find(|t| t.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("Timer {}", id)))?;
+        // The same date and hours `stop_timer` will store
+        let log = WorkLog {
+            id: 0,
+            project: timer.project,
+            hours: timer.elapsed_seconds as f32 / 3600.0,
+            date: parse_time(&timer.started_at)?
+                .format(DATE_FORMAT)
+                .to_string(),
+            started_at: Some(timer.started_at),
+            ended_at: None,
+            duration_seconds: Some(timer.elapsed_seconds),
+            description: None,
+        };
+        self.check_day_total(&log, None)?;
+
+        self.repo.stop_timer(id, now)?;
+        self.get_all()
+    }
+
+    pub fn discard_timer(&self, id: u32) -> Result<Vec<WorkTimer>, AppError> {
+        self.repo.discard_timer(id)?;
+        self.timers()
+    }
 }
@@ -0,0 +1,187 @@
+//! Aggregation maths behind the work log charts. The sums themselves come from SQL;
+//! these fill the gaps between logged days and derive averages and streaks.
+
+use super::model::{DailyHours, ProjectHours};
+use chrono::{Datelike, Duration, NaiveDate};
+use std::collections::HashMap;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Rounds to hundredths, so `f32` noise doesn't reach the charts
+pub fn round_hours(hours: f64) -> f64 {
+    (hours * 100.0).round() / 100.0
+}
+
+/// The Monday of `date`'s week
+pub fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(i64::from(date.weekday().num_days_from_monday()))
+}
+
+/// One entry per day from `from` to `to` (inclusive), taking hours from `totals`
+pub fn daily_series(from: NaiveDate, to: NaiveDate, totals: &[(String, f64)]) -> Vec<DailyHours> {
+    let by_date: HashMap<&str, f64> = totals.iter().map(|(d, h)| (d.as_str(), *h)).collect();
+    from.iter_days()
+        .take_while(|d| *d <= to)
+        .map(|d| {
+            let date = d.format(DATE_FORMAT).to_string();
+            let hours = round_hours(by_date.get(date.as_str()).copied().unwrap_or(0.0));
+            DailyHours { date, hours }
+        })
+        .collect()
+}
+
+/// Mean of each value and the `window - 1` before it; the first few average what there is
+pub fn rolling_average(values: &[f64], window: usize) -> Vec<f64> {
+    let window = window.max(1);
+    let mut sum = 0.0;
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            sum += value;
+            if i >= window {
+                sum -= values[i - window];
+            }
+            round_hours(sum / (i + 1).min(window) as f64)
+        })
+        .collect()
+}
+
+/// `(current, longest)` runs of consecutive days in `days`, which must be sorted.
+/// The current run ends today, or yesterday when nothing is logged today yet;
+/// days after `today` are ignored.
+pub fn streaks(days: &[NaiveDate], today: NaiveDate) -> (u32, u32) {
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous: Option<NaiveDate> = None;
+    for &day in days.iter().take_while(|d| **d <= today) {
+        run = match previous {
+            Some(p) if day == p + Duration::days(1) => run + 1,
+            Some(p) if day == p => run,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        previous = Some(day);
+    }
+
+    let current = match previous {
+        Some(last) if last == today || last + Duration::days(1) == today => run,
+        _ => 0,
+    };
+    (current, longest)
+}
+
+/// Turns per-project sums into chart slices, largest first
+pub fn project_shares(totals: Vec<(String, f64)>) -> Vec<ProjectHours> {
+    let total: f64 = totals.iter().map(|(_, h)| h).sum();
+    let mut shares: Vec<ProjectHours> = totals
+        .into_iter()
+        .map(|(project, hours)| ProjectHours {
+            project,
+            share: if total > 0.0 {
+                (hours / total * 1000.0).round() / 1000.0
+            } else {
+                0.0
+            },
+            hours: round_hours(hours),
+        })
+        .collect();
+    shares.sort_by(|a, b| {
+        b.hours
+            .total_cmp(&a.hours)
+            .then_with(|| a.project.cmp(&b.project))
+    });
+    shares
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, DATE_FORMAT).unwrap()
+    }
+
+    fn dates(list: &[&str]) -> Vec<NaiveDate> {
+        list.iter().map(|d| date(d)).collect()
+    }
+
+    #[test]
+    fn test_week_start_is_monday() {
+        // 2025-03-10 is a Monday
+        assert_eq!(week_start(date("2025-03-10")), date("2025-03-10"));
+        assert_eq!(week_start(date("2025-03-16")), date("2025-03-10"));
+        assert_eq!(week_start(date("2025-01-01")), date("2024-12-30"));
+    }
+
+    #[test]
+    fn test_daily_series_fills_gaps() {
+        let totals = vec![
+            ("2025-02-27".to_string(), 1.5),
+            ("2025-03-01".to_string(), 0.1 + 0.2),
+        ];
+        let series = daily_series(date("2025-02-27"), date("2025-03-02"), &totals);
+        let flat: Vec<(&str, f64)> = series.iter().map(|d| (d.date.as_str(), d.hours)).collect();
+        assert_eq!(
+            flat,
+            vec![
+                ("2025-02-27", 1.5),
+                ("2025-02-28", 0.0),
+                ("2025-03-01", 0.3),
+                ("2025-03-02", 0.0)
+            ]
+        );
+        assert!(daily_series(date("2025-03-02"), date("2025-03-01"), &totals).is_empty());
+    }
+
+    #[test]
+    fn test_rolling_average() {
+        let values = [2.0, 4.0, 0.0, 6.0, 1.0];
+        assert_eq!(rolling_average(&values, 3), vec![2.0, 3.0, 2.0, 3.33, 2.33]);
+        assert_eq!(rolling_average(&values, 1), values.to_vec());
+        assert_eq!(rolling_average(&values, 10), vec![2.0, 3.0, 2.0, 3.0, 2.6]);
+        assert!(rolling_average(&[], 7).is_empty());
+    }
+
+    #[test]
+    fn test_streaks() {
+        let days = dates(&[
+            "2025-02-26",
+            "2025-02-27",
+            "2025-02-28",
+            "2025-03-01",
+            "2025-03-05",
+            "2025-03-06",
+        ]);
+        assert_eq!(streaks(&days, date("2025-03-06")), (2, 4));
+        // Today isn't logged yet: the run up to yesterday still counts
+        assert_eq!(streaks(&days, date("2025-03-07")), (2, 4));
+        assert_eq!(streaks(&days, date("2025-03-08")), (0, 4));
+        assert_eq!(streaks(&[], date("2025-03-08")), (0, 0));
+        // Logs dated ahead count once their day comes
+        assert_eq!(streaks(&days, date("2025-02-28")), (3, 3));
+        // Across a leap day
+        let leap = dates(&["2024-02-28", "2024-02-29", "2024-03-01"]);
+        assert_eq!(streaks(&leap, date("2024-03-01")), (3, 3));
+    }
+
+    #[test]
+    fn test_project_shares() {
+        let shares = project_shares(vec![
+            ("B".to_string(), 1.0),
+            ("A".to_string(), 2.0),
+            ("C".to_string(), 1.0),
+        ]);
+        let flat: Vec<(&str, f64, f64)> = shares
+            .iter()
+            .map(|p| (p.project.as_str(), p.hours, p.share))
+            .collect();
+        assert_eq!(
+            flat,
+            vec![("A", 2.0, 0.5), ("B", 1.0, 0.25), ("C", 1.0, 0.25)]
+        );
+
+        let empty = project_shares(vec![("Idle".to_string(), 0.0)]);
+        assert_eq!(empty[0].share, 0.0);
+    }
+}
@@ -0,0 +1,472 @@
+//! Timesheet files: CSV and iCalendar export of work logs, and CSV import from other trackers.
+
+use super::model::{NewWorkLog, ProjectHours, WorkLog};
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+
+const CSV_HEADER: &str = "date,project,hours,started_at,ended_at,description";
+const ICAL_DATETIME: &str = "%Y%m%dT%H%M%SZ";
+/// RFC 5545 content lines are folded at 75 octets
+const ICAL_LINE_LIMIT: usize = 75;
+
+/// Quotes a CSV field when it holds a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn format_hours(hours: f64) -> String {
+    format!("{:.2}", hours)
+}
+
+/// One row per entry under a header row. With `summary`, a blank line and a table of
+/// per-project totals follow; the importer stops at that blank line.
+pub fn to_csv(logs: &[WorkLog], summary: Option<&[ProjectHours]>) -> String {
+    let mut out = String::from(CSV_HEADER);
+    out.push_str("\r\n");
+    for log in logs {
+        let fields = [
+            csv_field(&log.date),
+            csv_field(&log.project),
+            format_hours(f64::from(log.hours)),
+            csv_field(log.started_at.as_deref().unwrap_or_default()),
+            csv_field(log.ended_at.as_deref().unwrap_or_default()),
+            csv_field(log.description.as_deref().unwrap_or_default()),
+        ];
+        out.push_str(&fields.join(","));
+        out.push_str("\r\n");
+    }
+
+    if let Some(projects) = summary {
+        out.push_str("\r\nproject,hours,share\r\n");
+        for project in projects {
+            out.push_str(&format!(
+                "{},{},{}\r\n",
+                csv_field(&project.project),
+                format_hours(project.hours),
+                project.share
+            ));
+        }
+        let total: f64 = projects.iter().map(|p| p.hours).sum();
+        out.push_str(&format!("Total,{},1\r\n", format_hours(total)));
+    }
+    out
+}
+
+fn ical_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Appends a content line, folding it onto continuation lines without splitting a character
+fn push_ical_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > ICAL_LINE_LIMIT {
+            out.push_str("\r\n ");
+            // The leading space counts towards the continuation line
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+fn ical_time(value: &str) -> Option<String> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|t| t.with_timezone(&Utc).format(ICAL_DATETIME).to_string())
+}
+
+/// An iCalendar document with one event per entry that has start and end times
+pub fn to_ical(logs: &[WorkLog], now: DateTime<FixedOffset>) -> String {
+    let stamp = now.with_timezone(&Utc).format(ICAL_DATETIME).to_string();
+    let mut out = String::new();
+    for line in [
+        "BEGIN:VCALENDAR",
+        "VERSION:2.0",
+        "PRODID:-//Z-Project//Work Log//EN",
+        "CALSCALE:GREGORIAN",
+    ] {
+        push_ical_line(&mut out, line);
+    }
+
+    for log in logs {
+        let start = log.started_at.as_deref().and_then(ical_time);
+        let end = log.ended_at.as_deref().and_then(ical_time);
+        let (Some(start), Some(end)) = (start, end) else {
+            continue;
+        };
+        push_ical_line(&mut out, "BEGIN:VEVENT");
+        push_ical_line(&mut out, &format!("UID:work-log-{}@z-project", log.id));
+        push_ical_line(&mut out, &format!("DTSTAMP:{}", stamp));
+        push_ical_line(&mut out, &format!("DTSTART:{}", start));
+        push_ical_line(&mut out, &format!("DTEND:{}", end));
+        push_ical_line(&mut out, &format!("SUMMARY:{}", ical_text(&log.project)));
+        if let Some(description) = &log.description {
+            push_ical_line(&mut out, &format!("DESCRIPTION:{}", ical_text(description)));
+        }
+        push_ical_line(&mut out, "END:VEVENT");
+    }
+    push_ical_line(&mut out, "END:VCALENDAR");
+    out
+}
+
+/// Splits CSV text into records, each with the line it starts on.
+/// Quoted fields may hold separators, doubled quotes and line breaks.
+/// A blank line comes out as an empty record.
+fn csv_records(content: &str) -> Vec<(usize, Vec<String>)> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' if in_quotes => in_quotes = false,
+            '"' if field.is_empty() => in_quotes = true,
+            ',' if !in_quotes => record.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {}
+            '\n' if !in_quotes => {
+                record.push(std::mem::take(&mut field));
+                if record.len() == 1 && record[0].trim().is_empty() {
+                    record.clear();
+                }
+                records.push((record_line, std::mem::take(&mut record)));
+                line += 1;
+                record_line = line;
+            }
+            '\n' => {
+                field.push(c);
+                line += 1;
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push((record_line, record));
+    }
+    records
+}
+
+/// Where each known column sits in the header row.
+/// Names are matched case-insensitively and include those used by common time trackers.
+#[derive(Default)]
+struct Columns {
+    date: Option<usize>,
+    project: Option<usize>,
+    hours: Option<usize>,
+    start: Option<usize>,
+    end: Option<usize>,
+    end_date: Option<usize>,
+    description: Option<usize>,
+}
+
+impl Columns {
+    fn from_header(header: &[String]) -> Result<Self, String> {
+        let mut columns = Columns::default();
+        for (i, name) in header.iter().enumerate() {
+            let slot = match name.trim().to_lowercase().as_str() {
+                "date" | "day" | "start date" => &mut columns.date,
+                "project" | "project name" => &mut columns.project,
+                "hours" | "duration" | "duration (h)" | "duration (decimal)" => &mut columns.hours,
+                "started_at" | "start" | "start time" => &mut columns.start,
+                "ended_at" | "end" | "end time" => &mut columns.end,
+                "end date" => &mut columns.end_date,
+                "description" | "notes" | "note" | "task" => &mut columns.description,
+                _ => continue,
+            };
+            // The first of several matching columns wins
+            slot.get_or_insert(i);
+        }
+        if columns.project.is_none() {
+            return Err("The CSV has no project column".to_string());
+        }
+        if columns.hours.is_none() && (columns.start.is_none() || columns.end.is_none()) {
+            return Err("The CSV needs an hours column, or start and end columns".to_string());
+        }
+        Ok(columns)
+    }
+}
+
+/// Decimal hours (`1.5`) or a clock duration (`1:30`, `01:30:00`)
+fn parse_hours(value: &str) -> Result<f32, String> {
+    if value.contains(':') {
+        let parts: Vec<&str> = value.split(':').collect();
+        let numbers = parts
+            .iter()
+            .map(|p| p.trim().parse::<u32>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| format!("Invalid duration: {}", value))?;
+        let seconds = match numbers[..] {
+            [h, m] if m < 60 => h.checked_mul(3600).and_then(|s| s.checked_add(m * 60)),
+            [h, m, s] if m < 60 && s < 60 => h
+                .checked_mul(3600)
+                .and_then(|hs| hs.checked_add(m * 60 + s)),
+            _ => None,
+        }
+        .ok_or_else(|| format!("Invalid duration: {}", value))?;
+        return Ok(seconds as f32 / 3600.0);
+    }
+    value
+        .parse::<f32>()
+        .map_err(|_| format!("Invalid hours: {}", value))
+}
+
+/// An RFC 3339 time as is, or a local date and time in `offset`. A bare time of day
+/// needs `date` to say which day it is on.
+fn parse_timestamp(
+    value: &str,
+    date: Option<NaiveDate>,
+    offset: FixedOffset,
+) -> Result<DateTime<FixedOffset>, String> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time);
+    }
+    let naive = [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+    ]
+    .iter()
+    .find_map(|f| NaiveDateTime::parse_from_str(value, f).ok())
+    .or_else(|| {
+        let time = ["%H:%M:%S", "%H:%M"]
+            .iter()
+            .find_map(|f| NaiveTime::parse_from_str(value, f).ok())?;
+        Some(date?.and_time(time))
+    })
+    .ok_or_else(|| format!("Invalid time: {}", value))?;
+    offset
+        .from_local_datetime(&naive)
+        .single()
+        .ok_or_else(|| format!("Invalid time: {}", value))
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| format!("Invalid date: {}", value))
+}
+
+fn read_row(row: &[String], columns: &Columns, offset: FixedOffset) -> Result<NewWorkLog, String> {
+    let get = |column: Option<usize>| {
+        column
+            .and_then(|i| row.get(i))
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+    };
+    let date = get(columns.date).map(parse_date).transpose()?;
+    let end_date = get(columns.end_date).map(parse_date).transpose()?;
+    let start = get(columns.start)
+        .map(|v| parse_timestamp(v, date, offset))
+        .transpose()?;
+    let mut end = get(columns.end)
+        .map(|v| parse_timestamp(v, end_date.or(date), offset))
+        .transpose()?;
+    // A bare end time before the start, with no end date, runs past midnight
+    if let (Some(s), Some(e)) = (start, end) {
+        if e <= s && end_date.is_none() && get(columns.end).is_some_and(|v| v.len() <= 8) {
+            end = Some(e + Duration::days(1));
+        }
+    }
+
+    Ok(NewWorkLog {
+        project: get(columns.project).unwrap_or_default().to_string(),
+        hours: get(columns.hours).map(parse_hours).transpose()?,
+        date: date.map(|d| d.format("%Y-%m-%d").to_string()),
+        started_at: start.map(|t| t.to_rfc3339()),
+        ended_at: end.map(|t| t.to_rfc3339()),
+        description: get(columns.description).map(String::from),
+    })
+}
+
+/// A CSV data row's line number, and the entry read from it or why it couldn't be
+pub type CsvRow = (usize, Result<NewWorkLog, String>);
+
+/// Reads work log entries from CSV with a header row. Times without a zone are taken to be
+/// in `offset`. Rows are returned with their line numbers; a row that can't be read carries
+/// its error instead. Reading stops at the first blank line, so an exported summary is skipped.
+pub fn parse_csv(content: &str, offset: FixedOffset) -> Result<Vec<CsvRow>, String> {
+    let mut records = csv_records(content).into_iter();
+    let header = loop {
+        match records.next() {
+            Some((_, record)) if record.is_empty() => continue,
+            Some((_, record)) => break record,
+            None => return Err("The CSV is empty".to_string()),
+        }
+    };
+    let columns = Columns::from_header(&header)?;
+    Ok(records
+        .take_while(|(_, record)| !record.is_empty())
+        .map(|(line, record)| (line, read_row(&record, &columns, offset)))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kst() -> FixedOffset {
+        FixedOffset::east_opt(9 * 3600).unwrap()
+    }
+
+    fn log(id: u32, project: &str, hours: f32, date: &str) -> WorkLog {
+        WorkLog {
+            id,
+            project: project.into(),
+            hours,
+            date: date.into(),
+            started_at: None,
+            ended_at: None,
+            duration_seconds: None,
+            description: None,
+        }
+    }
+
+    fn timed_log() -> WorkLog {
+        WorkLog {
+            started_at: Some("2025-03-12T09:00:00+09:00".into()),
+            ended_at: Some("2025-03-12T10:30:00+09:00".into()),
+            duration_seconds: Some(5400),
+            description: Some("Review, plan; \"ship\"\nthen rest".into()),
+            ..log(7, "Brain", 1.5, "2025-03-12")
+        }
+    }
+
+    #[test]
+    fn test_csv_export_quotes_and_summary() {
+        let logs = vec![timed_log(), log(8, "Mobile", 1.0 / 3.0, "2025-03-13")];
+        let csv = to_csv(&logs, None);
+        assert!(csv.starts_with(&format!("{}\r\n", CSV_HEADER)));
+        assert!(csv.contains(
+            "2025-03-12,Brain,1.50,2025-03-12T09:00:00+09:00,2025-03-12T10:30:00+09:00,\
+             \"Review, plan; \"\"ship\"\"\nthen rest\"\r\n"
+        ));
+        assert!(csv.ends_with("2025-03-13,Mobile,0.33,,,\r\n"));
+
+        let summary = vec![ProjectHours {
+            project: "Brain".into(),
+            hours: 1.5,
+            share: 1.0,
+        }];
+        let csv = to_csv(&[timed_log()], Some(&summary));
+        assert!(csv.ends_with("\r\n\r\nproject,hours,share\r\nBrain,1.50,1\r\nTotal,1.50,1\r\n"));
+    }
+
+    #[test]
+    fn test_csv_round_trip() {
+        let logs = vec![timed_log(), log(8, "Mobile, web", 2.0, "2025-03-13")];
+        let summary = vec![ProjectHours {
+            project: "Brain".into(),
+            hours: 1.5,
+            share: 1.0,
+        }];
+        let rows = parse_csv(&to_csv(&logs, Some(&summary)), kst()).unwrap();
+        assert_eq!(rows.len(), 2, "summary rows are not read as entries");
+        let (line, first) = &rows[0];
+        assert_eq!(*line, 2);
+        let first = first.as_ref().unwrap();
+        assert_eq!(first.project, "Brain");
+        assert_eq!(first.hours, Some(1.5));
+        assert_eq!(
+            first.started_at.as_deref(),
+            Some("2025-03-12T09:00:00+09:00")
+        );
+        assert_eq!(first.description, timed_log().description);
+        // The quoted line break moves the next row's line number on
+        assert_eq!(rows[1].0, 4);
+        assert_eq!(rows[1].1.as_ref().unwrap().project, "Mobile, web");
+    }
+
+    #[test]
+    fn test_csv_import_from_other_trackers() {
+        // Toggl-style: separate date and time columns, clock durations
+        let toggl =
+            "\u{feff}Project,Description,Start date,Start time,End date,End time,Duration\n\
+                     Brain,Standup,2025-03-12,09:00:00,2025-03-12,09:15:00,00:15:00\n\
+                     Brain,Night shift,2025-03-12,23:30,,00:30,1:00\n";
+        let rows = parse_csv(toggl, kst()).unwrap();
+        let standup = rows[0].1.as_ref().unwrap();
+        assert_eq!(standup.hours, Some(0.25));
+        assert_eq!(standup.date.as_deref(), Some("2025-03-12"));
+        assert_eq!(
+            standup.started_at.as_deref(),
+            Some("2025-03-12T09:00:00+09:00")
+        );
+        assert_eq!(
+            standup.ended_at.as_deref(),
+            Some("2025-03-12T09:15:00+09:00")
+        );
+        assert_eq!(standup.description.as_deref(), Some("Standup"));
+        let night = rows[1].1.as_ref().unwrap();
+        assert_eq!(night.ended_at.as_deref(), Some("2025-03-13T00:30:00+09:00"));
+
+        let rows = parse_csv(
+            "date,project,hours\n2025-03-12,Brain,two\n12/03/2025,Brain,2\n,Brain,1:75\n\
+             2025-03-12,Brain,2000000:00\n2025-03-12,Brain,4294967295:59:59\n",
+            kst(),
+        )
+        .unwrap();
+        let errors: Vec<(usize, String)> = rows
+            .into_iter()
+            .map(|(line, row)| (line, row.unwrap_err()))
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                (2, "Invalid hours: two".to_string()),
+                (3, "Invalid date: 12/03/2025".to_string()),
+                (4, "Invalid duration: 1:75".to_string()),
+                (5, "Invalid duration: 2000000:00".to_string()),
+                (6, "Invalid duration: 4294967295:59:59".to_string())
+            ]
+        );
+
+        assert!(parse_csv("", kst()).is_err());
+        assert!(parse_csv("date,hours\n2025-03-12,1\n", kst()).is_err());
+        assert!(parse_csv("project,date\nBrain,2025-03-12\n", kst()).is_err());
+    }
+
+    #[test]
+    fn test_ical_export() {
+        let now = DateTime::parse_from_rfc3339("2025-03-14T12:00:00+09:00").unwrap();
+        let long = WorkLog {
+            description: Some("가".repeat(40)),
+            ..timed_log()
+        };
+        let ical = to_ical(
+            &[timed_log(), log(8, "Mobile", 2.0, "2025-03-13"), long],
+            now,
+        );
+
+        assert!(ical.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ical.ends_with("END:VCALENDAR\r\n"));
+        // Entries without start and end times have no place on a calendar
+        assert_eq!(ical.matches("BEGIN:VEVENT").count(), 2);
+        assert!(ical.contains("UID:work-log-7@z-project\r\n"));
+        assert!(ical.contains("DTSTAMP:20250314T030000Z\r\n"));
+        assert!(ical.contains("DTSTART:20250312T000000Z\r\nDTEND:20250312T013000Z\r\n"));
+        assert!(ical.contains("DESCRIPTION:Review\\, plan\\; \"ship\"\\nthen rest\r\n"));
+        for line in ical.split("\r\n") {
+            assert!(line.len() <= ICAL_LINE_LIMIT, "{}", line);
+        }
+        let unfolded = ical.replace("\r\n ", "");
+        assert!(unfolded.contains(&format!("DESCRIPTION:{}\r\n", "가".repeat(40))));
+    }
+}
@@ -3,32 +3,57 @@ pub mod db;
 mod db_tests;
 pub mod error;
 pub mod features;
+pub mod serde_util;
+#[cfg(test)]
+mod test_utils;
 
 // Re-exports for easier access if needed, or update consumers to use features::*
 use features::recommendation::{
     commands::{
-        fetch_articles, get_recommended_articles, get_user_interests, save_user_interests,
-        submit_feedback,
+        add_feed, archive_article, export_opml_document, fetch_articles, get_archived_articles,
+        get_feed_states, get_feeds, get_keyword_weights, get_read_articles,
+        get_recommendation_batches, get_recommendation_stats, get_recommended_articles,
+        get_saved_articles, get_unread_articles, get_user_interests, import_opml,
+        mark_article_read, mark_article_unread, remove_feed, rename_feed, reset_keyword_weights,
+        save_article_for_later, save_user_interests, search_articles, set_feed_category,
+        set_feed_enabled, submit_feedback,
     },
     repository::SqliteRecommendationRepository,
     system::RecommendationState,
 };
 use features::todo::{
-    commands::{add_todo, delete_todo, get_todos, toggle_todo},
+    commands::{
+        add_subtask, add_todo, delete_todo, get_overdue_todos, get_todos, get_todos_by_category,
+        get_todos_by_priority, get_todos_due_today, move_subtask, move_todo, preview_quick_add,
+        quick_add_todo, reorder_subtasks, toggle_todo, update_todo,
+    },
     repository::SqliteTodoRepository,
     service::TodoState,
 };
 use features::worklog::{
-    commands::{add_work_log, get_work_logs},
+    commands::{
+        add_work_log, delete_work_log, discard_timer, export_work_logs, get_daily_hours,
+        get_project_hours, get_timers, get_work_log_totals, get_work_log_trend, get_work_logs,
+        import_work_logs_csv, pause_timer, resume_timer, start_timer, stop_timer, update_work_log,
+    },
     repository::SqliteWorkLogRepository,
     service::WorkLogState,
 };
 
-use features::sync::{check_server_health, sync_article_to_server};
+use features::sync::{
+    cancel_sync_all, check_server_health, clear_server_credentials,
+    credentials::{CredentialStore, CREDENTIALS_FILE},
+    get_server_credentials, get_sync_outbox, pull_from_server,
+    repository::SqliteSyncRepository,
+    retry_sync_outbox,
+    service::SyncState,
+    set_server_credentials, sync_all, sync_article_to_server, test_server_credentials,
+};
 
 #[cfg(debug_assertions)]
 use features::debug::commands::test_ai_connection;
 
+use std::path::PathBuf;
 use std::sync::Arc;
 use tauri::Manager;
 
@@ -56,22 +81,87 @@ pub fn run() {
             rec_state.load_persona(app.handle());
             app.manage(rec_state);
 
+            let sync_repo = Arc::new(SqliteSyncRepository::new(pool.clone()));
+            let credentials_path = app
+                .path()
+                .app_data_dir()
+                .unwrap_or(PathBuf::from("."))
+                .join(CREDENTIALS_FILE);
+            let credentials = Arc::new(CredentialStore::load(credentials_path));
+            app.manage(SyncState::new(sync_repo, credentials));
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             get_todos,
             add_todo,
+            quick_add_todo,
+            preview_quick_add,
+            add_subtask,
+            reorder_subtasks,
+            move_subtask,
+            move_todo,
+            update_todo,
             toggle_todo,
             delete_todo,
+            get_overdue_todos,
+            get_todos_due_today,
+            get_todos_by_category,
+            get_todos_by_priority,
             get_work_logs,
             add_work_log,
+            update_work_log,
+            delete_work_log,
+            get_timers,
+            start_timer,
+            pause_timer,
+            resume_timer,
+            stop_timer,
+            discard_timer,
+            get_work_log_totals,
+            get_project_hours,
+            get_daily_hours,
+            get_work_log_trend,
+            export_work_logs,
+            import_work_logs_csv,
             fetch_articles,
+            get_feed_states,
+            get_feeds,
+            add_feed,
+            remove_feed,
+            set_feed_enabled,
+            rename_feed,
+            set_feed_category,
+            import_opml,
+            export_opml_document,
+            search_articles,
+            mark_article_read,
+            mark_article_unread,
+            save_article_for_later,
+            archive_article,
+            get_unread_articles,
+            get_read_articles,
+            get_saved_articles,
+            get_archived_articles,
             get_recommended_articles,
+            get_recommendation_batches,
+            get_recommendation_stats,
             submit_feedback,
             save_user_interests,
             get_user_interests,
+            get_keyword_weights,
+            reset_keyword_weights,
             sync_article_to_server,
             check_server_health,
+            get_sync_outbox,
+            retry_sync_outbox,
+            pull_from_server,
+            sync_all,
+            cancel_sync_all,
+            get_server_credentials,
+            set_server_credentials,
+            test_server_credentials,
+            clear_server_credentials,
             #[cfg(debug_assertions)]
             test_ai_connection
         ])
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Deserializer};
+
+/// For `Option<Option<T>>` patch fields: tells a present `null` (`Some(None)`) apart from a
+/// missing field (`None`, via `#[serde(default)]`).
+pub fn nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
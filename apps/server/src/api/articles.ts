@@ -2,6 +2,7 @@ import { Router, Request, Response, NextFunction } from "express";
 import { articleService } from "../services/article.service.js";
 import { CreateArticleInput } from "../types/article.js";
 import logger from "../lib/logger.js";
+import { idempotent } from "../lib/idempotency.js";
 
 const router = Router();
 
@@ -58,38 +59,44 @@ const validateCreateArticleInput = (
 };
 
 // POST /articles - Create a new article
-router.post("/", async (req: Request, res: Response, next: NextFunction) => {
-  try {
-    const { title, url, content, tags } = req.body as CreateArticleInput;
-
-    // Validation
-    const validationResult = validateCreateArticleInput({
-      title,
-      url,
-      content,
-      tags,
-    });
-    if (!validationResult.isValid) {
-      return res.status(400).json({
-        error: validationResult.error,
-        message: validationResult.message,
+// An article whose URL is already stored is returned as is (200) instead of being created again
+router.post(
+  "/",
+  idempotent,
+  async (req: Request, res: Response, next: NextFunction) => {
+    try {
+      const { title, url, content, tags } = req.body as CreateArticleInput;
+
+      // Validation
+      const validationResult = validateCreateArticleInput({
+        title,
+        url,
+        content,
+        tags,
       });
-    }
+      if (!validationResult.isValid) {
+        return res.status(400).json({
+          error: validationResult.error,
+          message: validationResult.message,
+        });
+      }
 
-    const article = await articleService.createArticle({
-      title,
-      url,
-      content,
-      tags,
-    });
-    res.status(201).json(article);
-  } catch (error) {
-    logger.error(error, "Error creating article");
-    next(error);
+      const { article, created } = await articleService.createArticle({
+        title,
+        url,
+        content,
+        tags,
+      });
+      res.status(created ? 201 : 200).json(article);
+    } catch (error) {
+      logger.error(error, "Error creating article");
+      next(error);
+    }
   }
-});
+);
 
 // GET /articles - List articles with pagination
+// ?updatedSince=<ISO timestamp> returns only articles changed after it
 router.get("/", (req: Request, res: Response, next: NextFunction) => {
   try {
     const page = Math.max(1, parseInt(req.query.page as string) || 1);
@@ -98,7 +105,19 @@ router.get("/", (req: Request, res: Response, next: NextFunction) => {
       Math.max(1, parseInt(req.query.limit as string) || 20)
     );
 
-    const result = articleService.listArticles(page, limit);
+    let updatedSince: string | undefined;
+    if (typeof req.query.updatedSince === "string") {
+      const since = new Date(req.query.updatedSince);
+      if (isNaN(since.getTime())) {
+        return res.status(400).json({
+          error: "Invalid updatedSince",
+          message: "updatedSince must be an ISO 8601 timestamp",
+        });
+      }
+      updatedSince = since.toISOString();
+    }
+
+    const result = articleService.listArticles(page, limit, updatedSince);
     res.json(result);
   } catch (error) {
     logger.error(error, "Error listing articles");
@@ -144,6 +163,7 @@ router.delete(
 // POST /articles/:id/feedback - Submit feedback for an article
 router.post(
   "/:id/feedback",
+  idempotent,
   async (req: Request, res: Response, next: NextFunction) => {
     try {
       const { type, comment } = req.body;
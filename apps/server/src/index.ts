@@ -8,6 +8,7 @@ import articlesRouter from "./api/articles.js";
 import { vectorStoreService } from "./lib/vector-store.js";
 import { articleStoreService } from "./lib/article-store.js";
 import logger from "./lib/logger.js";
+import { requireApiToken } from "./lib/auth.js";
 
 // Environment variable validation
 const requiredEnvVars = ["GEMINI_API_KEY"];
@@ -39,6 +40,9 @@ app.get("/", (req, res) => {
   res.send("AI Brain Server is running!");
 });
 
+// Health check above stays public; everything below needs the token when API_TOKEN is set
+app.use(requireApiToken);
+
 app.use("/knowledge", knowledgeRouter);
 app.use("/interactions", interactionsRouter);
 app.use("/feedback", feedbackRouter);
@@ -73,15 +73,27 @@ class ArticleStoreService {
     return this.store.articles.find((a) => a.id === id);
   }
 
+  public findByUrl(url: string): ArticleMetadata | undefined {
+    return this.store.articles.find((a) => a.url === url);
+  }
+
   public findAll(
     page: number = 1,
-    limit: number = 20
+    limit: number = 20,
+    updatedSince?: string
   ): { articles: ArticleMetadata[]; total: number } {
+    // With updatedSince, only changed articles are returned, oldest change first,
+    // so clients can page through them and keep the last updatedAt as a cursor
+    const articles = updatedSince
+      ? this.store.articles
+          .filter((a) => a.updatedAt > updatedSince)
+          .sort((a, b) => a.updatedAt.localeCompare(b.updatedAt))
+      : this.store.articles;
     const start = (page - 1) * limit;
     const end = start + limit;
     return {
-      articles: this.store.articles.slice(start, end),
-      total: this.store.articles.length,
+      articles: articles.slice(start, end),
+      total: articles.length,
     };
   }
 
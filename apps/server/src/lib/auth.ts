@@ -0,0 +1,36 @@
+import { timingSafeEqual } from "crypto";
+import { NextFunction, Request, Response } from "express";
+
+/**
+ * 두 문자열을 상수 시간에 비교한다. 길이가 다르면 바로 false를 반환한다.
+ */
+function safeEqual(a: string, b: string): boolean {
+  const left = Buffer.from(a);
+  const right = Buffer.from(b);
+  return left.length === right.length && timingSafeEqual(left, right);
+}
+
+/**
+ * API_TOKEN이 설정되어 있으면 `Authorization: Bearer <token>` 헤더를 요구한다.
+ * 설정되지 않은 경우(로컬 개발) 모든 요청을 통과시킨다.
+ */
+export function requireApiToken(
+  req: Request,
+  res: Response,
+  next: NextFunction
+) {
+  const token = process.env.API_TOKEN;
+  if (!token) {
+    return next();
+  }
+
+  const header = req.headers.authorization ?? "";
+  if (!safeEqual(header, `Bearer ${token}`)) {
+    return res.status(401).json({
+      error: "Unauthorized",
+      message: "Missing or invalid API token",
+    });
+  }
+
+  next();
+}
@@ -0,0 +1,89 @@
+import { NextFunction, Request, Response } from "express";
+
+/** 성공 응답을 기억하는 기간 */
+const TTL_MS = 24 * 60 * 60 * 1000;
+/** 기억하는 응답 수 상한 (가장 오래된 것부터 버린다) */
+const MAX_ENTRIES = 10_000;
+/** 이보다 오래 끝나지 않은 요청은 멈춘 것으로 보고 다시 처리한다 */
+const IN_FLIGHT_TIMEOUT_MS = 10 * 60 * 1000;
+
+interface StoredResponse {
+  status: number;
+  body: unknown;
+  expiresAt: number;
+}
+
+interface InFlight {
+  settled: Promise<void>;
+  startedAt: number;
+}
+
+const completed = new Map<string, StoredResponse>();
+const inFlight = new Map<string, InFlight>();
+
+function remember(scope: string, status: number, body: unknown) {
+  completed.delete(scope);
+  completed.set(scope, { status, body, expiresAt: Date.now() + TTL_MS });
+  for (const oldest of completed.keys()) {
+    if (completed.size <= MAX_ENTRIES) break;
+    completed.delete(oldest);
+  }
+}
+
+/**
+ * `Idempotency-Key` 헤더가 있는 요청을 한 번만 처리한다.
+ * 같은 키(같은 메서드와 경로)로 다시 오면 처리하지 않고 처음의 성공 응답을 그대로 돌려준다.
+ * 클라이언트가 타임아웃으로 응답을 받지 못하고 재시도해도 아티클 생성이나 피드백이 두 번 반영되지 않는다.
+ * 처리 중인 요청과 같은 키는 처음 요청이 끝날 때까지 기다린다. 실패한 요청은 기억하지 않으므로 재시도하면 다시 처리된다.
+ * 기록은 메모리에만 있어 서버를 재시작하면 사라진다.
+ */
+export async function idempotent(
+  req: Request,
+  res: Response,
+  next: NextFunction
+) {
+  const key = req.header("Idempotency-Key");
+  if (!key) {
+    return next();
+  }
+  const scope = `${req.method} ${req.baseUrl}${req.path} ${key}`;
+
+  const pending = inFlight.get(scope);
+  if (pending && Date.now() - pending.startedAt < IN_FLIGHT_TIMEOUT_MS) {
+    await pending.settled;
+    return idempotent(req, res, next);
+  }
+
+  const stored = completed.get(scope);
+  if (stored && stored.expiresAt > Date.now()) {
+    res.setHeader("Idempotent-Replayed", "true");
+    return res.status(stored.status).json(stored.body);
+  }
+  completed.delete(scope);
+
+  let settle!: () => void;
+  const entry: InFlight = {
+    settled: new Promise<void>((resolve) => (settle = resolve)),
+    startedAt: Date.now(),
+  };
+  inFlight.set(scope, entry);
+
+  const json = res.json.bind(res);
+  res.json = (body: unknown) => {
+    if (res.statusCode >= 200 && res.statusCode < 300) {
+      remember(scope, res.statusCode, body);
+    }
+    return json(body);
+  };
+  // end()는 클라이언트가 이미 연결을 끊었어도 핸들러가 끝나면 호출되므로 여기서 처리 완료로 본다
+  const end = res.end.bind(res) as (...args: unknown[]) => Response;
+  res.end = ((...args: unknown[]) => {
+    if (inFlight.get(scope) === entry) {
+      inFlight.delete(scope);
+    }
+    settle();
+    return end(...args);
+  }) as Response["end"];
+
+  next();
+}
@@ -21,7 +21,19 @@ export class ArticleService {
     });
   }
 
-  async createArticle(input: CreateArticleInput): Promise<ArticleMetadata> {
+  /**
+   * Creates an article, unless one with the same URL is already stored;
+   * then that one is returned with `created: false`, so a retried upload doesn't duplicate it.
+   */
+  async createArticle(
+    input: CreateArticleInput
+  ): Promise<{ article: ArticleMetadata; created: boolean }> {
+    const existing = articleStoreService.findByUrl(input.url);
+    if (existing) {
+      logger.info(`Article already exists for ${input.url}: ${existing.id}`);
+      return { article: existing, created: false };
+    }
+
     const articleId = crypto.randomUUID();
     const now = new Date().toISOString();
 
@@ -54,15 +66,22 @@ export class ArticleService {
     };
 
     // Save to article store
-    return await articleStoreService.create(article);
+    return {
+      article: await articleStoreService.create(article),
+      created: true,
+    };
   }
 
   getArticleById(id: string): ArticleMetadata | undefined {
     return articleStoreService.findById(id);
   }
 
-  listArticles(page: number = 1, limit: number = 20) {
-    const { articles, total } = articleStoreService.findAll(page, limit);
+  listArticles(page: number = 1, limit: number = 20, updatedSince?: string) {
+    const { articles, total } = articleStoreService.findAll(
+      page,
+      limit,
+      updatedSince
+    );
     return {
       articles,
       pagination: {
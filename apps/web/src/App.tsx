@@ -11,7 +11,7 @@ function App() {
   );
 
   // Lifted state to persist data across tab switches
-  const { articles, refreshArticles, syncArticle } = useArticles();
+  const { recommendations, refreshArticles, syncArticle } = useArticles();
 
   return (
     <>
@@ -21,7 +21,7 @@ function App() {
           <DashboardView />
         ) : (
           <ArticleView
-            articles={articles}
+            recommendations={recommendations}
             onRefresh={refreshArticles}
             onSyncArticle={syncArticle}
           />
@@ -1,10 +1,18 @@
 import { memo } from "react";
-import type { Article, ArticleCategory } from "../types";
+import type {
+  Article,
+  ArticleCategory,
+  RecommendationSource,
+  ScoreBreakdown,
+} from "../types";
 import FeedbackForm from "./FeedbackForm";
 import { cn } from "../lib/utils";
 
 interface ArticleCardProps {
   article: Article;
+  score?: ScoreBreakdown;
+  source?: RecommendationSource;
+  aiReason?: string | null;
   isFeedbacking: boolean;
   onSetFeedbackingId: (id: string | null) => void;
   onSubmitFeedback: (id: string, helpful: boolean, reason: string) => void;
@@ -28,8 +36,20 @@ function getCategoryColorClass(cat: ArticleCategory): string {
   }
 }
 
+// Why the article was recommended: the model's reason, or the keywords it matched
+function recommendationReason(
+  score: ScoreBreakdown,
+  aiReason?: string | null
+): string {
+  if (aiReason) return aiReason;
+  return [...score.high_keywords, ...score.medium_keywords].join(", ");
+}
+
 const ArticleCard = memo(function ArticleCard({
   article,
+  score,
+  source,
+  aiReason,
   isFeedbacking,
   onSetFeedbackingId,
   onSubmitFeedback,
@@ -82,6 +102,16 @@ const ArticleCard = memo(function ArticleCard({
         {article.summary}
       </p>
 
+      {score && (
+        <div className="mb-4 font-sans text-sm text-foreground/70">
+          <span className="font-bold">Score {score.total}</span>
+          {source === "ai" && <span> · AI pick</span>}
+          {recommendationReason(score, aiReason) && (
+            <span> — {recommendationReason(score, aiReason)}</span>
+          )}
+        </div>
+      )}
+
       {article.author && (
         <small className="block text-foreground/60 font-sans mb-4 italic">
           — {article.author}
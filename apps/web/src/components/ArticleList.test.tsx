@@ -1,7 +1,7 @@
 import { render, screen, fireEvent } from "@testing-library/react";
 import { describe, it, expect, vi } from "vitest";
 import ArticleList from "./ArticleList";
-import type { Article } from "../types";
+import type { Article, Recommendation, ScoreBreakdown } from "../types";
 
 // Mock Tauri invoke
 vi.mock("@tauri-apps/api/core", () => ({
@@ -33,11 +33,39 @@ const mockArticles: Article[] = [
   },
 ];
 
+const score = (
+  total: number,
+  high_keywords: string[] = []
+): ScoreBreakdown => ({
+  total,
+  high_keywords,
+  medium_keywords: [],
+  negative_keywords: [],
+  keyword_score: 0,
+  interest_bonus: 0,
+  category_bonus: 0,
+  feedback_penalty: 0,
+});
+
+const mockRecommendations: Recommendation[] = [
+  {
+    article: mockArticles[0],
+    score: score(25, ["rust"]),
+    source: "rule_based",
+  },
+  {
+    article: mockArticles[1],
+    score: score(7),
+    source: "ai",
+    ai_reason: "Covers the new React compiler",
+  },
+];
+
 describe("ArticleList", () => {
   it("renders articles", () => {
     render(
       <ArticleList
-        articles={mockArticles}
+        recommendations={mockRecommendations}
         onRefresh={() => {}}
         onFeedbackUpdate={() => {}}
         onSyncArticle={() => {}}
@@ -50,7 +78,7 @@ describe("ArticleList", () => {
   it("filters articles", () => {
     render(
       <ArticleList
-        articles={mockArticles}
+        recommendations={mockRecommendations}
         onRefresh={() => {}}
         onFeedbackUpdate={() => {}}
         onSyncArticle={() => {}}
@@ -68,7 +96,7 @@ describe("ArticleList", () => {
   it("uses lazy loading for images", () => {
     render(
       <ArticleList
-        articles={mockArticles}
+        recommendations={mockRecommendations}
         onRefresh={() => {}}
         onFeedbackUpdate={() => {}}
         onSyncArticle={() => {}}
@@ -83,4 +111,20 @@ describe("ArticleList", () => {
     expect(img1).toBeDefined();
     expect(img1?.getAttribute("loading")).toBe("lazy");
   });
+
+  it("shows the score and why each article was picked", () => {
+    render(
+      <ArticleList
+        recommendations={mockRecommendations}
+        onRefresh={() => {}}
+        onFeedbackUpdate={() => {}}
+        onSyncArticle={() => {}}
+      />
+    );
+
+    expect(screen.getByText("Score 25")).toBeDefined();
+    expect(screen.getByText("— rust")).toBeDefined();
+    expect(screen.getByText("Score 7")).toBeDefined();
+    expect(screen.getByText("— Covers the new React compiler")).toBeDefined();
+  });
 });
@@ -1,21 +1,21 @@
 import { useState, useMemo, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { ArticleCategory } from "../types";
-import type { Article } from "../types";
+import type { Article, Recommendation } from "../types";
 import ArticleCard from "./ArticleCard";
 import { cn } from "../lib/utils";
 
 const CATEGORIES: ArticleCategory[] = Object.values(ArticleCategory);
 
 interface Props {
-  articles: Article[];
+  recommendations: Recommendation[];
   onRefresh: () => void;
   onFeedbackUpdate: () => void;
   onSyncArticle: (article: Article) => void;
 }
 
 export default function ArticleList({
-  articles,
+  recommendations,
   onRefresh,
   onFeedbackUpdate,
   onSyncArticle,
@@ -26,9 +26,9 @@ export default function ArticleList({
 
   const filtered = useMemo(() => {
     return filter === "All"
-      ? articles
-      : articles.filter((a) => a.tags.includes(filter));
-  }, [articles, filter]);
+      ? recommendations
+      : recommendations.filter((r) => r.article.tags.includes(filter));
+  }, [recommendations, filter]);
 
   const handleRefresh = useCallback(async () => {
     setLoading(true);
@@ -91,10 +91,13 @@ export default function ArticleList({
       </div>
 
       <div className="space-y-8">
-        {filtered.map((article) => (
+        {filtered.map(({ article, score, source, ai_reason }) => (
           <ArticleCard
             key={article.id}
             article={article}
+            score={score}
+            source={source}
+            aiReason={ai_reason}
             isFeedbacking={feedbackingId === article.id}
             onSetFeedbackingId={setFeedbackingId}
             onSubmitFeedback={handleSubmitFeedback}
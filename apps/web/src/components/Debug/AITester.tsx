@@ -1,5 +1,6 @@
 import React, { useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { errorMessage } from "../../lib/utils";
 
 const AITester: React.FC = () => {
   const [isLoading, setIsLoading] = useState(false);
@@ -14,7 +15,7 @@ const AITester: React.FC = () => {
       setResult(response);
     } catch (error) {
       console.error("AI Test failed:", error);
-      setResult("❌ Error invoking command: " + String(error));
+      setResult("❌ Error invoking command: " + errorMessage(error));
     } finally {
       setIsLoading(false);
     }
@@ -1,15 +1,17 @@
 import { useState, useCallback, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import type { Article } from "../types";
+import type { Article, Recommendation } from "../types";
 
 export function useArticles() {
-  const [articles, setArticles] = useState<Article[]>([]);
+  const [recommendations, setRecommendations] = useState<Recommendation[]>(
+    []
+  );
 
   const refreshArticles = useCallback(async () => {
     console.log("Refreshing articles...");
     try {
-      const data = await invoke<Article[]>("get_recommended_articles");
-      setArticles(data);
+      const data = await invoke<Recommendation[]>("get_recommended_articles");
+      setRecommendations(data);
     } catch (e) {
       console.error("Failed to fetch articles", e);
     }
@@ -27,5 +29,5 @@ export function useArticles() {
     void refreshArticles();
   }, [refreshArticles]);
 
-  return { articles, refreshArticles, syncArticle };
+  return { recommendations, refreshArticles, syncArticle };
 }
@@ -24,7 +24,7 @@ export function useTodos() {
   const addTodo = useCallback(
     async (text: string) => {
       if (!text) return;
-      await invoke("add_todo", { text });
+      await invoke("add_todo", { todo: { text } });
       await refreshTodos();
     },
     [refreshTodos]
@@ -22,7 +22,7 @@ export function useWorkLogs() {
 
   const addWorkLog = async (project: string, hours: number) => {
     if (!project || !hours) return;
-    await invoke("add_work_log", { project, hours });
+    await invoke("add_work_log", { log: { project, hours } });
     await refreshWorkLogs();
   };
 
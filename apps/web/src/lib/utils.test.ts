@@ -0,0 +1,19 @@
+import { describe, it, expect } from "vitest";
+import { errorMessage } from "./utils";
+
+describe("errorMessage", () => {
+  it("reads the message of a command error", () => {
+    expect(
+      errorMessage({
+        code: "network",
+        message: "connection refused",
+        retryable: true,
+      })
+    ).toBe("connection refused");
+  });
+
+  it("falls back to the string form", () => {
+    expect(errorMessage("plain failure")).toBe("plain failure");
+    expect(errorMessage(undefined)).toBe("undefined");
+  });
+});
@@ -30,3 +30,32 @@ export interface Article {
   image_url?: string;
   author?: string;
 }
+
+export interface ScoreBreakdown {
+  total: number;
+  high_keywords: string[];
+  medium_keywords: string[];
+  negative_keywords: string[];
+  keyword_score: number;
+  interest_bonus: number;
+  category_bonus: number;
+  feedback_penalty: number;
+}
+
+export type RecommendationSource = "rule_based" | "ai" | "fallback";
+
+// What `get_recommended_articles` returns for each article
+export interface Recommendation {
+  article: Article;
+  score: ScoreBreakdown;
+  source: RecommendationSource;
+  ai_reason?: string | null;
+}
+
+// What a failed Tauri command rejects with
+export interface AppError {
+  code: string;
+  message: string;
+  details?: unknown;
+  retryable: boolean;
+}
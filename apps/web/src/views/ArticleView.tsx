@@ -1,17 +1,17 @@
 import { useState } from "react";
 import ArticleList from "../components/ArticleList";
 import { CategorySelector } from "../components/CategorySelector";
-import type { Article } from "../types";
+import type { Article, Recommendation } from "../types";
 import { cn } from "../lib/utils";
 
 interface ArticleViewProps {
-  articles: Article[];
+  recommendations: Recommendation[];
   onRefresh: () => void;
   onSyncArticle: (article: Article) => void;
 }
 
 export default function ArticleView({
-  articles,
+  recommendations,
   onRefresh,
   onSyncArticle,
 }: ArticleViewProps) {
@@ -63,7 +63,7 @@ export default function ArticleView({
       )}
 
       <ArticleList
-        articles={articles}
+        recommendations={recommendations}
         onRefresh={onRefresh}
         onFeedbackUpdate={onRefresh}
         onSyncArticle={onSyncArticle}